@@ -1,3 +1,4 @@
+use alloc::format;
 use alloc::string::String;
 use core::fmt;
 
@@ -6,19 +7,82 @@ use core::fmt;
 pub enum AssetError {
     /// Asset read error
     ReadError(String),
+
+    /// Attempted to read `count` bytes at `offset` from an asset of `size`
+    /// bytes. Holds the offset, the count and the size, in that order.
+    OutOfBounds(usize, usize, usize),
+
+    /// The asset is not a valid archive
+    InvalidArchive(String),
+
+    /// The archived file given by name is compressed with the given
+    /// compression method, but only uncompressed files can be read.
+    UnsupportedCompression(String, u16),
+
+    /// The data of the archived file given by name starts at the given
+    /// offset, which is not a multiple of the given alignment.
+    MisalignedData(String, usize, usize),
+
+    /// The content of the asset does not match its expected digest. Holds
+    /// the expected and actual digests, in that order.
+    DigestMismatch(String, String),
 }
 
 impl fmt::Display for AssetError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             AssetError::ReadError(s) => write!(f, "failed to read asset: {}", s),
+            AssetError::OutOfBounds(offset, count, size) => write!(
+                f,
+                "cannot read {} bytes at offset {} from asset of size {}",
+                count, offset, size
+            ),
+            AssetError::InvalidArchive(reason) => write!(f, "invalid zip archive: {}", reason),
+            AssetError::UnsupportedCompression(path, method) => write!(
+                f,
+                "cannot read `{}` compressed with method {}, only uncompressed files are supported",
+                path, method
+            ),
+            AssetError::MisalignedData(path, offset, alignment) => write!(
+                f,
+                "data of `{}` at offset {} is not aligned to {} bytes",
+                path, offset, alignment
+            ),
+            AssetError::DigestMismatch(expected, actual) => write!(
+                f,
+                "content digest {} does not match expected digest {}",
+                actual, expected
+            ),
         }
     }
 }
 
+/// Checks a read of `count` bytes at `offset` from an asset of `size` bytes
+/// into a buffer of `buffer_len` bytes, as done by [`Asset::read`].
+pub(crate) fn check_read_bounds(
+    buffer_len: usize,
+    count: usize,
+    offset: usize,
+    size: usize,
+) -> Result<(), AssetError> {
+    if offset.checked_add(count).is_none_or(|end| end > size) {
+        return Err(AssetError::OutOfBounds(offset, count, size));
+    }
+    if count > buffer_len {
+        return Err(AssetError::ReadError(format!(
+            "buffer of size {} does not hold {} bytes",
+            buffer_len, count
+        )));
+    }
+    Ok(())
+}
+
 /// Trait for accessing the contents of an asset
 /// See [`crate::Resolver::open_asset()`] for how to retrieve instances of this object.
-pub trait Asset {
+///
+/// Assets are handed out as shared `Arc<dyn Asset>` and may be read from
+/// several threads at once.
+pub trait Asset: Send + Sync {
     /// Returns size of the asset.
     fn get_size(&self) -> usize;
 
@@ -1,7 +1,31 @@
+use alloc::boxed::Box;
 use alloc::string::String;
 
+use crate::ResolverInfo;
+
 /// Contains information about a resolved asset.
-#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+///
+/// Resolvers return this from [`crate::Resolver::get_asset_info`], filling in
+/// whichever fields apply using the `with_*` methods.
+///
+/// With the `serde` feature, asset infos can be serialized and deserialized.
+/// The resolver info is type-erased and is skipped, so a deserialized asset
+/// info never has one.
+///
+/// # Examples
+/// ```
+/// use ar::AssetInfo;
+///
+/// let info = AssetInfo::new()
+///     .with_version("3")
+///     .with_asset_name("chair")
+///     .with_repo_path("/props/chair.usd");
+/// assert_eq!(info.get_version(), Some("3"));
+/// assert_eq!(info.get_asset_name(), Some("chair"));
+/// assert_eq!(info.get_repo_path(), "/props/chair.usd");
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AssetInfo {
     /// Version of the resolved asset, if any.
     version: Option<String>,
@@ -12,7 +36,135 @@ pub struct AssetInfo {
 
     /// The repository path corresponding to the resolved asset.
     repo_path: String,
-    // Additional information specific to the active plugin
-    // asset resolver implementation.
-    // resolver_info: Box<&dyn Any>,
+
+    /// Additional information specific to the active plugin
+    /// asset resolver implementation.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    resolver_info: Option<Box<dyn ResolverInfo>>,
+}
+
+impl AssetInfo {
+    /// Creates an empty asset info.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the version of the resolved asset.
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Sets the name of the asset represented by the resolved asset.
+    pub fn with_asset_name(mut self, asset_name: &str) -> Self {
+        self.asset_name = Some(asset_name.into());
+        self
+    }
+
+    /// Sets the repository path corresponding to the resolved asset.
+    pub fn with_repo_path(mut self, repo_path: &str) -> Self {
+        self.repo_path = repo_path.into();
+        self
+    }
+
+    /// Sets the resolver-specific information about the resolved asset.
+    pub fn with_resolver_info(mut self, resolver_info: impl ResolverInfo + 'static) -> Self {
+        self.resolver_info = Some(Box::new(resolver_info));
+        self
+    }
+
+    /// Returns the version of the resolved asset, if any.
+    pub fn get_version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Returns the name of the asset represented by the resolved asset, if
+    /// any.
+    pub fn get_asset_name(&self) -> Option<&str> {
+        self.asset_name.as_deref()
+    }
+
+    /// Returns the repository path corresponding to the resolved asset, or an
+    /// empty string if there is none.
+    pub fn get_repo_path(&self) -> &str {
+        &self.repo_path
+    }
+
+    /// Returns the resolver-specific information about the resolved asset,
+    /// if any.
+    pub fn get_resolver_info(&self) -> Option<&dyn ResolverInfo> {
+        self.resolver_info.as_deref()
+    }
+
+    /// Returns the resolver-specific information about the resolved asset if
+    /// it is of type `Info`.
+    pub fn get_resolver_info_as<Info: ResolverInfo + 'static>(&self) -> Option<&Info> {
+        self.resolver_info
+            .as_ref()
+            .and_then(|info| info.as_any().downcast_ref::<Info>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cmp::Ordering;
+
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct RecordId(u64);
+
+    impl ResolverInfo for RecordId {}
+
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct Checksum(String);
+
+    impl ResolverInfo for Checksum {}
+
+    #[test]
+    fn test_resolver_info() {
+        let info = AssetInfo::new().with_resolver_info(RecordId(42));
+        assert_eq!(info.get_resolver_info_as::<RecordId>(), Some(&RecordId(42)));
+        assert_eq!(info.get_resolver_info_as::<Checksum>(), None);
+        assert!(info.get_resolver_info().is_some());
+        assert!(AssetInfo::new().get_resolver_info().is_none());
+    }
+
+    #[test]
+    fn test_compare() {
+        let info = AssetInfo::new()
+            .with_repo_path("/a.usd")
+            .with_resolver_info(RecordId(1));
+        assert_eq!(info.clone(), info);
+        assert_ne!(info, AssetInfo::new().with_repo_path("/a.usd"));
+
+        let newer = info.clone().with_resolver_info(RecordId(2));
+        assert_ne!(info, newer);
+        assert_eq!(info.partial_cmp(&newer), Some(Ordering::Less));
+
+        // Infos of different types are never equal but still ordered.
+        let other = info.clone().with_resolver_info(Checksum("1".into()));
+        assert_ne!(info, other);
+        assert_ne!(info.cmp(&other), Ordering::Equal);
+        assert_eq!(info.cmp(&other), other.cmp(&info).reverse());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let info = AssetInfo::new()
+            .with_version("3")
+            .with_repo_path("/a.usd")
+            .with_resolver_info(RecordId(1));
+        let json = serde_json::to_string(&info).unwrap();
+        assert_eq!(
+            json,
+            r#"{"version":"3","asset_name":null,"repo_path":"/a.usd"}"#
+        );
+
+        let deserialized: AssetInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            deserialized,
+            AssetInfo::new().with_version("3").with_repo_path("/a.usd")
+        );
+    }
 }
@@ -0,0 +1,110 @@
+//! Thin layer over the host filesystem used by the filesystem-backed
+//! resolvers and writable assets. Without the `std` feature there is no
+//! filesystem, so paths never exist and every operation fails.
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "std")] {
+        extern crate std;
+        use alloc::format;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        use std::fs::{File, OpenOptions};
+        use std::io;
+        use std::path::{Path, PathBuf};
+
+        /// Counter used to generate unique temporary file names within this
+        /// process.
+        static TEMPORARY_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        /// Creates a new, uniquely named temporary file in `dir`, whose name
+        /// starts with `prefix`. Returns the file, opened for writing, and
+        /// its path.
+        pub(crate) fn create_temporary_file(dir: &Path, prefix: &str) -> io::Result<(File, PathBuf)> {
+            loop {
+                let temporary_path = dir.join(format!(
+                    "{}{}.{}.tmp",
+                    prefix,
+                    std::process::id(),
+                    TEMPORARY_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+                ));
+                match OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&temporary_path)
+                {
+                    Ok(file) => return Ok((file, temporary_path)),
+                    Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "resolver_v1", feature = "resolver_v2"))]
+use alloc::string::String;
+
+#[cfg(any(feature = "resolver_v1", feature = "resolver_v2"))]
+use crate::path_utils::norm_path;
+
+cfg_if::cfg_if! {
+    if #[cfg(all(feature = "std", any(feature = "resolver_v1", feature = "resolver_v2")))] {
+        use alloc::string::ToString;
+        use std::fs;
+        use std::time::UNIX_EPOCH;
+
+        use crate::path_utils::{cat_paths, is_relative_path};
+
+        /// Returns true if a file or directory exists at `path`.
+        pub(crate) fn path_exists(path: &str) -> bool {
+            fs::metadata(path).is_ok()
+        }
+
+        /// Returns the normalized absolute form of `path`, anchoring relative
+        /// paths to the current working directory.
+        pub(crate) fn abs_path(path: &str) -> String {
+            if path.is_empty() || !is_relative_path(path) {
+                return norm_path(path);
+            }
+
+            match std::env::current_dir() {
+                Ok(cwd) => norm_path(&cat_paths(&cwd.to_string_lossy(), path)),
+                Err(_) => norm_path(path),
+            }
+        }
+
+        /// Returns the last modification time of the file at `path` in
+        /// nanoseconds since the Unix epoch.
+        pub(crate) fn get_modification_time(path: &str) -> Result<i64, String> {
+            let modified = fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .map_err(|err| err.to_string())?;
+            let duration = modified
+                .duration_since(UNIX_EPOCH)
+                .map_err(|err| err.to_string())?;
+            Ok(duration.as_nanos() as i64)
+        }
+
+        /// Creates `path` and any missing parent directories.
+        pub(crate) fn create_directories(path: &str) -> Result<(), String> {
+            fs::create_dir_all(path).map_err(|err| err.to_string())
+        }
+    } else if #[cfg(any(feature = "resolver_v1", feature = "resolver_v2"))] {
+        use alloc::borrow::ToOwned;
+
+        pub(crate) fn path_exists(_path: &str) -> bool {
+            false
+        }
+
+        pub(crate) fn abs_path(path: &str) -> String {
+            norm_path(path)
+        }
+
+        pub(crate) fn get_modification_time(_path: &str) -> Result<i64, String> {
+            Err("no filesystem available".to_owned())
+        }
+
+        pub(crate) fn create_directories(_path: &str) -> Result<(), String> {
+            Err("no filesystem available".to_owned())
+        }
+    }
+}
@@ -0,0 +1,172 @@
+extern crate std;
+
+use alloc::string::ToString;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io;
+use std::sync::OnceLock;
+
+use crate::asset::check_read_bounds;
+use crate::{Asset, AssetError};
+
+#[cfg(unix)]
+fn read_at(file: &File, buffer: &mut [u8], offset: u64) -> io::Result<usize> {
+    use std::os::unix::fs::FileExt;
+    file.read_at(buffer, offset)
+}
+
+#[cfg(windows)]
+fn read_at(file: &File, buffer: &mut [u8], offset: u64) -> io::Result<usize> {
+    use std::os::windows::fs::FileExt;
+    file.seek_read(buffer, offset)
+}
+
+/// [`Asset`] implementation for an asset stored on the local filesystem.
+///
+/// Reads are positional, so a single asset may be read from multiple threads
+/// concurrently without any locking. [`Asset::get_buffer`] memory-maps the
+/// file the first time it is called, giving zero-copy access to its contents
+/// for the lifetime of the asset.
+///
+/// Note that the file must not be truncated or overwritten while it is
+/// mapped.
+#[derive(Debug)]
+pub struct FilesystemAsset {
+    file: File,
+    size: usize,
+    mapping: OnceLock<Mmap>,
+}
+
+impl FilesystemAsset {
+    /// Opens the file at `path` for reading.
+    pub fn open(path: &str) -> Result<Self, AssetError> {
+        let file = File::open(path).map_err(|err| AssetError::ReadError(err.to_string()))?;
+        Self::from_file(file)
+    }
+
+    /// Creates an asset reading from the already opened `file`.
+    pub fn from_file(file: File) -> Result<Self, AssetError> {
+        let size = file
+            .metadata()
+            .map_err(|err| AssetError::ReadError(err.to_string()))?
+            .len() as usize;
+        Ok(Self {
+            file,
+            size,
+            mapping: OnceLock::new(),
+        })
+    }
+}
+
+impl Asset for FilesystemAsset {
+    fn get_size(&self) -> usize {
+        self.size
+    }
+
+    fn get_buffer(&self) -> Result<&[u8], AssetError> {
+        if self.size == 0 {
+            return Ok(&[]);
+        }
+
+        if let Some(mapping) = self.mapping.get() {
+            return Ok(&mapping[..self.size]);
+        }
+
+        // Safety: the mapping is read-only and lives as long as this asset.
+        // As with any file mapping, modifying the file on disk while it is
+        // mapped is not supported.
+        let mapping = unsafe { Mmap::map(&self.file) }
+            .map_err(|err| AssetError::ReadError(err.to_string()))?;
+        if mapping.len() < self.size {
+            return Err(AssetError::ReadError(
+                "file was truncated after it was opened".to_string(),
+            ));
+        }
+
+        // Another thread may have mapped the file in the meantime, in which
+        // case our mapping is simply dropped.
+        let mapping = self.mapping.get_or_init(|| mapping);
+        Ok(&mapping[..self.size])
+    }
+
+    fn read(&self, buffer: &mut [u8], count: usize, offset: usize) -> Result<usize, AssetError> {
+        check_read_bounds(buffer.len(), count, offset, self.size)?;
+
+        if let Some(mapping) = self.mapping.get() {
+            buffer[..count].copy_from_slice(&mapping[offset..offset + count]);
+            return Ok(count);
+        }
+
+        let mut bytes_read = 0;
+        while bytes_read < count {
+            match read_at(
+                &self.file,
+                &mut buffer[bytes_read..count],
+                (offset + bytes_read) as u64,
+            ) {
+                Ok(0) => break,
+                Ok(n) => bytes_read += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(AssetError::ReadError(err.to_string())),
+            }
+        }
+
+        Ok(bytes_read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_asset(contents: &[u8]) -> (tempfile::TempDir, FilesystemAsset) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("asset.usd");
+        std::fs::write(&path, contents).unwrap();
+        let asset = FilesystemAsset::open(path.to_str().unwrap()).unwrap();
+        (dir, asset)
+    }
+
+    #[test]
+    fn test_read() {
+        let (_dir, asset) = create_asset(b"0123456789");
+        assert_eq!(asset.get_size(), 10);
+
+        let mut buffer = [0u8; 4];
+        assert_eq!(asset.read(&mut buffer, 4, 3).unwrap(), 4);
+        assert_eq!(&buffer, b"3456");
+        assert_eq!(asset.read(&mut buffer, 2, 8).unwrap(), 2);
+        assert_eq!(&buffer[..2], b"89");
+        assert_eq!(asset.read(&mut buffer, 0, 10).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_read_out_of_bounds() {
+        let (_dir, asset) = create_asset(b"0123456789");
+
+        let mut buffer = [0u8; 4];
+        assert!(matches!(
+            asset.read(&mut buffer, 4, 8),
+            Err(AssetError::OutOfBounds(8, 4, 10))
+        ));
+        assert!(matches!(
+            asset.read(&mut buffer, 1, usize::MAX),
+            Err(AssetError::OutOfBounds(..))
+        ));
+        assert!(asset.read(&mut buffer, 5, 0).is_err());
+    }
+
+    #[test]
+    fn test_get_buffer() {
+        let (_dir, asset) = create_asset(b"0123456789");
+        assert_eq!(asset.get_buffer().unwrap(), b"0123456789");
+
+        // Reads are served from the mapping once it exists.
+        let mut buffer = [0u8; 3];
+        assert_eq!(asset.read(&mut buffer, 3, 7).unwrap(), 3);
+        assert_eq!(&buffer, b"789");
+
+        let (_dir, empty_asset) = create_asset(b"");
+        assert_eq!(empty_asset.get_buffer().unwrap(), b"");
+    }
+}
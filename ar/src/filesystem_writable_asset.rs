@@ -0,0 +1,228 @@
+extern crate std;
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::filesystem::create_temporary_file;
+use crate::writable_asset::check_write_buffer;
+use crate::{WritableAsset, WritableAssetError, WriteMode};
+
+/// Creates a new, uniquely named temporary file next to `path`.
+fn create_temporary_sibling(path: &Path) -> io::Result<(File, PathBuf)> {
+    let file_name = path
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    create_temporary_file(dir, &format!(".{}.", file_name))
+}
+
+/// Writes `count` bytes from `buffer` at `offset` from the beginning of
+/// `file`, as done by [`WritableAsset::write`].
+pub(crate) fn write_file_at(
+    file: &mut File,
+    buffer: &[u8],
+    count: usize,
+    offset: usize,
+) -> Result<usize, WritableAssetError> {
+    check_write_buffer(buffer.len(), count)?;
+    file.seek(SeekFrom::Start(offset as u64))
+        .and_then(|_| file.write_all(&buffer[..count]))
+        .map_err(|err| WritableAssetError::WriteFailed(err.to_string()))?;
+    Ok(count)
+}
+
+/// [`WritableAsset`] implementation for an asset stored on the local
+/// filesystem. Any missing parent directories are created when the asset is
+/// opened.
+///
+/// With [`WriteMode::Replace`] all writes go to a temporary file next to the
+/// destination, which is atomically renamed over the destination by
+/// [`WritableAsset::close`]. Readers therefore only ever see the previous or
+/// the fully written contents, and if the process dies or the asset is
+/// dropped without being closed the destination is left untouched.
+///
+/// With [`WriteMode::Update`] the destination is opened for in-place writes
+/// and created if it does not exist yet.
+#[derive(Debug)]
+pub struct FilesystemWritableAsset {
+    file: Option<File>,
+    path: PathBuf,
+    temporary_path: Option<PathBuf>,
+}
+
+impl FilesystemWritableAsset {
+    /// Opens the file at `path` for writing using the given `write_mode`.
+    pub fn create(path: &str, write_mode: WriteMode) -> Result<Self, WritableAssetError> {
+        let path = PathBuf::from(path);
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).map_err(|err| {
+                WritableAssetError::WriteFailed(format!(
+                    "could not create directory {}: {}",
+                    parent.display(),
+                    err
+                ))
+            })?;
+        }
+
+        let (file, temporary_path) = match write_mode {
+            WriteMode::Update => {
+                let file = OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .truncate(false)
+                    .open(&path)
+                    .map_err(|err| WritableAssetError::WriteFailed(err.to_string()))?;
+                (file, None)
+            }
+            WriteMode::Replace => {
+                let (file, temporary_path) = create_temporary_sibling(&path)
+                    .map_err(|err| WritableAssetError::WriteFailed(err.to_string()))?;
+                // Keep the permissions of the asset being replaced.
+                if let Ok(metadata) = fs::metadata(&path) {
+                    let _ = file.set_permissions(metadata.permissions());
+                }
+                (file, Some(temporary_path))
+            }
+        };
+
+        Ok(Self {
+            file: Some(file),
+            path,
+            temporary_path,
+        })
+    }
+
+    fn commit(&mut self, file: File) -> Result<(), String> {
+        let result = file.sync_all().map_err(|err| err.to_string());
+        drop(file);
+
+        let temporary_path = match self.temporary_path.take() {
+            Some(temporary_path) => temporary_path,
+            None => return result,
+        };
+
+        let result = result.and_then(|_| {
+            fs::rename(&temporary_path, &self.path)
+                .map_err(|err| format!("could not replace {}: {}", self.path.display(), err))
+        });
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary_path);
+        }
+        result
+    }
+}
+
+impl WritableAsset for FilesystemWritableAsset {
+    fn close(&mut self) -> Result<(), WritableAssetError> {
+        let file = self.file.take().ok_or_else(|| {
+            WritableAssetError::CloseFailed("asset is already closed".to_string())
+        })?;
+        self.commit(file).map_err(WritableAssetError::CloseFailed)
+    }
+
+    fn write(
+        &mut self,
+        buffer: &[u8],
+        count: usize,
+        offset: usize,
+    ) -> Result<usize, WritableAssetError> {
+        let file = self
+            .file
+            .as_mut()
+            .ok_or_else(|| WritableAssetError::WriteFailed("asset is closed".to_string()))?;
+        write_file_at(file, buffer, count, offset)
+    }
+}
+
+impl Drop for FilesystemWritableAsset {
+    fn drop(&mut self) {
+        // An asset that was never closed is discarded, leaving the
+        // destination as it was.
+        if let Some(file) = self.file.take() {
+            drop(file);
+            if let Some(temporary_path) = self.temporary_path.take() {
+                let _ = fs::remove_file(temporary_path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(dir: &Path) -> usize {
+        fs::read_dir(dir).unwrap().count()
+    }
+
+    #[test]
+    fn test_replace_is_atomic() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("layer.usd");
+        fs::write(&path, b"old contents").unwrap();
+
+        let mut asset =
+            FilesystemWritableAsset::create(path.to_str().unwrap(), WriteMode::Replace).unwrap();
+        assert_eq!(asset.write(b"new", 3, 0).unwrap(), 3);
+        assert_eq!(fs::read(&path).unwrap(), b"old contents");
+
+        asset.close().unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert_eq!(entries(dir.path()), 1);
+        assert!(asset.write(b"new", 3, 0).is_err());
+        assert!(matches!(
+            asset.close(),
+            Err(WritableAssetError::CloseFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_replace_discarded_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("layer.usd");
+        fs::write(&path, b"old contents").unwrap();
+
+        let mut asset =
+            FilesystemWritableAsset::create(path.to_str().unwrap(), WriteMode::Replace).unwrap();
+        asset.write(b"new", 3, 0).unwrap();
+        drop(asset);
+
+        assert_eq!(fs::read(&path).unwrap(), b"old contents");
+        assert_eq!(entries(dir.path()), 1);
+    }
+
+    #[test]
+    fn test_update_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("layer.usd");
+        fs::write(&path, b"old contents").unwrap();
+
+        let mut asset =
+            FilesystemWritableAsset::create(path.to_str().unwrap(), WriteMode::Update).unwrap();
+        asset.write(b"new", 3, 0).unwrap();
+        asset.close().unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"new contents");
+    }
+
+    #[test]
+    fn test_create_intermediate_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a/b/layer.usd");
+
+        for write_mode in [WriteMode::Replace, WriteMode::Update] {
+            let mut asset =
+                FilesystemWritableAsset::create(path.to_str().unwrap(), write_mode).unwrap();
+            asset.write(b"contents", 8, 0).unwrap();
+            asset.close().unwrap();
+            assert_eq!(fs::read(&path).unwrap(), b"contents");
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::asset::check_read_bounds;
+use crate::{Asset, AssetError};
+
+/// [`Asset`] implementation for an asset held in memory.
+///
+/// The contents are shared rather than copied, so cloning the asset or
+/// creating it from contents held elsewhere is cheap. The contents of an
+/// asset never change once it is created.
+#[derive(Clone, Debug)]
+pub struct InMemoryAsset {
+    data: Arc<[u8]>,
+}
+
+impl InMemoryAsset {
+    /// Creates an asset holding `data`.
+    pub fn new(data: impl Into<Arc<[u8]>>) -> Self {
+        Self { data: data.into() }
+    }
+}
+
+impl From<Vec<u8>> for InMemoryAsset {
+    fn from(data: Vec<u8>) -> Self {
+        Self::new(data)
+    }
+}
+
+impl Asset for InMemoryAsset {
+    fn get_size(&self) -> usize {
+        self.data.len()
+    }
+
+    fn get_buffer(&self) -> Result<&[u8], AssetError> {
+        Ok(&self.data)
+    }
+
+    fn read(&self, buffer: &mut [u8], count: usize, offset: usize) -> Result<usize, AssetError> {
+        check_read_bounds(buffer.len(), count, offset, self.data.len())?;
+
+        buffer[..count].copy_from_slice(&self.data[offset..offset + count]);
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let asset = InMemoryAsset::from(b"0123456789".to_vec());
+        assert_eq!(asset.get_size(), 10);
+        assert_eq!(asset.get_buffer().unwrap(), b"0123456789");
+
+        let mut buffer = [0u8; 4];
+        assert_eq!(asset.read(&mut buffer, 4, 3).unwrap(), 4);
+        assert_eq!(&buffer, b"3456");
+        assert_eq!(asset.read(&mut buffer, 0, 10).unwrap(), 0);
+        assert!(matches!(
+            asset.read(&mut buffer, 4, 8),
+            Err(AssetError::OutOfBounds(8, 4, 10))
+        ));
+        assert!(asset.read(&mut buffer, 5, 0).is_err());
+    }
+}
@@ -20,9 +20,6 @@
 
 #[macro_use]
 extern crate alloc;
-#[cfg(not(target_arch = "wasm32"))]
-#[macro_use]
-extern crate lazy_static;
 #[cfg(test)]
 #[macro_use]
 extern crate std;
@@ -32,18 +29,42 @@ extern crate std;
 
 pub use asset::*;
 pub use asset_info::*;
+#[cfg(feature = "std")]
+pub use filesystem_asset::*;
+#[cfg(feature = "std")]
+pub use filesystem_writable_asset::*;
+pub use in_memory_asset::*;
+pub use package_utils::*;
 pub use resolved_path::*;
 pub use resolver::*;
 pub use resolver_context::*;
+pub use resolver_info::*;
 pub use writable_asset::*;
+pub use zip_file::*;
 
 mod plugin;
 
+#[cfg(any(feature = "resolver_v1", feature = "resolver_v2", feature = "std"))]
+mod filesystem;
 mod package_utils;
+#[cfg(any(feature = "resolver_v1", feature = "resolver_v2"))]
+mod path_utils;
+#[cfg(any(feature = "resolver_v1", feature = "resolver_v2"))]
+mod sync;
 
 mod asset;
 mod asset_info;
+#[cfg(feature = "std")]
+mod filesystem_asset;
+#[cfg(feature = "std")]
+mod filesystem_writable_asset;
+mod in_memory_asset;
 mod resolved_path;
 mod resolver;
 mod resolver_context;
+mod resolver_info;
 mod writable_asset;
+mod zip_file;
+
+#[cfg(feature = "testing")]
+pub mod testing;
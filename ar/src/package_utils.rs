@@ -1,47 +1,171 @@
+//! Utilities for working with package-relative paths.
+//!
+//! A package-relative path refers to an asset inside a package, e.g. a layer
+//! inside a `.usdz` archive. The packaged path is enclosed in `[` and `]`
+//! after the package path, and packages may be nested:
+//!
+//! ```text
+//! /dir/outer.usdz[inner.usdz[layer.usd]]
+//! ```
+//!
+//! Any `[` or `]` that is part of a path itself is escaped with a leading
+//! `\`, e.g. `/dir/\[a\].usdz[layer.usd]` is the asset `layer.usd` inside the
+//! package `/dir/[a].usdz`. The functions here take care of the escaping.
+//!
+//! A path that ends in a `]` with a matching unescaped `[` is always treated
+//! as a package-relative path.
+
 use alloc::string::{String, ToString};
 
-pub(crate) fn is_package_relative_path(path: &str) -> bool {
+/// Returns true if `path` is a package-relative path, i.e. it ends with a
+/// packaged path enclosed in unescaped delimiters.
+///
+/// # Examples
+/// ```
+/// use ar::is_package_relative_path;
+///
+/// assert!(is_package_relative_path("a.usdz[b.usd]"));
+/// assert!(!is_package_relative_path("a.usd"));
+/// assert!(!is_package_relative_path("\\[a\\].usd"));
+/// ```
+pub fn is_package_relative_path(path: &str) -> bool {
     !path.is_empty()
         && path.ends_with(']')
         && find_matching_opening_delimiter(path, path.len() - 1).is_some()
 }
 
-/// Given a string `path, find the index range of the outermost opening and closing
-/// delimiters `[` and `]`.
-pub(crate) fn split_package_relative_path_outer(path: &str) -> Option<(String, String)> {
-    let outermost_closing_index = find_outermost_closing_delimiter(path)?;
-    let outermost_opening_index = find_matching_opening_delimiter(path, path.len() - 1)?;
+/// Combines the given `paths` into a single package-relative path, nesting
+/// each path inside the package given by the previous ones. Empty paths are
+/// ignored.
+///
+/// Delimiters in each path are escaped, except for paths that are already
+/// package-relative paths, which are inserted as-is.
+///
+/// # Examples
+/// ```
+/// use ar::join_package_relative_path;
+///
+/// assert_eq!(join_package_relative_path(&["a.usdz", "b.usd"]), "a.usdz[b.usd]");
+/// assert_eq!(
+///     join_package_relative_path(&["a.usdz", "b.usdz", "c.usd"]),
+///     "a.usdz[b.usdz[c.usd]]"
+/// );
+/// assert_eq!(
+///     join_package_relative_path(&["a.usdz[b.usdz]", "c.usd"]),
+///     "a.usdz[b.usdz[c.usd]]"
+/// );
+/// assert_eq!(
+///     join_package_relative_path(&["[a].usdz", "b.usd"]),
+///     "\\[a\\].usdz[b.usd]"
+/// );
+/// ```
+pub fn join_package_relative_path(paths: &[&str]) -> String {
+    let mut result = String::new();
+
+    for path in paths.iter().filter(|path| !path.is_empty()) {
+        let path = if is_package_relative_path(path) {
+            path.to_string()
+        } else {
+            escape_delimiters(path)
+        };
+
+        if result.is_empty() {
+            result = path;
+            continue;
+        }
+
+        // Nest the path inside the innermost package.
+        let insert_index = match find_innermost_packaged_path(&result) {
+            Some((_, end)) => end,
+            None => result.len(),
+        };
+        result.insert_str(insert_index, &(String::from("[") + &path + "]"));
+    }
+
+    result
+}
+
+/// Splits a package-relative path into the outermost package path and the
+/// packaged path within it. If `path` is not a package-relative path, it is
+/// returned unescaped with an empty packaged path.
+///
+/// Delimiters in the returned paths are unescaped, except in the packaged
+/// path if it is itself a package-relative path.
+///
+/// # Examples
+/// ```
+/// use ar::split_package_relative_path_outer;
+///
+/// assert_eq!(
+///     split_package_relative_path_outer("a.usdz[b.usdz[c.usd]]"),
+///     ("a.usdz".to_string(), "b.usdz[c.usd]".to_string())
+/// );
+/// assert_eq!(
+///     split_package_relative_path_outer("a.usd"),
+///     ("a.usd".to_string(), String::new())
+/// );
+/// ```
+pub fn split_package_relative_path_outer(path: &str) -> (String, String) {
+    if !is_package_relative_path(path) {
+        return (unescape_delimiters(path), String::new());
+    }
 
-    Some((
-        unescape_delimiters(&path[..outermost_opening_index]),
-        path[outermost_opening_index + 1..outermost_closing_index].to_string(),
-    ))
+    let (package_path, packaged_path) = split_package_path(path);
+    (
+        unescape_delimiters(package_path),
+        unescape_delimiters(&packaged_path[1..packaged_path.len() - 1]),
+    )
+}
+
+/// Splits a package-relative path into the package path and the innermost
+/// packaged path. If `path` is not a package-relative path, it is returned
+/// unescaped with an empty packaged path.
+///
+/// Delimiters in the returned paths are unescaped, except in the package
+/// path if it is still a package-relative path.
+///
+/// # Examples
+/// ```
+/// use ar::split_package_relative_path_inner;
+///
+/// assert_eq!(
+///     split_package_relative_path_inner("a.usdz[b.usdz[c.usd]]"),
+///     ("a.usdz[b.usdz]".to_string(), "c.usd".to_string())
+/// );
+/// assert_eq!(
+///     split_package_relative_path_inner("a.usd"),
+///     ("a.usd".to_string(), String::new())
+/// );
+/// ```
+pub fn split_package_relative_path_inner(path: &str) -> (String, String) {
+    let (begin, end) = match find_innermost_packaged_path(path) {
+        Some(range) => range,
+        None => return (unescape_delimiters(path), String::new()),
+    };
+
+    // Remove the innermost packaged path along with its delimiters.
+    let package_path = path[..begin - 1].to_string() + &path[end + 1..];
+    (
+        unescape_delimiters(&package_path),
+        unescape_delimiters(&path[begin..end]),
+    )
+}
+
+/// Escapes all delimiters in `path`.
+pub(crate) fn escape_delimiters(path: &str) -> String {
+    path.replace('[', "\\[").replace(']', "\\]")
 }
 
 // Unescape delimiters in the given path to give clients the 'real' path
 // when extracting paths from the packaged part of a package-relative path.
 //
-// If path is a package-relative path, we assume the packaged portion of
-// that path has already been escaped and only process the package portion.
+// If path is a package-relative path, it is left as-is so it can still be
+// split further.
 pub(crate) fn unescape_delimiters(path: &str) -> String {
-    if path.is_empty() {
+    if is_package_relative_path(path) {
         return path.to_string();
     }
-
-    let escape_range_begin = 0;
-    let escape_range_end = if path.ends_with(']') {
-        path.len()
-    } else if let Some(outer_open_index) = find_matching_opening_delimiter(path, path.len() - 1) {
-        outer_open_index
-    } else {
-        path.len()
-    };
-
-    let escaped_string = &path[escape_range_begin..escape_range_end];
-    let escaped_string = escaped_string.replace("\\[", "[");
-    let escaped_string = escaped_string.replace("\\]", "]");
-
-    escaped_string + &path[escape_range_end..path.len()]
+    path.replace("\\[", "[").replace("\\]", "]")
 }
 
 /// Given an index `closing_delimiter_index` for `path` pointing to a closing
@@ -81,11 +205,36 @@ pub(crate) fn find_matching_opening_delimiter(
     None
 }
 
-pub(crate) fn find_outermost_closing_delimiter(path: &str) -> Option<usize> {
-    if path.is_empty() || !path.ends_with(']') {
-        return None;
+/// Splits `path` into its outer package path and the remaining packaged
+/// portion including the enclosing delimiters, e.g. `/dir/foo.usdz[bar.usd]`
+/// is split into `/dir/foo.usdz` and `[bar.usd]`. Both parts are returned
+/// as-is, without unescaping. If `path` is not a package-relative path the
+/// packaged portion is empty.
+pub(crate) fn split_package_path(path: &str) -> (&str, &str) {
+    if is_package_relative_path(path) {
+        if let Some(index) = find_matching_opening_delimiter(path, path.len() - 1) {
+            return path.split_at(index);
+        }
     }
-    Some(path.len() - 1)
+    (path, "")
+}
+
+/// Returns the byte range of the innermost packaged path in `path`, e.g.
+/// `baz.usd` in `foo.usdz[bar.usdz[baz.usd]]`, or [`None`] if `path` is not
+/// a package-relative path.
+pub(crate) fn find_innermost_packaged_path(path: &str) -> Option<(usize, usize)> {
+    let mut range = None;
+    let (mut begin, mut end) = (0, path.len());
+
+    while is_package_relative_path(&path[begin..end]) {
+        let segment = &path[begin..end];
+        let open_index = find_matching_opening_delimiter(segment, segment.len() - 1)?;
+        begin += open_index + 1;
+        end -= 1;
+        range = Some((begin, end));
+    }
+
+    range
 }
 
 #[cfg(test)]
@@ -94,11 +243,13 @@ mod tests {
 
     #[test]
     fn test_unescape_delimiters() {
-        let path = "/dir/\\[foo\\].package[bar.package[baz.file]]";
         assert_eq!(
-            unescape_delimiters(path),
-            "/dir/[foo].package[bar.package[baz.file]]"
+            unescape_delimiters("/dir/\\[foo\\].package"),
+            "/dir/[foo].package"
         );
+        // The escaping of package-relative paths is preserved.
+        let path = "/dir/\\[foo\\].package[bar.package[baz.file]]";
+        assert_eq!(unescape_delimiters(path), path);
     }
 
     #[test]
@@ -120,13 +271,46 @@ mod tests {
     #[test]
     fn test_split_package_relative_path_outer() {
         let path = "/dir/\\[foo\\].package[bar.package[baz.file]]";
-        let paths = split_package_relative_path_outer(path);
         assert_eq!(
-            paths,
-            Some((
+            split_package_relative_path_outer(path),
+            (
                 "/dir/[foo].package".to_string(),
                 "bar.package[baz.file]".to_string()
-            ))
+            )
+        );
+        assert_eq!(
+            split_package_relative_path_outer("foo.package[\\[bar\\].file]"),
+            ("foo.package".to_string(), "[bar].file".to_string())
+        );
+    }
+
+    #[test]
+    fn test_split_package_relative_path_inner() {
+        let path = "/dir/\\[foo\\].package[bar.package[\\[baz\\].file]]";
+        assert_eq!(
+            split_package_relative_path_inner(path),
+            (
+                "/dir/\\[foo\\].package[bar.package]".to_string(),
+                "[baz].file".to_string()
+            )
+        );
+        assert_eq!(
+            split_package_relative_path_inner("/dir/\\[foo\\].package[bar.file]"),
+            ("/dir/[foo].package".to_string(), "bar.file".to_string())
+        );
+    }
+
+    #[test]
+    fn test_join_package_relative_path() {
+        assert_eq!(join_package_relative_path(&[]), "");
+        assert_eq!(join_package_relative_path(&["", "a.usd", ""]), "a.usd");
+        assert_eq!(
+            join_package_relative_path(&["/dir/[foo].package", "bar.package[baz.file]"]),
+            "/dir/\\[foo\\].package[bar.package[baz.file]]"
+        );
+        assert_eq!(
+            join_package_relative_path(&["a.package[b.package]", "c.package[d.file]"]),
+            "a.package[b.package[c.package[d.file]]]"
         );
     }
 }
@@ -0,0 +1,143 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Returns true if `path` is a relative path. Paths starting with `/` or
+/// with a drive letter like `C:/` are treated as absolute.
+pub(crate) fn is_relative_path(path: &str) -> bool {
+    // Backslashes are deliberately not treated as separators since they are
+    // used to escape package delimiters, see `package_utils`.
+    if path.starts_with('/') {
+        return false;
+    }
+
+    let bytes = path.as_bytes();
+    !(bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'/')
+}
+
+/// Returns true if `path` is relative to the file it is authored in, i.e.
+/// it starts with `./` or `../`.
+pub(crate) fn is_file_relative_path(path: &str) -> bool {
+    path.starts_with("./") || path.starts_with("../")
+}
+
+/// Returns the directory portion of `path`, including the trailing
+/// separator. Returns an empty string if `path` has no directory portion.
+pub(crate) fn get_path_name(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(index) => &path[..=index],
+        None => "",
+    }
+}
+
+/// Returns the final component of `path`.
+pub(crate) fn get_base_name(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(index) => &path[index + 1..],
+        None => path,
+    }
+}
+
+/// Returns the extension of `path` without the leading `.`, or an empty
+/// string if there is none. Dot files like `.hidden` have no extension.
+pub(crate) fn get_extension(path: &str) -> &str {
+    let base_name = get_base_name(path);
+    match base_name.rfind('.') {
+        Some(0) | None => "",
+        Some(index) => &base_name[index + 1..],
+    }
+}
+
+/// Concatenates `prefix` and `suffix` with a single `/` separator.
+pub(crate) fn cat_paths(prefix: &str, suffix: &str) -> String {
+    if prefix.is_empty() {
+        return suffix.to_string();
+    }
+    if suffix.is_empty() {
+        return prefix.to_string();
+    }
+
+    let mut path = prefix.to_string();
+    if !path.ends_with('/') {
+        path.push('/');
+    }
+    path.push_str(suffix);
+    path
+}
+
+/// Returns a normalized version of `path`. Repeated separators and `.`
+/// components are removed and `..` components are collapsed where possible.
+pub(crate) fn norm_path(path: &str) -> String {
+    if path.is_empty() {
+        return String::new();
+    }
+
+    let (root, rest) = if let Some(rest) = path.strip_prefix('/') {
+        ("/", rest)
+    } else if !is_relative_path(path) {
+        path.split_at(3)
+    } else {
+        ("", path)
+    };
+
+    let mut components: Vec<&str> = Vec::new();
+    for component in rest.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => match components.last() {
+                Some(&last) if last != ".." => {
+                    components.pop();
+                }
+                // `..` at the root of an absolute path is a no-op.
+                _ if !root.is_empty() => {}
+                _ => components.push(component),
+            },
+            _ => components.push(component),
+        }
+    }
+
+    let normalized = root.to_string() + &components.join("/");
+    if normalized.is_empty() {
+        ".".to_string()
+    } else {
+        normalized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_relative_path() {
+        assert!(is_relative_path("foo/bar.usd"));
+        assert!(is_relative_path("./bar.usd"));
+        assert!(!is_relative_path("/foo/bar.usd"));
+        assert!(!is_relative_path("C:/foo/bar.usd"));
+    }
+
+    #[test]
+    fn test_get_extension() {
+        assert_eq!(get_extension("/foo/bar.usd"), "usd");
+        assert_eq!(get_extension("/foo.dir/bar"), "");
+        assert_eq!(get_extension("/foo/.hidden"), "");
+        assert_eq!(get_extension("bar.tar.gz"), "gz");
+    }
+
+    #[test]
+    fn test_norm_path() {
+        assert_eq!(norm_path("/foo//bar/./baz.usd"), "/foo/bar/baz.usd");
+        assert_eq!(norm_path("/foo/bar/../baz.usd"), "/foo/baz.usd");
+        assert_eq!(norm_path("/../baz.usd"), "/baz.usd");
+        assert_eq!(norm_path("../foo/../../baz.usd"), "../../baz.usd");
+        assert_eq!(norm_path("C:/foo/../baz.usd"), "C:/baz.usd");
+        assert_eq!(norm_path("/dir/\\[foo\\].pkg"), "/dir/\\[foo\\].pkg");
+        assert_eq!(norm_path("foo/.."), ".");
+    }
+
+    #[test]
+    fn test_cat_paths() {
+        assert_eq!(cat_paths("/foo/", "bar.usd"), "/foo/bar.usd");
+        assert_eq!(cat_paths("/foo", "bar.usd"), "/foo/bar.usd");
+        assert_eq!(cat_paths("", "bar.usd"), "bar.usd");
+    }
+}
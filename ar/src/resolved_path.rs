@@ -3,7 +3,7 @@ use alloc::fmt;
 use alloc::string::String;
 
 /// Represents a resolved asset path.
-#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, Ord, PartialOrd)]
 pub struct ResolvedPath(String);
 
 impl ResolvedPath {
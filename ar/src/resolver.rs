@@ -1,12 +1,28 @@
+use alloc::sync::Arc;
+use core::any::Any;
+
 pub use error::*;
 
+#[cfg(any(feature = "resolver_v1", feature = "resolver_v2"))]
+mod default_resolution;
 mod error;
+
+/// Data associated with a resolution cache scope, see
+/// [`Resolver::begin_cache_scope`]. It can be sent to other threads to share
+/// the scope's cache with them.
+pub type CacheScopeData = Option<Arc<dyn Any + Send + Sync>>;
+
+#[cfg(feature = "resolver_v1")]
+pub mod v1;
+#[cfg(feature = "resolver_v2")]
+pub mod v2;
+
+// The crate root exposes the Ar 2.0 API if it is enabled, so enabling
+// `resolver_v1` alongside it only adds the `v1` module.
 cfg_if::cfg_if! {
-    if #[cfg(feature = "resolver_v1")] {
-        mod v1;
-        pub use v1::*;
-    } else if #[cfg(feature = "resolver_v2")] {
-        mod v2;
+    if #[cfg(feature = "resolver_v2")] {
         pub use v2::*;
+    } else if #[cfg(feature = "resolver_v1")] {
+        pub use v1::*;
     }
 }
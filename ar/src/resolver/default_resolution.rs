@@ -0,0 +1,187 @@
+//! Filesystem-based resolution shared by the Ar 1.0 and Ar 2.0 default
+//! resolvers.
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::filesystem;
+use crate::package_utils::{find_innermost_packaged_path, split_package_path, unescape_delimiters};
+use crate::path_utils::{
+    cat_paths, get_path_name, is_file_relative_path, is_relative_path, norm_path,
+};
+use crate::{Asset, ResolvedPath, ResolverError, WritableAsset, WriteMode};
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "std")] {
+        extern crate std;
+        use alloc::format;
+
+        use crate::sync::{lock, Mutex};
+        use crate::{DefaultResolverContext, FilesystemAsset, FilesystemWritableAsset};
+
+        /// Environment variable holding additional fallback search paths,
+        /// separated by [`crate::PATH_LIST_SEPARATOR`].
+        const DEFAULT_SEARCH_PATH_ENV_VAR: &str = "PXR_AR_DEFAULT_SEARCH_PATH";
+
+        static DEFAULT_SEARCH_PATH: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+        pub(crate) fn get_default_search_path() -> Vec<String> {
+            let mut search_path = lock(&DEFAULT_SEARCH_PATH).clone();
+
+            if let Ok(env_search_path) = std::env::var(DEFAULT_SEARCH_PATH_ENV_VAR) {
+                search_path.extend(
+                    DefaultResolverContext::from_path_list(&env_search_path)
+                        .get_search_path()
+                        .iter()
+                        .cloned(),
+                );
+            }
+
+            search_path
+        }
+
+        /// Sets the default search path used by the default resolvers, see
+        /// `DefaultResolver::set_default_search_path`.
+        pub(crate) fn set_default_search_path(search_path: &[&str]) {
+            let context = DefaultResolverContext::new(search_path);
+            *lock(&DEFAULT_SEARCH_PATH) = context.get_search_path().to_vec();
+        }
+
+        pub(crate) fn open_filesystem_asset(path: &str) -> Result<Arc<dyn Asset>, ResolverError> {
+            match FilesystemAsset::open(path) {
+                Ok(asset) => Ok(Arc::new(asset)),
+                Err(err) => Err(ResolverError::OpenAssetError(format!("{}: {}", path, err))),
+            }
+        }
+
+        // Only the Ar 2.0 resolvers provide writable assets.
+        #[cfg_attr(not(feature = "resolver_v2"), allow(dead_code))]
+        pub(crate) fn open_filesystem_asset_for_write(
+            path: &str,
+            write_mode: WriteMode,
+        ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+            match FilesystemWritableAsset::create(path, write_mode) {
+                Ok(asset) => Ok(Box::new(asset)),
+                Err(err) => Err(ResolverError::OpenAssetError(format!("{}: {}", path, err))),
+            }
+        }
+    } else {
+        pub(crate) fn get_default_search_path() -> Vec<String> {
+            Vec::new()
+        }
+
+        pub(crate) fn open_filesystem_asset(path: &str) -> Result<Arc<dyn Asset>, ResolverError> {
+            Err(ResolverError::OpenAssetError(path.to_string()))
+        }
+
+        // Only the Ar 2.0 resolvers provide writable assets.
+        #[cfg_attr(not(feature = "resolver_v2"), allow(dead_code))]
+        pub(crate) fn open_filesystem_asset_for_write(
+            path: &str,
+            _write_mode: WriteMode,
+        ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+            Err(ResolverError::OpenAssetError(path.to_string()))
+        }
+    }
+}
+
+/// Returns true if `path` should be looked up using the search path, i.e. it
+/// is a relative path that does not start with `./` or `../`.
+pub(crate) fn is_search_path(path: &str) -> bool {
+    !path.is_empty() && is_relative_path(path) && !is_file_relative_path(path)
+}
+
+/// Returns the resolved path for `path` anchored to the directory
+/// `anchor_path` if the asset exists, or an empty [`ResolvedPath`] otherwise.
+/// Relative paths are anchored to the current working directory.
+pub(crate) fn resolve_anchored(anchor_path: &str, path: &str) -> ResolvedPath {
+    let (package_path, packaged_path) = split_package_path(path);
+    let resolved_path = filesystem::abs_path(&cat_paths(anchor_path, package_path));
+    if filesystem::path_exists(&unescape_delimiters(&resolved_path)) {
+        ResolvedPath::new(&(resolved_path + packaged_path))
+    } else {
+        ResolvedPath::default()
+    }
+}
+
+/// Normalizes the outer package path of `path`, leaving any packaged portion
+/// untouched.
+pub(crate) fn norm_asset_path(path: &str) -> String {
+    let (package_path, packaged_path) = split_package_path(path);
+    norm_path(package_path) + packaged_path
+}
+
+/// Anchors `path` to `anchor_path`. If `anchor_path` is a package-relative
+/// path, `path` is anchored to the innermost packaged path so the result
+/// refers to an asset within the same package.
+pub(crate) fn anchor_to(anchor_path: &str, path: &str) -> String {
+    if path.is_empty() || !is_relative_path(path) {
+        return path.to_string();
+    }
+
+    if let Some((begin, end)) = find_innermost_packaged_path(anchor_path) {
+        let packaged_anchor = &anchor_path[begin..end];
+        let anchored = norm_asset_path(&cat_paths(get_path_name(packaged_anchor), path));
+        return anchor_path[..begin].to_string() + &anchored + &anchor_path[end..];
+    }
+
+    if is_relative_path(anchor_path) {
+        return path.to_string();
+    }
+
+    norm_asset_path(&cat_paths(get_path_name(anchor_path), path))
+}
+
+/// Returns the resolved path for the search-path style or relative
+/// `asset_path`: it is first looked up relative to the current working
+/// directory, then in each directory of `search_path` and finally in each
+/// directory of `fallback_search_path`.
+pub(crate) fn resolve_in_search_path(
+    asset_path: &str,
+    search_path: &[String],
+    fallback_search_path: &[String],
+) -> ResolvedPath {
+    let resolved_path = resolve_anchored("", asset_path);
+    if !resolved_path.is_empty() || !is_search_path(asset_path) {
+        return resolved_path;
+    }
+
+    search_path
+        .iter()
+        .chain(fallback_search_path.iter())
+        .map(|search_dir| resolve_anchored(search_dir, asset_path))
+        .find(|resolved_path| !resolved_path.is_empty())
+        .unwrap_or_default()
+}
+
+/// Returns the path to the asset at `path` if it is created, making relative
+/// paths absolute. The packaged portion of package-relative paths is kept
+/// as-is.
+pub(crate) fn compute_local_path(path: &str) -> String {
+    if path.is_empty() {
+        return String::new();
+    }
+
+    let (package_path, packaged_path) = split_package_path(path);
+    filesystem::abs_path(package_path) + packaged_path
+}
+
+/// Creates the directories needed to write a file to `path`.
+pub(crate) fn create_path_for_layer(path: &str) -> Result<(), ResolverError> {
+    let directory = get_path_name(path);
+    if directory.is_empty() {
+        return Ok(());
+    }
+    filesystem::create_directories(directory)
+        .map_err(|reason| ResolverError::CannotWriteLayerToPath(path.to_string(), reason))
+}
+
+/// Returns the modification time of the file at `resolved_path`, or of the
+/// outer package for package-relative paths.
+pub(crate) fn get_modification_timestamp(resolved_path: &str) -> Result<i64, ResolverError> {
+    let (package_path, _) = split_package_path(resolved_path);
+    filesystem::get_modification_time(&unescape_delimiters(package_path))
+        .map_err(|_| ResolverError::AssetMtimeError)
+}
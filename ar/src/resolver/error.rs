@@ -1,6 +1,8 @@
 use alloc::string::String;
 use core::fmt;
 
+use crate::AssetError;
+
 /// Resolver errors
 #[derive(Clone, Debug)]
 pub enum ResolverError {
@@ -15,6 +17,16 @@ pub enum ResolverError {
 
     /// Resolver cannot create a new layer with the given identifier
     CannotCreateNewLayerWithIdentifier(String, String),
+
+    /// A resolver cannot be registered for the given URI scheme
+    InvalidUriScheme(String, String),
+
+    /// A package resolver cannot be registered for the given extension
+    InvalidPackageExtension(String, String),
+
+    /// The asset at the given resolved path cannot be opened because of the
+    /// given asset error
+    InvalidAsset(String, AssetError),
 }
 
 impl fmt::Display for ResolverError {
@@ -34,6 +46,15 @@ impl fmt::Display for ResolverError {
                     identifier, reason
                 )
             }
+            ResolverError::InvalidUriScheme(uri_scheme, reason) => {
+                write!(f, "invalid URI scheme `{}`: {}", uri_scheme, reason)
+            }
+            ResolverError::InvalidPackageExtension(extension, reason) => {
+                write!(f, "invalid package extension `{}`: {}", extension, reason)
+            }
+            ResolverError::InvalidAsset(resolved_path, err) => {
+                write!(f, "cannot open asset `{}`: {}", resolved_path, err)
+            }
         }
     }
 }
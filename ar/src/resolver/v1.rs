@@ -1,15 +1,28 @@
+//! The Ar 1.0 asset resolution API.
+//!
+//! This module is available with the `resolver_v1` feature. If the
+//! `resolver_v2` feature is enabled as well, the crate root exposes the Ar 2.0
+//! API instead, and resolvers implementing [`Resolver`] can be used with it
+//! through [`crate::v2::V1ResolverAdapter`].
+
+use alloc::string::String;
+use alloc::sync::Arc;
 use core::any::Any;
 
-use crate::{Asset, AssetInfo, ResolverContext, ResolverError};
+use crate::{Asset, AssetInfo, ResolverError};
+
+pub use super::CacheScopeData;
+pub use crate::resolver_context::resolver_context_v1::ResolverContext;
+pub use default_resolver::*;
 
-mod wrapper;
+mod default_resolver;
 
 /// Trait for the asset resolution system. An asset resolver is
 /// responsible for resolving asset information (including the asset's
 /// physical path) from a logical path.
 pub trait Resolver {
     /// Configures the resolver for a given asset path
-    fn configure_resolver_for_asset(&mut self);
+    fn configure_resolver_for_asset(&mut self, path: &str);
 
     /// Returns the path formed by anchoring `path` to `anchor_path`.
     ///
@@ -22,33 +35,38 @@ pub trait Resolver {
     ///
     /// If `path` is empty or not a relative path, it will be
     /// returned as-is.
-    fn anchor_relative_path(&self, anchor_path: &str, path: &str) -> &str;
+    fn anchor_relative_path(&self, anchor_path: &str, path: &str) -> String;
 
     /// Returns true if the given path is a relative path.
-    fn is_relative_path(&self) -> bool;
+    fn is_relative_path(&self, path: &str) -> bool;
 
-    /// Returns true if the given path is a repository path.
-    fn is_repository_path(&self) -> bool;
+    /// Returns true if the given path is a repository path, i.e. a path
+    /// understood by an asset management system rather than a filesystem
+    /// path.
+    fn is_repository_path(&self, path: &str) -> bool;
 
     /// Returns whether this path is a search path.
-    fn is_search_path(&self) -> bool;
+    fn is_search_path(&self, path: &str) -> bool;
 
-    /// Returns the normalized extension for the given `path`.
-    fn get_extension(&self) -> &str;
+    /// Returns the normalized extension for the given `path`, without a
+    /// leading ".".
+    fn get_extension(&self, path: &str) -> String;
 
     /// Returns a normalized version of the given `path`.
-    fn compute_normalized_path(&self) -> &str;
+    fn compute_normalized_path(&self, path: &str) -> String;
 
-    /// Returns the computed repository path using the current resolver.
-    fn compute_repository_path(&self) -> &str;
+    /// Returns the computed repository path for the given `path` using the
+    /// current resolver, or an empty string if it has none.
+    fn compute_repository_path(&self, path: &str) -> String;
 
-    /// Returns the local path for the given `path`.
-    fn compute_local_path(&self, path: &str) -> &str;
+    /// Returns the local path for the given `path`, i.e. the filesystem path
+    /// at which a new layer for `path` would be created.
+    fn compute_local_path(&self, path: &str) -> String;
 
     /// Returns the resolved filesystem path for the file identified by
     /// the given `path` if it exists. If the file does not exist,
     /// returns an empty string.
-    fn resolve(&self, path: &str) -> &str;
+    fn resolve(&self, path: &str) -> String;
 
     /// Binds the given context to this resolver.
     ///
@@ -78,7 +96,7 @@ pub trait Resolver {
     fn create_default_context_for_asset(&self, file_path: &str) -> ResolverContext;
 
     /// Refresh any caches associated with the given context.
-    fn refresh_context(&mut self, context: ResolverContext);
+    fn refresh_context(&mut self, context: &ResolverContext);
 
     /// Returns the currently-bound asset resolver context.
     ///
@@ -95,7 +113,7 @@ pub trait Resolver {
     /// reasonably compute about the asset without actually opening it.
     ///
     /// see [`Self::resolve()`].
-    fn resolve_with_asset_info(&self, path: &str, asset_info: Option<AssetInfo>) -> &str;
+    fn resolve_with_asset_info(&self, path: &str, asset_info: Option<&mut AssetInfo>) -> String;
 
     /// Update `asset_info` with respect to the given `file_version`.
     /// Note: This API is currently in flux. In general, you should prefer
@@ -125,6 +143,17 @@ pub trait Resolver {
         resolved_path: &str,
     ) -> Result<i64, ResolverError>;
 
+    /// Fetch the asset identified by `path` to the filesystem location
+    /// specified by `resolved_path`. `resolved_path` is the resolved path
+    /// that results from calling [`Self::resolve()`] or
+    /// [`Self::resolve_with_asset_info()`] on `path`.
+    ///
+    /// Returns true if the asset was successfully fetched to the specified
+    /// `resolved_path` or if no fetching was required. If `resolved_path`
+    /// is not a local path or the asset could not be fetched to that path,
+    /// returns false.
+    fn fetch_to_local_resolved_path(&self, path: &str, resolved_path: &str) -> bool;
+
     /// Returns an [`Asset`] object for the asset located at `resolved_path`.
     /// Returns an error if object could not be created.
     ///
@@ -136,7 +165,7 @@ pub trait Resolver {
     /// example, a client may have created a memory mapping using the FILE*
     /// presented in the ArAsset object; this would preclude truncating or
     /// overwriting any of the contents of that file.
-    fn open_asset(&self, resolved_path: &str) -> Result<&dyn Asset, ResolverError>;
+    fn open_asset(&self, resolved_path: &str) -> Result<Arc<dyn Asset>, ResolverError>;
 
     /// Create path needed to write a file to the given `path`.
     ///
@@ -151,13 +180,13 @@ pub trait Resolver {
     /// [`Self::create_path_for_layer()`] will be called before writing the layer out.
     fn create_path_for_layer(&self, path: &str) -> Result<(), ResolverError>;
 
-    /// Returns [`Result::Ok`] if a file may be written to the given `path`, [`ResolverError::CannotWriteLayerToPath`]
-    /// otherwise.
+    /// Returns [`Result::Ok`] if a file may be written to the given `path`,
+    /// [`ResolverError::CannotWriteLayerToPath`] otherwise.
     ///
     /// In practice, when writing a layer, [`Self::can_write_layer_to_path()`] will be called
     /// first to check if writing is permitted. If this returns success, then
     /// [`Self::create_path_for_layer()`] will be called before writing the layer out.
-    fn can_write_layer_to_path(&self) -> Result<(), ResolverError>;
+    fn can_write_layer_to_path(&self, path: &str) -> Result<(), ResolverError>;
 
     /// Returns success if a new file may be created using the given.
     /// `identifier`, [`ResolverError::CannotCreateNewLayerWithIdentifier`] otherwise.
@@ -196,9 +225,7 @@ pub trait Resolver {
     /// Resolvers may fill `cache_scope_data` with arbitrary data. Clients may
     /// also pass in a `cache_scope_data` populated by an earlier call to
     /// [`Self::begin_cache_scope()`] to allow the resolver access to that information.
-    ///
-    /// see [`ResolverScopedCache`]
-    fn begin_cache_scope(&mut self, cache_scope_data: Option<&dyn Any>);
+    fn begin_cache_scope(&mut self, cache_scope_data: &mut CacheScopeData);
 
     /// Mark the end of a resolution caching scope.
     ///
@@ -207,9 +234,7 @@ pub trait Resolver {
     ///
     /// `cache_scope_data` should contain the data that was populated by the
     /// previous corresponding call to begin_cache_scope.
-    ///
-    /// see [`ResolverScopedCache`]
-    fn end_cache_scope(&mut self, cache_scope_data: &dyn Any);
+    fn end_cache_scope(&mut self, cache_scope_data: &mut CacheScopeData);
 }
 
 // /// Returns the configured asset resolver.
@@ -0,0 +1,372 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::any::Any;
+
+use super::{CacheScopeData, Resolver, ResolverContext};
+use crate::package_utils::{find_innermost_packaged_path, is_package_relative_path};
+use crate::path_utils::{self, get_path_name};
+use crate::resolver::default_resolution::{
+    anchor_to, compute_local_path, get_default_search_path, get_modification_timestamp,
+    is_search_path, norm_asset_path, open_filesystem_asset, resolve_in_search_path,
+};
+use crate::sync::{lock, Mutex};
+use crate::{Asset, AssetInfo, DefaultResolverContext, ResolverError};
+
+/// Results of [`DefaultResolver::resolve`] cached for a cache scope, shared
+/// through the scope's [`CacheScopeData`].
+#[derive(Debug, Default)]
+struct ScopeCache {
+    resolved_paths: Mutex<BTreeMap<String, String>>,
+}
+
+/// Default Ar 1.0 asset resolution implementation.
+///
+/// Asset paths are treated as filesystem paths and resolved the same way as
+/// by the Ar 2.0 default resolver: search-path style asset paths like `props/chair.usd` are looked up
+/// relative to the current working directory, then in each directory of the
+/// search path of the bound [`DefaultResolverContext`], and finally in the
+/// fallback search path. The fallback search path is made up of the paths
+/// given to [`DefaultResolver::set_default_search_path`] followed by the
+/// paths in the `PXR_AR_DEFAULT_SEARCH_PATH` environment variable.
+///
+/// Filesystem paths are not repository paths, so this resolver has no
+/// repository paths. The local path of an asset, where a new layer for it is
+/// created, is its absolute filesystem path.
+///
+/// # Examples
+/// ```
+/// use ar::v1::{DefaultResolver, Resolver};
+///
+/// let resolver = DefaultResolver::new();
+/// assert_eq!(
+///     resolver.anchor_relative_path("/shots/s01/shot.usd", "./layout.usd"),
+///     "/shots/s01/layout.usd"
+/// );
+/// assert!(!resolver.is_repository_path("/shots/s01/shot.usd"));
+/// assert_eq!(resolver.compute_repository_path("/shots/s01/shot.usd"), "");
+/// ```
+#[derive(Debug)]
+pub struct DefaultResolver {
+    context_stack: Vec<ResolverContext>,
+    cache_scopes: Vec<Arc<ScopeCache>>,
+    fallback_search_path: Vec<String>,
+}
+
+impl DefaultResolver {
+    /// Constructor
+    pub fn new() -> Self {
+        Self {
+            context_stack: Vec::new(),
+            cache_scopes: Vec::new(),
+            fallback_search_path: get_default_search_path(),
+        }
+    }
+
+    /// Sets the default search path consulted by resolvers constructed after
+    /// this call when a search-path style asset path cannot be found using
+    /// the bound context. These paths are searched before the paths in the
+    /// `PXR_AR_DEFAULT_SEARCH_PATH` environment variable.
+    #[cfg(feature = "std")]
+    pub fn set_default_search_path(search_path: &[&str]) {
+        crate::resolver::default_resolution::set_default_search_path(search_path);
+    }
+
+    fn resolve_with_context(&self, path: &str) -> String {
+        let search_path = self
+            .context_stack
+            .last()
+            .and_then(|context| context.get::<DefaultResolverContext>())
+            .map(|context| context.get_search_path())
+            .unwrap_or_default();
+        resolve_in_search_path(path, search_path, &self.fallback_search_path)
+            .get_path_string()
+            .to_string()
+    }
+}
+
+impl Default for DefaultResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver for DefaultResolver {
+    fn configure_resolver_for_asset(&mut self, _path: &str) {}
+
+    fn anchor_relative_path(&self, anchor_path: &str, path: &str) -> String {
+        if anchor_path.is_empty() {
+            return path.to_string();
+        }
+        anchor_to(anchor_path, path)
+    }
+
+    fn is_relative_path(&self, path: &str) -> bool {
+        !path.is_empty() && path_utils::is_relative_path(path)
+    }
+
+    fn is_repository_path(&self, _path: &str) -> bool {
+        false
+    }
+
+    fn is_search_path(&self, path: &str) -> bool {
+        is_search_path(path)
+    }
+
+    fn get_extension(&self, path: &str) -> String {
+        let path = match find_innermost_packaged_path(path) {
+            Some((begin, end)) => &path[begin..end],
+            None => path,
+        };
+        path_utils::get_extension(path).to_string()
+    }
+
+    fn compute_normalized_path(&self, path: &str) -> String {
+        if path.is_empty() {
+            return String::new();
+        }
+        norm_asset_path(path)
+    }
+
+    fn compute_repository_path(&self, _path: &str) -> String {
+        String::new()
+    }
+
+    fn compute_local_path(&self, path: &str) -> String {
+        compute_local_path(path)
+    }
+
+    fn resolve(&self, path: &str) -> String {
+        self.resolve_with_asset_info(path, None)
+    }
+
+    fn bind_context(&mut self, context: &ResolverContext, _binding_data: &dyn Any) {
+        self.context_stack.push(context.clone());
+    }
+
+    fn unbind_context(&mut self, context: &ResolverContext, _binding_data: &dyn Any) {
+        if self.context_stack.last() == Some(context) {
+            self.context_stack.pop();
+        }
+    }
+
+    fn create_default_context(&self) -> ResolverContext {
+        ResolverContext::default()
+    }
+
+    fn create_default_context_for_asset(&self, file_path: &str) -> ResolverContext {
+        if file_path.is_empty() {
+            return ResolverContext::default();
+        }
+        let asset_dir = compute_local_path(file_path);
+        ResolverContext::new(DefaultResolverContext::new(&[get_path_name(&asset_dir)]))
+    }
+
+    fn refresh_context(&mut self, _context: &ResolverContext) {}
+
+    fn get_current_context(&self) -> ResolverContext {
+        self.context_stack.last().cloned().unwrap_or_default()
+    }
+
+    fn resolve_with_asset_info(&self, path: &str, _asset_info: Option<&mut AssetInfo>) -> String {
+        if path.is_empty() {
+            return String::new();
+        }
+
+        let cache = match self.cache_scopes.last() {
+            Some(cache) => cache,
+            None => return self.resolve_with_context(path),
+        };
+
+        if let Some(resolved_path) = lock(&cache.resolved_paths).get(path) {
+            return resolved_path.clone();
+        }
+        let resolved_path = self.resolve_with_context(path);
+        lock(&cache.resolved_paths).insert(path.to_string(), resolved_path.clone());
+        resolved_path
+    }
+
+    fn update_asset_info(
+        &self,
+        _identifier: &str,
+        _file_path: &str,
+        _file_version: &str,
+        _asset_info: &mut AssetInfo,
+    ) {
+    }
+
+    fn get_modification_timestamp(
+        &self,
+        _path: &str,
+        resolved_path: &str,
+    ) -> Result<i64, ResolverError> {
+        get_modification_timestamp(resolved_path)
+    }
+
+    fn fetch_to_local_resolved_path(&self, _path: &str, _resolved_path: &str) -> bool {
+        true
+    }
+
+    fn open_asset(&self, resolved_path: &str) -> Result<Arc<dyn Asset>, ResolverError> {
+        if is_package_relative_path(resolved_path) {
+            return Err(ResolverError::OpenAssetError(resolved_path.to_string()));
+        }
+        open_filesystem_asset(resolved_path)
+    }
+
+    fn create_path_for_layer(&self, path: &str) -> Result<(), ResolverError> {
+        crate::resolver::default_resolution::create_path_for_layer(path)
+    }
+
+    fn can_write_layer_to_path(&self, path: &str) -> Result<(), ResolverError> {
+        if is_package_relative_path(path) {
+            return Err(ResolverError::CannotWriteLayerToPath(
+                path.to_string(),
+                "cannot write to a path inside a package".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn can_create_new_layer_with_identifier(&self, identifier: &str) -> Result<(), ResolverError> {
+        if is_package_relative_path(identifier) {
+            return Err(ResolverError::CannotCreateNewLayerWithIdentifier(
+                identifier.to_string(),
+                "cannot create a layer inside a package".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn begin_cache_scope(&mut self, cache_scope_data: &mut CacheScopeData) {
+        // Share the cache of the scope that populated `cache_scope_data`, if
+        // any, so all threads using it see the same results. Nested scopes
+        // otherwise share the cache of the enclosing scope.
+        let cache = cache_scope_data
+            .clone()
+            .and_then(|data| data.downcast::<ScopeCache>().ok())
+            .or_else(|| self.cache_scopes.last().cloned())
+            .unwrap_or_default();
+        *cache_scope_data = Some(cache.clone());
+        self.cache_scopes.push(cache);
+    }
+
+    fn end_cache_scope(&mut self, _cache_scope_data: &mut CacheScopeData) {
+        self.cache_scopes.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths() {
+        let resolver = DefaultResolver::new();
+        assert_eq!(resolver.anchor_relative_path("", "./foo.usd"), "./foo.usd");
+        assert_eq!(
+            resolver.anchor_relative_path("/dir/pkg.usdz[sub/layer.usd]", "./foo.usd"),
+            "/dir/pkg.usdz[sub/foo.usd]"
+        );
+        assert_eq!(
+            resolver.anchor_relative_path("/dir/layer.usd", "/abs/foo.usd"),
+            "/abs/foo.usd"
+        );
+
+        assert!(resolver.is_relative_path("foo.usd"));
+        assert!(!resolver.is_relative_path("/foo.usd"));
+        assert!(!resolver.is_relative_path(""));
+        assert!(resolver.is_search_path("props/foo.usd"));
+        assert!(!resolver.is_search_path("./props/foo.usd"));
+
+        assert_eq!(resolver.get_extension("/dir/foo.usdz[bar.usdc]"), "usdc");
+        assert_eq!(
+            resolver.compute_normalized_path("/dir//a/../foo.usd"),
+            "/dir/foo.usd"
+        );
+        assert_eq!(resolver.compute_normalized_path(""), "");
+        assert_eq!(resolver.compute_local_path("/dir/foo.usd"), "/dir/foo.usd");
+        assert_eq!(resolver.compute_local_path(""), "");
+
+        assert!(resolver.can_write_layer_to_path("/dir/foo.usd").is_ok());
+        assert!(resolver
+            .can_write_layer_to_path("/dir/foo.usdz[a.usd]")
+            .is_err());
+        assert!(resolver
+            .can_create_new_layer_with_identifier("/dir/foo.usdz[a.usd]")
+            .is_err());
+    }
+
+    #[test]
+    fn test_contexts() {
+        let mut resolver = DefaultResolver::new();
+        assert!(resolver.get_current_context().is_empty());
+        assert!(resolver.create_default_context().is_empty());
+
+        let context = resolver.create_default_context_for_asset("/dir/layer.usd");
+        assert_eq!(
+            context.get::<DefaultResolverContext>(),
+            Some(&DefaultResolverContext::new(&["/dir/"]))
+        );
+
+        resolver.bind_context(&context, &());
+        assert_eq!(resolver.get_current_context(), context);
+        resolver.unbind_context(&context, &());
+        assert!(resolver.get_current_context().is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_resolve() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().to_str().unwrap();
+        std::fs::create_dir(dir.path().join("props")).unwrap();
+        std::fs::write(dir.path().join("props/chair.usd"), b"#usda 1.0").unwrap();
+
+        let mut resolver = DefaultResolver::new();
+        let file_path = std::format!("{}/props/chair.usd", dir_path);
+        assert_eq!(resolver.resolve(&file_path), file_path);
+        assert_eq!(resolver.resolve("props/chair.usd"), "");
+
+        let context = ResolverContext::new(DefaultResolverContext::new(&[dir_path]));
+        resolver.bind_context(&context, &());
+        assert_eq!(resolver.resolve("props/chair.usd"), file_path);
+        assert!(resolver.get_modification_timestamp("", &file_path).is_ok());
+        assert_eq!(
+            resolver
+                .open_asset(&file_path)
+                .unwrap()
+                .get_buffer()
+                .unwrap(),
+            b"#usda 1.0"
+        );
+        resolver.unbind_context(&context, &());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_cache_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = std::format!("{}/a.usd", dir.path().to_str().unwrap());
+
+        let mut resolver = DefaultResolver::new();
+        let mut cache_scope_data = None;
+        resolver.begin_cache_scope(&mut cache_scope_data);
+        assert_eq!(resolver.resolve(&file_path), "");
+
+        // Results are cached until the scope ends.
+        std::fs::write(&file_path, b"").unwrap();
+        assert_eq!(resolver.resolve(&file_path), "");
+
+        // Scopes opened with the same data share the cache.
+        let mut other_resolver = DefaultResolver::new();
+        let mut shared_data = cache_scope_data.clone();
+        other_resolver.begin_cache_scope(&mut shared_data);
+        assert_eq!(other_resolver.resolve(&file_path), "");
+        other_resolver.end_cache_scope(&mut shared_data);
+
+        resolver.end_cache_scope(&mut cache_scope_data);
+        assert_eq!(resolver.resolve(&file_path), file_path);
+    }
+}
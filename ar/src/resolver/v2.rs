@@ -1,26 +1,80 @@
+//! The Ar 2.0 asset resolution API.
+//!
+//! This module is available with the `resolver_v2` feature, and its contents
+//! are also exposed at the crate root.
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
 use core::any::Any;
 
-use crate::{Asset, AssetInfo, ResolvedPath, ResolverContext, ResolverError, WritableAsset};
-
+use crate::package_utils::find_innermost_packaged_path;
+use crate::path_utils;
+use crate::{Asset, AssetInfo, ResolvedPath, ResolverError, WritableAsset};
+
+pub use super::CacheScopeData;
+pub use crate::resolver_context::resolver_context_v2::ResolverContext;
+pub use crate::WriteMode;
+
+#[cfg(feature = "cas")]
+pub use cas_resolver::*;
+pub use default_resolver::*;
+pub use discovery::*;
+#[cfg(feature = "http")]
+pub use http_resolver::*;
+pub use in_memory_resolver::*;
+pub use package_resolver::*;
+pub use recording_resolver::*;
+pub use resolve_cache::*;
+pub use resolver_context_binder::*;
+pub use resolver_scoped_cache::*;
+#[cfg(feature = "s3")]
+pub use s3_resolver::*;
+#[cfg(feature = "sqlite")]
+pub use sqlite_resolver::*;
+#[cfg(feature = "resolver_v1")]
+pub use v1_resolver_adapter::*;
+pub use versioned_resolver::*;
+pub use wrapper::*;
+pub use zip_package_resolver::*;
+
+#[cfg(feature = "cas")]
+mod cas_resolver;
+mod context_stack;
+mod default_resolver;
+mod discovery;
+#[cfg(feature = "http")]
+mod http_resolver;
+#[cfg(any(feature = "http", feature = "s3"))]
+mod http_utils;
+mod in_memory_resolver;
+mod package_resolver;
+mod recording_resolver;
+mod resolve_cache;
+mod resolver_context_binder;
+mod resolver_scoped_cache;
+#[cfg(feature = "s3")]
+mod s3_resolver;
+#[cfg(feature = "sqlite")]
+mod sqlite_resolver;
+mod thread_local_stack;
+#[cfg(feature = "resolver_v1")]
+mod v1_resolver_adapter;
+mod versioned_resolver;
 mod wrapper;
-
-/// Enumeration of write modes for open_asset_for_write
-#[derive(Clone, Debug)]
-pub enum WriteMode {
-    /// Open asset for in-place updates. If the asset exists, its contents
-    /// will not be discarded and writes may overwrite existing data.
-    /// Otherwise, the asset will be created.
-    Update = 0,
-
-    /// Open asset for replacement. If the asset exists, its contents will
-    /// be discarded by the time the ArWritableAsset is destroyed.
-    /// Otherwise, the asset will be created.
-    Replace,
-}
+mod zip_package_resolver;
 
 /// Trait for the asset resolution system. An asset resolver is
 /// responsible for resolving asset information (including the asset's
 /// physical path) from a logical path.
+///
+/// Only identifier creation, resolution and asset access must be
+/// implemented; every other method has a default implementation.
+///
+/// All methods take `&self` so a single resolver can be shared between
+/// threads, e.g. as an `Arc<dyn Resolver + Send + Sync>`. Implementations
+/// that keep mutable state, such as bound contexts or caches, are expected
+/// to use interior mutability.
 pub trait Resolver {
     /// Identifiers are canonicalized asset paths that may be assigned
     /// to a logical asset to facilitate comparisons and lookups. They
@@ -41,8 +95,11 @@ pub trait Resolver {
     /// Returns an identifier for the asset specified by `asset_path`.
     /// If `anchor_asset_path` is not [`None`], it is the resolved asset path
     /// that `asset_path` should be anchored to if it is a relative path.
-    fn create_identifier(&self, asset_path: &str, anchor_asset_path: Option<&ResolvedPath>)
-        -> &str;
+    fn create_identifier(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: Option<&ResolvedPath>,
+    ) -> String;
 
     /// Returns an identifier for a new asset specified by `asset_path`.
     /// If `anchor_asset_path` is not empty, it is the resolved asset path
@@ -51,7 +108,7 @@ pub trait Resolver {
         &self,
         asset_path: &str,
         anchor_asset_path: &ResolvedPath,
-    ) -> &str;
+    ) -> String;
 
     /// Returns the resolved path for the asset identified by the given
     /// `asset_path` if it exists. If the asset does not exist, returns an empty
@@ -72,7 +129,7 @@ pub trait Resolver {
     /// this function directly.
     ///
     /// see [`ResolverContextBinder`]
-    fn bind_context(&mut self, context: &ResolverContext, binding_data: &dyn Any);
+    fn bind_context(&self, _context: &ResolverContext, _binding_data: &dyn Any) {}
 
     /// Unbind the given context from this resolver.
     ///
@@ -80,20 +137,32 @@ pub trait Resolver {
     /// this function directly.
     ///
     /// see [`ResolverContextBinder`]
-    fn unbind_context(&mut self, context: &ResolverContext, binding_data: &dyn Any);
+    fn unbind_context(&self, _context: &ResolverContext, _binding_data: &dyn Any) {}
 
     /// Return a [`ResolverContext`] that may be bound to this resolver
     /// to resolve assets when no other context is explicitly specified.
-    fn create_default_context(&self) -> ResolverContext;
+    ///
+    /// The default implementation returns an empty [`ResolverContext`].
+    fn create_default_context(&self) -> ResolverContext {
+        ResolverContext::new()
+    }
 
     /// Return a [`ResolverContext`] that may be bound to this resolver
     /// to resolve the asset located at `asset_path` when no other context is
     /// explicitly specified.
-    fn create_default_context_for_asset(&self, asset_path: &str) -> ResolverContext;
+    ///
+    /// The default implementation returns an empty [`ResolverContext`].
+    fn create_default_context_for_asset(&self, _asset_path: &str) -> ResolverContext {
+        ResolverContext::new()
+    }
 
     /// Return a [`ResolverContext`] created from the primary [`Resolver`]
     /// implementation using the given `context_str`.
-    fn create_context_from_string(&self, context_str: &str) -> ResolverContext;
+    ///
+    /// The default implementation returns an empty [`ResolverContext`].
+    fn create_context_from_string(&self, _context_str: &str) -> ResolverContext {
+        ResolverContext::new()
+    }
 
     /// Return a [`ResolverContext`] created from the [`Resolver`] registered
     /// for the given `uri_scheme` using the given `context_str`.
@@ -107,7 +176,13 @@ pub trait Resolver {
         &self,
         uri_scheme: &str,
         context_str: &str,
-    ) -> ResolverContext;
+    ) -> ResolverContext {
+        if uri_scheme.is_empty() {
+            self.create_context_from_string(context_str)
+        } else {
+            ResolverContext::new()
+        }
+    }
 
     // Return a [`ResolverContext`] created by combining the [`ResolverContext`]
     /// objects created from the given `context_strings`.
@@ -131,15 +206,25 @@ pub trait Resolver {
     ///
     /// If no resolver is registered for a URI scheme in an entry in
     /// `context_strings`, that entry will be ignored.
-    fn create_context_from_strings(&self, context_strings: &[(&str, &str)]) -> ResolverContext;
+    fn create_context_from_strings(&self, context_strings: &[(&str, &str)]) -> ResolverContext {
+        let mut context = ResolverContext::new();
+        for (uri_scheme, context_str) in context_strings {
+            context += self.create_context_from_uri_and_string(uri_scheme, context_str);
+        }
+        context
+    }
 
     /// Refresh any caches associated with the given context.
-    fn refresh_context(&mut self, context: &ResolverContext);
+    fn refresh_context(&self, _context: &ResolverContext) {}
 
     /// Returns the asset resolver context currently bound in this thread.
     ///
+    /// The default implementation returns an empty [`ResolverContext`].
+    ///
     /// see [`Self::bind_context()`], [`Self::unbind_context()`]
-    fn get_current_context(&self) -> &ResolverContext;
+    fn get_current_context(&self) -> ResolverContext {
+        ResolverContext::new()
+    }
 
     /// Returns true if `asset_path` is a context-dependent path, false
     /// otherwise.
@@ -150,16 +235,34 @@ pub trait Resolver {
     /// be the same since those assets may have been loaded from different
     /// resolved paths. In this case, the assets' resolved paths must be
     /// consulted to determine if they are the same.
-    fn is_context_dependent_path(&self, asset_path: &str) -> bool;
+    ///
+    /// The default implementation returns false.
+    fn is_context_dependent_path(&self, _asset_path: &str) -> bool {
+        false
+    }
 
     /// Returns the file extension for the given `asset_path`. The returned
     /// extension does not include a "." at the beginning.
-    fn get_extension(&self, asset_path: &str) -> &str;
+    ///
+    /// The default implementation returns the extension of the innermost
+    /// packaged path for package-relative paths, and of `asset_path` itself
+    /// otherwise.
+    fn get_extension(&self, asset_path: &str) -> String {
+        let path = match find_innermost_packaged_path(asset_path) {
+            Some((begin, end)) => &asset_path[begin..end],
+            None => asset_path,
+        };
+        path_utils::get_extension(path).to_string()
+    }
 
     /// Returns a [`AssetInfo`] populated with additional metadata (if any)
     /// about the asset at the given `asset_path`. `resolved_path` is the
     /// resolved path computed for the given `asset_path`.
-    fn get_asset_info(&self, asset_path: &str, resolved_path: &ResolvedPath) -> &AssetInfo;
+    ///
+    /// The default implementation returns an empty [`AssetInfo`].
+    fn get_asset_info(&self, _asset_path: &str, _resolved_path: &ResolvedPath) -> AssetInfo {
+        AssetInfo::default()
+    }
 
     /// Return a value representing the last time the asset at the given
     /// `asset_path` was modified. `resolved_path` is the resolved path
@@ -168,18 +271,23 @@ pub trait Resolver {
     ///
     /// This timestamp may be equality compared to determine if an asset
     /// has been modified.
+    ///
+    /// The default implementation returns [`ResolverError::AssetMtimeError`].
     fn get_modification_timestamp(
         &self,
-        asset_path: &str,
-        resolved_path: &str,
-    ) -> Result<i64, ResolverError>;
+        _asset_path: &str,
+        _resolved_path: &ResolvedPath,
+    ) -> Result<i64, ResolverError> {
+        Err(ResolverError::AssetMtimeError)
+    }
 
     /// Returns an [`Asset`] object for the asset located at `resolved_path`.
     /// Returns an error if object could not be created.
     ///
     /// The returned [`Asset`] object provides functions for accessing the
-    /// contents of the specified asset.
-    fn open_asset(&self, resolved_path: &ResolvedPath) -> Result<&dyn Asset, ResolverError>;
+    /// contents of the specified asset. It is shared, so clients may hold on
+    /// to it or hand it to other threads for as long as they need the data.
+    fn open_asset(&self, resolved_path: &ResolvedPath) -> Result<Arc<dyn Asset>, ResolverError>;
 
     /// Returns an [`WritableAsset`] object for the asset located at
     /// `resolved_path` using the specified `write_mode`. Returns a [`ResolverError`]
@@ -196,7 +304,7 @@ pub trait Resolver {
         &self,
         resolved_path: &ResolvedPath,
         write_mode: WriteMode,
-    ) -> dyn WritableAsset;
+    ) -> Result<Box<dyn WritableAsset>, ResolverError>;
 
     /// Mark the start of a resolution caching scope.
     ///
@@ -207,26 +315,29 @@ pub trait Resolver {
     /// also pass in a `cache_scope_data` populated by an earlier call to
     /// [`Resolver::begin_cache_scope()`] to allow the resolver access to that information.
     ///
+    /// Resolvers that want to cache the results of [`Self::resolve()`] can use
+    /// a [`ResolveCache`] to implement this.
+    ///
     /// see [`ResolverScopedCache`]
-    fn begin_cache_scope(&mut self, cache_scope_data: Option<&dyn Any>);
+    fn begin_cache_scope(&self, _cache_scope_data: &mut CacheScopeData) {}
 
     /// Mark the end of a resolution caching scope.
     ///
     /// Clients should generally use [`ResolverScopedCache`] instead of calling
     /// this function directly.
     ///
-    /// \p cacheScopeData should contain the data that was populated by the
-    /// previous corresponding call to BeginCacheScope.
+    /// `cache_scope_data` should contain the data that was populated by the
+    /// previous corresponding call to [`Resolver::begin_cache_scope()`].
     ///
     /// see [`ResolverScopedCache`]
-    fn end_cache_scope(&mut self, cache_scope_data: Option<&dyn Any>);
+    fn end_cache_scope(&self, _cache_scope_data: &mut CacheScopeData) {}
 
     /// Configures the resolver for a given asset path
     /// Default implementation does nothing.
     ///
     /// deprecated
     #[deprecated(since = "2.0", note = "Default implementation does nothing")]
-    fn configure_resolver_for_asset(&mut self, _path: &str) {}
+    fn configure_resolver_for_asset(&self, _path: &str) {}
 
     /// Returns the path formed by anchoring `path` to `anchor_path`.
     ///
@@ -240,15 +351,25 @@ pub trait Resolver {
     /// If `path` is empty or not a relative path, it will be
     /// returned as-is.
     ///
+    /// The default implementation calls [`Self::create_identifier()`] with
+    /// `anchor_path` as the anchor.
+    ///
     /// deprecated Planned for removal in favor of [`Self::create_identifier()`].
     #[deprecated(since = "2.0", note = "Please use create_identifier() instead")]
-    fn anchor_relative_path(&self, anchor_path: &str, path: &str) -> &str;
+    fn anchor_relative_path(&self, anchor_path: &str, path: &str) -> String {
+        if anchor_path.is_empty() {
+            return path.to_string();
+        }
+        self.create_identifier(path, Some(&ResolvedPath::new(anchor_path)))
+    }
 
     /// Returns true if the given path is a relative path.
     ///
     /// deprecated
     #[deprecated(since = "2.0")]
-    fn is_relative_path(&self, path: &str) -> bool;
+    fn is_relative_path(&self, path: &str) -> bool {
+        !path.is_empty() && path_utils::is_relative_path(path)
+    }
 
     /// Returns whether this path is a search path.
     /// The default implementation returns false.
@@ -260,10 +381,13 @@ pub trait Resolver {
     }
 
     /// Returns true if the given path is a repository path.
+    /// The default implementation returns false.
     ///
     /// deprecated
     #[deprecated(since = "2.0")]
-    fn is_repository_path(&self, path: &str) -> bool;
+    fn is_repository_path(&self, _path: &str) -> bool {
+        false
+    }
 
     /// Fetch the asset identified by `path` to the filesystem location
     /// specified by `resolved_path`. `resolved_path` is the resolved path
@@ -302,12 +426,15 @@ pub trait Resolver {
     /// will be called first to check if writing is permitted. If this returns true,
     /// then [`Self::create_path_for_layer()`] will be called before writing the layer out.
     ///
-    /// Returns [`ResolverError`] on error.
+    /// Returns [`ResolverError`] on error. The default implementation does
+    /// nothing and returns [`Result::Ok`].
     ///
     /// deprecated Planned for removal in favor of making [`Self::open_asset_for_write()`]
     /// responsible for creating any intermediate path that might be needed.
     #[deprecated(since = "2.0", note = "Please use open_asset_for_write() instead")]
-    fn create_path_for_layer(&self, path: &str) -> Result<(), ResolverError>;
+    fn create_path_for_layer(&self, _path: &str) -> Result<(), ResolverError> {
+        Ok(())
+    }
 
     /// Returns [`Result::Ok`] if a file may be written to the given `path`,
     /// [`ResolverError`] otherwise.
@@ -0,0 +1,434 @@
+extern crate std;
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use core::fmt;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use super::{Resolver, WriteMode};
+use crate::filesystem::create_temporary_file;
+use crate::filesystem_writable_asset::write_file_at;
+use crate::package_utils::split_package_path;
+use crate::resolver::default_resolution::{anchor_to, open_filesystem_asset};
+use crate::{
+    is_package_relative_path, Asset, AssetError, ResolvedPath, ResolverError, WritableAsset,
+    WritableAssetError,
+};
+
+/// URI schemes served by [`CasResolver`], to register it with
+/// [`crate::ResolverWrapper::register_uri_resolver`].
+pub const CAS_URI_SCHEMES: &[&str] = &["cas"];
+
+/// Prefix of the identifiers of SHA-256 objects, followed by their digest.
+const SHA256_PREFIX: &str = "cas:sha256:";
+
+/// Asset path of objects whose digest is not known yet, to write new objects
+/// through [`Resolver::open_asset_for_write`].
+///
+/// This path never resolves to an object, and the [`WritableAsset`] handed
+/// out through the [`Resolver`] trait cannot report the identifier its
+/// content is published under. Use [`CasResolver::open_object_for_write`] or
+/// [`CasResolver::insert`] to learn it, or write through the trait to the
+/// identifier of the expected content, which resolves once it is written.
+pub const CAS_NEW_OBJECT: &str = "cas:sha256";
+
+/// Returns the lowercase hexadecimal digest of the `cas:` identifier
+/// `identifier`, or [`None`] if it isn't the identifier of a SHA-256 object.
+fn parse_identifier(identifier: &str) -> Option<String> {
+    let digest = identifier.strip_prefix(SHA256_PREFIX)?;
+    if digest.len() != 64 || !digest.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(digest.to_ascii_lowercase())
+}
+
+/// Returns the SHA-256 digest of the file at `path`.
+fn hash_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// [`Resolver`] implementation for immutable, deduplicated objects stored
+/// in a local object directory under their content's digest, to register
+/// with [`crate::ResolverWrapper::register_uri_resolver`] for
+/// [`CAS_URI_SCHEMES`]. Available with the `cas` feature.
+///
+/// Identifiers are SHA-256 digests in hexadecimal prefixed with
+/// `cas:sha256:`. Objects are stored in `sha256/<first two digits>/<digest>`
+/// under the object directory, and an identifier resolves to itself if its
+/// object exists. Since identifiers don't have directories, relative asset
+/// paths are only anchored to package-relative paths, inside their package.
+///
+/// New objects are written through [`Resolver::open_asset_for_write`] for
+/// [`CAS_NEW_OBJECT`], or for the identifier of the expected content. Written
+/// content is hashed as it is streamed to a temporary file, and published
+/// under its digest when the asset is closed, unless an object with the same
+/// content already exists. [`CasWritableAsset::get_identifier`] returns the
+/// identifier it was published under, which is lost for objects written to
+/// [`CAS_NEW_OBJECT`] through the [`Resolver`] trait. Closing fails if the
+/// content doesn't match the expected digest.
+///
+/// Opened objects are read in full and hashed, and are only returned if their
+/// content matches their digest. Otherwise, opening them fails with an
+/// [`AssetError::DigestMismatch`].
+///
+/// Objects never change, so their modification timestamp is derived from
+/// their digest.
+///
+/// # Examples
+/// ```
+/// use ar::{CasResolver, Resolver};
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let resolver = CasResolver::new(dir.path());
+/// let identifier = resolver.insert(b"#usda 1.0").unwrap();
+/// assert!(identifier.starts_with("cas:sha256:"));
+///
+/// let asset = resolver.open_asset(&resolver.resolve(&identifier)).unwrap();
+/// assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+/// ```
+#[derive(Debug)]
+pub struct CasResolver {
+    root: PathBuf,
+}
+
+impl CasResolver {
+    /// Creates a resolver for the objects stored in `root`, which is created
+    /// when the first object is written.
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Returns the object directory.
+    pub fn get_root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the path of the object with the given digest.
+    fn get_object_path(&self, digest: &str) -> PathBuf {
+        self.root.join("sha256").join(&digest[..2]).join(digest)
+    }
+
+    /// Opens a new object for writing at `resolved_path`, which is either
+    /// [`CAS_NEW_OBJECT`] or the identifier of the expected content.
+    pub fn open_object_for_write(
+        &self,
+        resolved_path: &ResolvedPath,
+    ) -> Result<CasWritableAsset, ResolverError> {
+        let path = resolved_path.get_path_string();
+        let expected_digest = match parse_identifier(path) {
+            Some(digest) => Some(digest),
+            None if path == CAS_NEW_OBJECT => None,
+            None => {
+                return Err(ResolverError::OpenAssetError(format!(
+                    "{}: not a content-addressed object",
+                    path
+                )))
+            }
+        };
+
+        let temporary_dir = self.root.join("tmp");
+        let (file, temporary_path) = fs::create_dir_all(&temporary_dir)
+            .and_then(|_| create_temporary_file(&temporary_dir, ""))
+            .map_err(|err| ResolverError::OpenAssetError(format!("{}: {}", path, err)))?;
+        Ok(CasWritableAsset {
+            object_dir: self.root.join("sha256"),
+            expected_digest,
+            file: Some(file),
+            temporary_path,
+            size: 0,
+            hasher: Some(Sha256::new()),
+            identifier: None,
+        })
+    }
+
+    /// Stores `data` as an object and returns its identifier.
+    pub fn insert(&self, data: &[u8]) -> Result<String, WritableAssetError> {
+        let mut asset = self
+            .open_object_for_write(&ResolvedPath::new(CAS_NEW_OBJECT))
+            .map_err(|err| WritableAssetError::WriteFailed(err.to_string()))?;
+        asset.write(data, data.len(), 0)?;
+        asset.close()?;
+        Ok(asset.identifier.take().unwrap_or_default())
+    }
+}
+
+impl Resolver for CasResolver {
+    fn create_identifier(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: Option<&ResolvedPath>,
+    ) -> String {
+        let (object_path, packaged_path) = split_package_path(asset_path);
+        if let Some(digest) = parse_identifier(object_path) {
+            return format!("{}{}{}", SHA256_PREFIX, digest, packaged_path);
+        }
+        match anchor_asset_path {
+            Some(anchor) if is_package_relative_path(anchor.get_path_string()) => {
+                anchor_to(anchor.get_path_string(), asset_path)
+            }
+            _ => asset_path.to_owned(),
+        }
+    }
+
+    fn create_identifier_for_new_asset(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: &ResolvedPath,
+    ) -> String {
+        self.create_identifier(asset_path, Some(anchor_asset_path))
+    }
+
+    fn resolve(&self, asset_path: &str) -> ResolvedPath {
+        // Assets inside a package exist if the package does.
+        let (object_path, packaged_path) = split_package_path(asset_path);
+        match parse_identifier(object_path) {
+            Some(digest) if self.get_object_path(&digest).is_file() => {
+                ResolvedPath::new(&format!("{}{}{}", SHA256_PREFIX, digest, packaged_path))
+            }
+            _ => ResolvedPath::default(),
+        }
+    }
+
+    fn resolve_for_new_asset(&self, asset_path: &str) -> ResolvedPath {
+        if asset_path == CAS_NEW_OBJECT {
+            return ResolvedPath::new(asset_path);
+        }
+        match parse_identifier(asset_path) {
+            Some(digest) => ResolvedPath::new(&format!("{}{}", SHA256_PREFIX, digest)),
+            None => ResolvedPath::default(),
+        }
+    }
+
+    fn get_modification_timestamp(
+        &self,
+        _asset_path: &str,
+        resolved_path: &ResolvedPath,
+    ) -> Result<i64, ResolverError> {
+        let (object_path, _) = split_package_path(resolved_path.get_path_string());
+        match parse_identifier(object_path) {
+            Some(digest) if self.get_object_path(&digest).is_file() => {
+                u64::from_str_radix(&digest[..16], 16)
+                    .map(|timestamp| timestamp as i64)
+                    .map_err(|_| ResolverError::AssetMtimeError)
+            }
+            _ => Err(ResolverError::AssetMtimeError),
+        }
+    }
+
+    fn open_asset(&self, resolved_path: &ResolvedPath) -> Result<Arc<dyn Asset>, ResolverError> {
+        let identifier = resolved_path.get_path_string();
+        let digest = parse_identifier(identifier).ok_or_else(|| {
+            ResolverError::OpenAssetError(format!("{}: not a content-addressed object", identifier))
+        })?;
+
+        let asset = open_filesystem_asset(&self.get_object_path(&digest).to_string_lossy())?;
+        let buffer = asset
+            .get_buffer()
+            .map_err(|err| ResolverError::InvalidAsset(identifier.to_owned(), err))?;
+        let actual_digest = format!("{:x}", Sha256::digest(buffer));
+        if actual_digest != digest {
+            return Err(ResolverError::InvalidAsset(
+                identifier.to_owned(),
+                AssetError::DigestMismatch(digest, actual_digest),
+            ));
+        }
+        Ok(asset)
+    }
+
+    fn open_asset_for_write(
+        &self,
+        resolved_path: &ResolvedPath,
+        _write_mode: WriteMode,
+    ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+        // Objects never change, so there is nothing to update.
+        Ok(Box::new(self.open_object_for_write(resolved_path)?))
+    }
+}
+
+/// [`WritableAsset`] implementation for a new object of a [`CasResolver`].
+///
+/// Writes go to a temporary file, and are hashed as they are written as
+/// long as they are sequential. Otherwise, the temporary file is hashed when
+/// the asset is closed. Closing the asset publishes the object under its
+/// digest, and assets dropped without being closed are discarded.
+pub struct CasWritableAsset {
+    object_dir: PathBuf,
+    expected_digest: Option<String>,
+    file: Option<File>,
+    temporary_path: PathBuf,
+    /// Size of the content written so far.
+    size: usize,
+    /// Hash of the content written so far, as long as it was written
+    /// sequentially.
+    hasher: Option<Sha256>,
+    identifier: Option<String>,
+}
+
+impl CasWritableAsset {
+    /// Returns the identifier of the object, once the asset was closed.
+    pub fn get_identifier(&self) -> Option<&str> {
+        self.identifier.as_deref()
+    }
+
+    /// Hashes the written content and moves it to its object path, unless
+    /// that object already exists. Returns the digest.
+    fn publish(&mut self, file: File) -> Result<String, String> {
+        file.sync_all().map_err(|err| err.to_string())?;
+        drop(file);
+
+        let digest = match self.hasher.take() {
+            Some(hasher) => format!("{:x}", hasher.finalize()),
+            None => hash_file(&self.temporary_path).map_err(|err| err.to_string())?,
+        };
+        if let Some(expected_digest) = &self.expected_digest {
+            if *expected_digest != digest {
+                return Err(AssetError::DigestMismatch(expected_digest.clone(), digest).to_string());
+            }
+        }
+
+        let object_path = self.object_dir.join(&digest[..2]).join(&digest);
+        if object_path.is_file() {
+            // Objects with the same digest have the same content.
+            return Ok(digest);
+        }
+        let publish = |object_path: &Path| -> io::Result<()> {
+            fs::create_dir_all(self.object_dir.join(&digest[..2]))?;
+            let mut permissions = fs::metadata(&self.temporary_path)?.permissions();
+            permissions.set_readonly(true);
+            fs::set_permissions(&self.temporary_path, permissions)?;
+            fs::rename(&self.temporary_path, object_path)
+        };
+        publish(&object_path)
+            .map_err(|err| format!("could not publish {}: {}", object_path.display(), err))?;
+        Ok(digest)
+    }
+}
+
+impl WritableAsset for CasWritableAsset {
+    fn close(&mut self) -> Result<(), WritableAssetError> {
+        let file = self.file.take().ok_or_else(|| {
+            WritableAssetError::CloseFailed("asset is already closed".to_string())
+        })?;
+        let result = self.publish(file);
+        // The temporary file is gone once the object was published, unless
+        // it already existed.
+        let _ = fs::remove_file(&self.temporary_path);
+        let digest = result.map_err(WritableAssetError::CloseFailed)?;
+        self.identifier = Some(format!("{}{}", SHA256_PREFIX, digest));
+        Ok(())
+    }
+
+    fn write(
+        &mut self,
+        buffer: &[u8],
+        count: usize,
+        offset: usize,
+    ) -> Result<usize, WritableAssetError> {
+        let file = self
+            .file
+            .as_mut()
+            .ok_or_else(|| WritableAssetError::WriteFailed("asset is closed".to_string()))?;
+        let count = write_file_at(file, buffer, count, offset)?;
+
+        match &mut self.hasher {
+            Some(hasher) if offset == self.size => hasher.update(&buffer[..count]),
+            _ => self.hasher = None,
+        }
+        self.size = self.size.max(offset + count);
+        Ok(count)
+    }
+}
+
+impl Drop for CasWritableAsset {
+    fn drop(&mut self) {
+        // An asset that was never closed is discarded.
+        if let Some(file) = self.file.take() {
+            drop(file);
+            let _ = fs::remove_file(&self.temporary_path);
+        }
+    }
+}
+
+impl fmt::Debug for CasWritableAsset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CasWritableAsset")
+            .field("expected_digest", &self.expected_digest)
+            .field("size", &self.size)
+            .field("identifier", &self.identifier)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGEST: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    #[test]
+    fn test_parse_identifier() {
+        assert_eq!(
+            parse_identifier(&format!("cas:sha256:{}", DIGEST)).as_deref(),
+            Some(DIGEST)
+        );
+        assert_eq!(
+            parse_identifier(&format!("cas:sha256:{}", DIGEST.to_ascii_uppercase())).as_deref(),
+            Some(DIGEST)
+        );
+        assert_eq!(parse_identifier(&format!("cas:sha1:{}", DIGEST)), None);
+        assert_eq!(parse_identifier(&format!("cas:sha256:{}0", DIGEST)), None);
+        assert_eq!(parse_identifier("cas:sha256:xyz"), None);
+        assert_eq!(parse_identifier(CAS_NEW_OBJECT), None);
+    }
+
+    #[test]
+    fn test_create_identifier() {
+        let resolver = CasResolver::new("/nonexistent");
+        let identifier = format!("cas:sha256:{}", DIGEST);
+        assert_eq!(
+            resolver
+                .create_identifier(&format!("cas:sha256:{}", DIGEST.to_ascii_uppercase()), None),
+            identifier
+        );
+        assert_eq!(
+            resolver.create_identifier(
+                "./b.usda",
+                Some(&ResolvedPath::new(&format!("{}[a/a.usda]", identifier)))
+            ),
+            format!("{}[a/b.usda]", identifier)
+        );
+        assert_eq!(
+            resolver.create_identifier("./b.usda", Some(&ResolvedPath::new(&identifier))),
+            "./b.usda"
+        );
+    }
+
+    #[test]
+    fn test_non_sequential_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let resolver = CasResolver::new(dir.path());
+
+        let mut asset = resolver
+            .open_object_for_write(&ResolvedPath::new(CAS_NEW_OBJECT))
+            .unwrap();
+        asset.write(b"1.0", 3, 6).unwrap();
+        asset.write(b"#usda ", 6, 0).unwrap();
+        asset.close().unwrap();
+        assert_eq!(
+            asset.get_identifier().unwrap(),
+            resolver.insert(b"#usda 1.0").unwrap()
+        );
+        assert_eq!(fs::read_dir(dir.path().join("tmp")).unwrap().count(), 0);
+    }
+}
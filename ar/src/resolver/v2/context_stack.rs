@@ -0,0 +1,35 @@
+use super::thread_local_stack::ThreadLocalStack;
+use crate::ResolverContext;
+
+/// Stack of the contexts bound to a resolver, kept separately for each
+/// thread so threads can resolve under different contexts at the same time.
+#[derive(Debug)]
+pub(crate) struct ContextStack {
+    stack: ThreadLocalStack<ResolverContext>,
+}
+
+impl ContextStack {
+    pub(crate) fn new() -> Self {
+        Self {
+            stack: ThreadLocalStack::new(),
+        }
+    }
+
+    /// Pushes `context` onto the current thread's stack.
+    pub(crate) fn push(&self, context: &ResolverContext) {
+        self.stack.push(context.clone());
+    }
+
+    /// Pops the top of the current thread's stack, which is expected to be
+    /// `context`.
+    pub(crate) fn pop(&self, context: &ResolverContext) {
+        if self.stack.pop().as_ref() != Some(context) {
+            log::warn!("unbinding resolver context in unexpected order");
+        }
+    }
+
+    /// Returns the context on top of the current thread's stack.
+    pub(crate) fn top(&self) -> Option<ResolverContext> {
+        self.stack.top()
+    }
+}
@@ -0,0 +1,427 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::any::Any;
+
+use super::context_stack::ContextStack;
+use super::{CacheScopeData, ResolveCache};
+use crate::filesystem;
+use crate::package_utils::{is_package_relative_path, split_package_path};
+use crate::path_utils::{get_path_name, is_relative_path};
+use crate::resolver::default_resolution::{
+    anchor_to, compute_local_path, get_default_search_path, get_modification_timestamp,
+    is_search_path, norm_asset_path, open_filesystem_asset, open_filesystem_asset_for_write,
+    resolve_in_search_path,
+};
+use crate::{
+    Asset, DefaultResolverContext, ResolvedPath, Resolver, ResolverContext, ResolverError,
+    WritableAsset, WriteMode,
+};
+
+/// Default asset resolution implementation used when no other resolver
+/// has been provided.
+///
+/// Asset paths are treated as filesystem paths. Relative paths are anchored
+/// to the directory of the anchoring asset when creating identifiers and to
+/// the current working directory when resolving. For package-relative paths
+/// like `/dir/foo.usdz[bar.usd]` only the outer package path is consulted on
+/// the filesystem; the packaged portion is carried through unchanged.
+///
+/// Search-path style asset paths like `props/chair.usd` (relative paths that
+/// do not start with `./` or `../`) are first looked up relative to the
+/// current working directory, then in each directory of the search path of
+/// the bound [`DefaultResolverContext`], and finally in the fallback search
+/// path. The fallback search path is made up of the paths given to
+/// [`DefaultResolver::set_default_search_path`] followed by the paths in the
+/// `PXR_AR_DEFAULT_SEARCH_PATH` environment variable, and is captured when
+/// the resolver is constructed.
+#[derive(Debug)]
+pub struct DefaultResolver {
+    context_stack: ContextStack,
+    resolve_cache: ResolveCache,
+    fallback_search_path: Vec<String>,
+}
+
+impl DefaultResolver {
+    /// Constructor
+    pub fn new() -> Self {
+        Self {
+            context_stack: ContextStack::new(),
+            resolve_cache: ResolveCache::new(),
+            fallback_search_path: get_default_search_path(),
+        }
+    }
+
+    /// Sets the default search path consulted by resolvers constructed after
+    /// this call when a search-path style asset path cannot be found using
+    /// the bound context. These paths are searched before the paths in the
+    /// `PXR_AR_DEFAULT_SEARCH_PATH` environment variable.
+    #[cfg(feature = "std")]
+    pub fn set_default_search_path(search_path: &[&str]) {
+        crate::resolver::default_resolution::set_default_search_path(search_path);
+    }
+
+    fn resolve_with_context(&self, asset_path: &str, context: &ResolverContext) -> ResolvedPath {
+        let search_path = context
+            .get::<DefaultResolverContext>()
+            .map(|context| context.get_search_path())
+            .unwrap_or_default();
+        resolve_in_search_path(asset_path, search_path, &self.fallback_search_path)
+    }
+}
+
+impl Default for DefaultResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver for DefaultResolver {
+    fn create_identifier(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: Option<&ResolvedPath>,
+    ) -> String {
+        if asset_path.is_empty() {
+            return asset_path.to_string();
+        }
+
+        let anchor_asset_path = match anchor_asset_path {
+            Some(anchor) if !anchor.is_empty() => anchor,
+            _ => return norm_asset_path(asset_path),
+        };
+
+        // Search paths are kept as-is unless the asset exists next to the
+        // anchoring asset, so they can be looked up through the search path
+        // when resolved.
+        let anchored_asset_path = anchor_to(anchor_asset_path.get_path_string(), asset_path);
+        if is_search_path(asset_path) && self.resolve(&anchored_asset_path).is_empty() {
+            return norm_asset_path(asset_path);
+        }
+
+        norm_asset_path(&anchored_asset_path)
+    }
+
+    fn create_identifier_for_new_asset(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: &ResolvedPath,
+    ) -> String {
+        if asset_path.is_empty() {
+            return asset_path.to_string();
+        }
+
+        if !is_relative_path(asset_path) {
+            return norm_asset_path(asset_path);
+        }
+
+        if anchor_asset_path.is_empty() {
+            return compute_local_path(asset_path);
+        }
+
+        norm_asset_path(&anchor_to(anchor_asset_path.get_path_string(), asset_path))
+    }
+
+    fn resolve(&self, asset_path: &str) -> ResolvedPath {
+        if asset_path.is_empty() {
+            return ResolvedPath::default();
+        }
+
+        let context = self.get_current_context();
+        self.resolve_cache.resolve(asset_path, &context, || {
+            self.resolve_with_context(asset_path, &context)
+        })
+    }
+
+    fn resolve_for_new_asset(&self, asset_path: &str) -> ResolvedPath {
+        ResolvedPath::new(&compute_local_path(asset_path))
+    }
+
+    fn bind_context(&self, context: &ResolverContext, _binding_data: &dyn Any) {
+        self.context_stack.push(context);
+    }
+
+    fn unbind_context(&self, context: &ResolverContext, _binding_data: &dyn Any) {
+        self.context_stack.pop(context);
+    }
+
+    fn create_default_context_for_asset(&self, asset_path: &str) -> ResolverContext {
+        let mut context = ResolverContext::new();
+        if !asset_path.is_empty() {
+            let (package_path, _) = split_package_path(asset_path);
+            let asset_dir = filesystem::abs_path(package_path);
+            context.push(DefaultResolverContext::new(&[get_path_name(&asset_dir)]));
+        }
+        context
+    }
+
+    fn create_context_from_string(&self, context_str: &str) -> ResolverContext {
+        let mut context = ResolverContext::new();
+        context.push(DefaultResolverContext::from_path_list(context_str));
+        context
+    }
+
+    fn get_current_context(&self) -> ResolverContext {
+        self.context_stack.top().unwrap_or_default()
+    }
+
+    fn is_context_dependent_path(&self, asset_path: &str) -> bool {
+        is_search_path(asset_path)
+    }
+
+    fn get_modification_timestamp(
+        &self,
+        _asset_path: &str,
+        resolved_path: &ResolvedPath,
+    ) -> Result<i64, ResolverError> {
+        get_modification_timestamp(resolved_path.get_path_string())
+    }
+
+    fn open_asset(&self, resolved_path: &ResolvedPath) -> Result<Arc<dyn Asset>, ResolverError> {
+        let path = resolved_path.get_path_string();
+        if is_package_relative_path(path) {
+            return Err(ResolverError::OpenAssetError(path.to_string()));
+        }
+        open_filesystem_asset(path)
+    }
+
+    fn open_asset_for_write(
+        &self,
+        resolved_path: &ResolvedPath,
+        write_mode: WriteMode,
+    ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+        let path = resolved_path.get_path_string();
+        if is_package_relative_path(path) {
+            return Err(ResolverError::OpenAssetError(path.to_string()));
+        }
+        open_filesystem_asset_for_write(path, write_mode)
+    }
+
+    fn begin_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        self.resolve_cache.begin_cache_scope(cache_scope_data);
+    }
+
+    fn end_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        self.resolve_cache.end_cache_scope(cache_scope_data);
+    }
+
+    fn anchor_relative_path(&self, anchor_path: &str, path: &str) -> String {
+        if anchor_path.is_empty() {
+            return path.to_string();
+        }
+        anchor_to(anchor_path, path)
+    }
+
+    fn is_search_path(&self, path: &str) -> bool {
+        is_search_path(path)
+    }
+
+    fn create_path_for_layer(&self, path: &str) -> Result<(), ResolverError> {
+        crate::resolver::default_resolution::create_path_for_layer(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::PATH_LIST_SEPARATOR;
+
+    #[test]
+    fn test_create_identifier() {
+        let resolver = DefaultResolver::new();
+        let anchor = ResolvedPath::new("/dir/layer.usd");
+
+        assert_eq!(resolver.create_identifier("", Some(&anchor)), "");
+        assert_eq!(
+            resolver.create_identifier("/abs//foo.usd", Some(&anchor)),
+            "/abs/foo.usd"
+        );
+        assert_eq!(
+            resolver.create_identifier("./foo.usd", Some(&anchor)),
+            "/dir/foo.usd"
+        );
+        assert_eq!(
+            resolver.create_identifier("../foo.usd", Some(&anchor)),
+            "/foo.usd"
+        );
+        assert_eq!(resolver.create_identifier("./foo.usd", None), "foo.usd");
+    }
+
+    #[test]
+    fn test_create_identifier_package_relative() {
+        let resolver = DefaultResolver::new();
+
+        let anchor = ResolvedPath::new("/dir/layer.usd");
+        assert_eq!(
+            resolver.create_identifier("./pkg.usdz[sub/../foo.usd]", Some(&anchor)),
+            "/dir/pkg.usdz[sub/../foo.usd]"
+        );
+
+        let anchor = ResolvedPath::new("/dir/pkg.usdz[sub/layer.usd]");
+        assert_eq!(
+            resolver.create_identifier("./foo.usd", Some(&anchor)),
+            "/dir/pkg.usdz[sub/foo.usd]"
+        );
+
+        let anchor = ResolvedPath::new("/dir/pkg.usdz[inner.usdz[sub/layer.usd]]");
+        assert_eq!(
+            resolver.create_identifier("../foo.usd", Some(&anchor)),
+            "/dir/pkg.usdz[inner.usdz[foo.usd]]"
+        );
+    }
+
+    #[test]
+    fn test_get_extension() {
+        let resolver = DefaultResolver::new();
+        assert_eq!(resolver.get_extension("/dir/foo.usda"), "usda");
+        assert_eq!(resolver.get_extension("/dir/foo"), "");
+        assert_eq!(resolver.get_extension("/dir/foo.usdz[bar.usdc]"), "usdc");
+        assert_eq!(
+            resolver.get_extension("/dir/foo.usdz[bar.usdz[baz.png]]"),
+            "png"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_resolve() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().to_str().unwrap();
+        let file_path = std::format!("{}/foo.usd", dir_path);
+        std::fs::write(&file_path, b"#usda 1.0").unwrap();
+
+        let resolver = DefaultResolver::new();
+        assert_eq!(resolver.resolve(&file_path).get_path_string(), file_path);
+        assert!(resolver
+            .resolve(&std::format!("{}/bar.usd", dir_path))
+            .is_empty());
+        assert_eq!(
+            resolver
+                .resolve(&std::format!("{}/foo.usd[bar.usd]", dir_path))
+                .get_path_string(),
+            std::format!("{}/foo.usd[bar.usd]", dir_path)
+        );
+        assert!(resolver.resolve("").is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_resolve_search_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().to_str().unwrap();
+        let search_dir_1 = std::format!("{}/search1", dir_path);
+        let search_dir_2 = std::format!("{}/search2", dir_path);
+        std::fs::create_dir_all(std::format!("{}/props", search_dir_1)).unwrap();
+        std::fs::create_dir_all(std::format!("{}/props", search_dir_2)).unwrap();
+        std::fs::write(std::format!("{}/props/chair.usd", search_dir_2), b"").unwrap();
+        std::fs::write(std::format!("{}/props/table.usd", search_dir_1), b"").unwrap();
+        std::fs::write(std::format!("{}/props/table.usd", search_dir_2), b"").unwrap();
+
+        let resolver = DefaultResolver::new();
+        assert!(resolver.is_context_dependent_path("props/chair.usd"));
+        assert!(!resolver.is_context_dependent_path("./props/chair.usd"));
+        assert!(resolver.resolve("props/chair.usd").is_empty());
+
+        let context = resolver.create_context_from_string(
+            &[search_dir_1.as_str(), search_dir_2.as_str()].join(&PATH_LIST_SEPARATOR.to_string()),
+        );
+        resolver.bind_context(&context, &());
+        assert_eq!(
+            resolver.resolve("props/chair.usd").get_path_string(),
+            std::format!("{}/props/chair.usd", search_dir_2)
+        );
+        assert_eq!(
+            resolver.resolve("props/table.usd").get_path_string(),
+            std::format!("{}/props/table.usd", search_dir_1)
+        );
+        assert!(resolver.resolve("./props/chair.usd").is_empty());
+        resolver.unbind_context(&context, &());
+
+        assert!(resolver.resolve("props/chair.usd").is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_create_identifier_search_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().to_str().unwrap();
+        std::fs::create_dir_all(std::format!("{}/props", dir_path)).unwrap();
+        std::fs::write(std::format!("{}/props/chair.usd", dir_path), b"").unwrap();
+
+        let resolver = DefaultResolver::new();
+        let anchor = ResolvedPath::new(&std::format!("{}/layer.usd", dir_path));
+        assert_eq!(
+            resolver.create_identifier("props/chair.usd", Some(&anchor)),
+            std::format!("{}/props/chair.usd", dir_path)
+        );
+        assert_eq!(
+            resolver.create_identifier("props/table.usd", Some(&anchor)),
+            "props/table.usd"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_create_default_context_for_asset() {
+        let resolver = DefaultResolver::new();
+        let context = resolver.create_default_context_for_asset("/dir/layer.usd");
+        let expected = DefaultResolverContext::new(&["/dir/"]);
+        assert_eq!(context.get::<DefaultResolverContext>(), Some(&expected));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_open_asset() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = std::format!("{}/foo.usd", dir.path().to_str().unwrap());
+
+        let resolver = DefaultResolver::new();
+        let resolved_path = resolver.resolve_for_new_asset(&file_path);
+        let mut writable_asset = resolver
+            .open_asset_for_write(&resolved_path, WriteMode::Replace)
+            .unwrap();
+        assert_eq!(writable_asset.write(b"#usda 1.0", 9, 0).unwrap(), 9);
+        writable_asset.close().unwrap();
+
+        let resolved_path = resolver.resolve(&file_path);
+        assert!(resolver
+            .get_modification_timestamp(&file_path, &resolved_path)
+            .is_ok());
+
+        let asset = resolver.open_asset(&resolved_path).unwrap();
+        assert_eq!(asset.get_size(), 9);
+        assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_resolve_cached_in_scope() {
+        use crate::ResolverScopedCache;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().to_str().unwrap();
+        let asset_path = std::format!("{}/a.usd", dir_path);
+        std::fs::write(&asset_path, b"").unwrap();
+
+        let resolver = DefaultResolver::new();
+        {
+            let _cache = ResolverScopedCache::with_resolver(&resolver, None);
+            assert!(resolver.resolve("a.usd").is_empty());
+            let context = resolver.create_context_from_string(dir_path);
+            resolver.bind_context(&context, &());
+            assert_eq!(resolver.resolve("a.usd").get_path_string(), asset_path);
+
+            // Results are served from the cache for the rest of the scope.
+            std::fs::remove_file(&asset_path).unwrap();
+            assert_eq!(resolver.resolve("a.usd").get_path_string(), asset_path);
+            resolver.unbind_context(&context, &());
+            assert!(resolver.resolve("a.usd").is_empty());
+            resolver.bind_context(&context, &());
+            assert_eq!(resolver.resolve("a.usd").get_path_string(), asset_path);
+            resolver.unbind_context(&context, &());
+        }
+        assert!(resolver.resolve(&asset_path).is_empty());
+    }
+}
@@ -0,0 +1,403 @@
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use usd_plugin::info::{PluginInfo, RegistrationMetadata};
+
+use super::{DefaultResolver, PackageResolver, Resolver, ResolverWrapper, ZipPackageResolver};
+use crate::sync::{lock, Mutex, OnceLock};
+
+/// Base type name that resolver types list under `bases` in their plugInfo
+/// metadata.
+pub const RESOLVER_BASE_TYPE_NAME: &str = "ArResolver";
+
+/// Type name of [`DefaultResolver`], which is always available.
+pub const DEFAULT_RESOLVER_TYPE_NAME: &str = "ArDefaultResolver";
+
+/// Base type name that package resolver types list under `bases` in their
+/// plugInfo metadata.
+pub const PACKAGE_RESOLVER_BASE_TYPE_NAME: &str = "ArPackageResolver";
+
+/// Function constructing a resolver, registered with [`define_resolver`].
+pub type ResolverFactory = fn() -> Box<dyn Resolver + Send + Sync>;
+
+/// Function constructing a package resolver, registered with
+/// [`define_package_resolver`].
+pub type PackageResolverFactory = fn() -> Box<dyn PackageResolver + Send + Sync>;
+
+static RESOLVER_FACTORIES: Mutex<BTreeMap<String, ResolverFactory>> = Mutex::new(BTreeMap::new());
+
+static PACKAGE_RESOLVER_FACTORIES: Mutex<BTreeMap<String, PackageResolverFactory>> =
+    Mutex::new(BTreeMap::new());
+
+/// Resolver types advertised by plugins, mapped to the URI schemes they
+/// handle. Types without URI schemes are candidates for the primary resolver.
+static PLUGIN_RESOLVER_TYPES: Mutex<BTreeMap<String, Vec<String>>> = Mutex::new(BTreeMap::new());
+
+/// Package resolver types advertised by plugins, mapped to the package
+/// extensions they handle.
+static PLUGIN_PACKAGE_RESOLVER_TYPES: Mutex<BTreeMap<String, Vec<String>>> =
+    Mutex::new(BTreeMap::new());
+
+static PREFERRED_RESOLVER: Mutex<Option<String>> = Mutex::new(None);
+
+static RESOLVER: OnceLock<ResolverWrapper> = OnceLock::new();
+
+/// Returns the types deriving from `base_type_name` declared in the `Types`
+/// dictionary of a plugin's `Info` metadata, along with the strings listed
+/// under `list_key` for each one.
+///
+/// ```json
+/// {
+///     "Types": {
+///         "StudioResolver": {
+///             "bases": ["ArResolver"],
+///             "uriSchemes": ["studio"]
+///         },
+///         "StudioPackageResolver": {
+///             "bases": ["ArPackageResolver"],
+///             "extensions": ["spkg"]
+///         }
+///     }
+/// }
+/// ```
+fn get_plugin_types(
+    metadata: &RegistrationMetadata,
+    base_type_name: &str,
+    list_key: &str,
+) -> Vec<(String, Vec<String>)> {
+    let types = match metadata
+        .info
+        .get("Types")
+        .and_then(|types| types.as_object())
+    {
+        Some(types) => types,
+        None => return Vec::new(),
+    };
+
+    types
+        .iter()
+        .filter(|(_, type_info)| {
+            type_info
+                .get("bases")
+                .and_then(|bases| bases.as_array())
+                .is_some_and(|bases| {
+                    bases
+                        .iter()
+                        .any(|base| base.as_str() == Some(base_type_name))
+                })
+        })
+        .map(|(type_name, type_info)| {
+            let values = type_info
+                .get(list_key)
+                .and_then(|values| values.as_array())
+                .map(|values| {
+                    values
+                        .iter()
+                        .filter_map(|value| value.as_str())
+                        .map(ToOwned::to_owned)
+                        .collect()
+                })
+                .unwrap_or_default();
+            (type_name.clone(), values)
+        })
+        .collect()
+}
+
+/// Makes the resolver type `type_name` constructible by [`create_resolver`]
+/// and [`get_resolver`]. This is typically called at startup by the crate
+/// providing the resolver.
+///
+/// Resolver types that are defined but not advertised by any plugin are
+/// treated as primary resolver candidates.
+pub fn define_resolver(type_name: &str, factory: ResolverFactory) {
+    lock(&RESOLVER_FACTORIES).insert(type_name.to_owned(), factory);
+}
+
+/// Makes the package resolver type `type_name` constructible by
+/// [`get_resolver`]. The package extensions it handles are taken from the
+/// plugin advertising it, see [`register_plugins`].
+pub fn define_package_resolver(type_name: &str, factory: PackageResolverFactory) {
+    lock(&PACKAGE_RESOLVER_FACTORIES).insert(type_name.to_owned(), factory);
+}
+
+/// Registers the resolver types advertised by the plugins in `plugin_info`.
+///
+/// A plugin advertises a resolver by listing [`RESOLVER_BASE_TYPE_NAME`] in
+/// the `bases` of a type in its `Info.Types` metadata. Types that also list
+/// `uriSchemes` are registered as URI resolvers for those schemes instead of
+/// being considered for the primary resolver.
+///
+/// Package resolvers are advertised the same way with
+/// [`PACKAGE_RESOLVER_BASE_TYPE_NAME`], listing the package extensions they
+/// handle under `extensions`.
+///
+/// Like [`set_preferred_resolver`], this only has an effect if called before
+/// the first call to [`get_resolver`].
+pub fn register_plugins(plugin_info: &PluginInfo) {
+    let mut plugin_resolver_types = lock(&PLUGIN_RESOLVER_TYPES);
+    let mut plugin_package_resolver_types = lock(&PLUGIN_PACKAGE_RESOLVER_TYPES);
+    for metadata in &plugin_info.plugins {
+        for (type_name, uri_schemes) in
+            get_plugin_types(metadata, RESOLVER_BASE_TYPE_NAME, "uriSchemes")
+        {
+            log::debug!("plugin {} provides resolver {}", metadata.name, type_name);
+            plugin_resolver_types.insert(type_name, uri_schemes);
+        }
+        for (type_name, extensions) in
+            get_plugin_types(metadata, PACKAGE_RESOLVER_BASE_TYPE_NAME, "extensions")
+        {
+            log::debug!(
+                "plugin {} provides package resolver {}",
+                metadata.name,
+                type_name
+            );
+            plugin_package_resolver_types.insert(type_name, extensions);
+        }
+    }
+}
+
+/// Sets the preferred resolver type used by [`get_resolver`].
+///
+/// Consumers may override [`get_resolver`]'s plugin resolver discovery and
+/// force the use of a specific resolver type by calling this function with
+/// the type name of the implementation to use.
+///
+/// If the type specified by `resolver_type_name` cannot be found,
+/// [`get_resolver`] will issue a warning and fall back to using
+/// [`DefaultResolver`].
+///
+/// This must be called before the first call to [`get_resolver`].
+pub fn set_preferred_resolver(resolver_type_name: &str) {
+    if RESOLVER.get().is_some() {
+        log::warn!(
+            "preferred resolver set to {} after the resolver was created, ignoring",
+            resolver_type_name
+        );
+    }
+    *lock(&PREFERRED_RESOLVER) = Some(resolver_type_name.to_owned());
+}
+
+/// Returns the type names of the available primary resolvers.
+///
+/// The list holds every resolver type advertised by a plugin or defined with
+/// [`define_resolver`], except URI resolvers, sorted by type name and
+/// followed by [`DEFAULT_RESOLVER_TYPE_NAME`].
+pub fn get_available_resolvers() -> Vec<String> {
+    let plugin_resolver_types = lock(&PLUGIN_RESOLVER_TYPES);
+    let mut type_names: BTreeSet<String> = plugin_resolver_types
+        .iter()
+        .filter(|(_, uri_schemes)| uri_schemes.is_empty())
+        .map(|(type_name, _)| type_name.clone())
+        .collect();
+    type_names.extend(
+        lock(&RESOLVER_FACTORIES)
+            .keys()
+            .filter(|type_name| !plugin_resolver_types.contains_key(*type_name))
+            .cloned(),
+    );
+    type_names.remove(DEFAULT_RESOLVER_TYPE_NAME);
+
+    let mut available: Vec<String> = type_names.into_iter().collect();
+    available.push(DEFAULT_RESOLVER_TYPE_NAME.to_owned());
+    available
+}
+
+/// Constructs an instance of the resolver type `resolver_type_name`.
+///
+/// If no factory was defined for that type with [`define_resolver`], a
+/// warning is issued and a [`DefaultResolver`] is returned instead.
+///
+/// This does not affect the resolver returned by [`get_resolver`].
+pub fn create_resolver(resolver_type_name: &str) -> Box<dyn Resolver + Send + Sync> {
+    if resolver_type_name != DEFAULT_RESOLVER_TYPE_NAME {
+        // Copy the factory out so the lock isn't held while it runs.
+        let factory = lock(&RESOLVER_FACTORIES).get(resolver_type_name).copied();
+        match factory {
+            Some(factory) => return factory(),
+            None => log::warn!(
+                "cannot create resolver {}, no factory was defined for it; \
+                 falling back to {}",
+                resolver_type_name,
+                DEFAULT_RESOLVER_TYPE_NAME
+            ),
+        }
+    }
+    Box::new(DefaultResolver::new())
+}
+
+fn create_primary_resolver() -> Box<dyn Resolver + Send + Sync> {
+    if let Some(preferred) = lock(&PREFERRED_RESOLVER).clone() {
+        log::debug!("using preferred resolver {}", preferred);
+        return create_resolver(&preferred);
+    }
+
+    let available = get_available_resolvers();
+    if available.len() > 2 {
+        log::debug!(
+            "found multiple primary resolvers {:?}, using {}",
+            &available[..available.len() - 1],
+            available[0]
+        );
+    }
+    create_resolver(&available[0])
+}
+
+fn create_resolver_wrapper() -> ResolverWrapper {
+    let mut wrapper = ResolverWrapper::new(create_primary_resolver());
+
+    let uri_resolver_types: Vec<(String, Vec<String>)> = lock(&PLUGIN_RESOLVER_TYPES)
+        .iter()
+        .filter(|(_, uri_schemes)| !uri_schemes.is_empty())
+        .map(|(type_name, uri_schemes)| (type_name.clone(), uri_schemes.clone()))
+        .collect();
+
+    for (type_name, uri_schemes) in uri_resolver_types {
+        let factory = lock(&RESOLVER_FACTORIES).get(&type_name).copied();
+        let resolver: Arc<dyn Resolver + Send + Sync> = match factory {
+            Some(factory) => Arc::from(factory()),
+            None => {
+                log::warn!(
+                    "cannot create URI resolver {}, no factory was defined for it",
+                    type_name
+                );
+                continue;
+            }
+        };
+
+        for uri_scheme in &uri_schemes {
+            if let Err(err) = wrapper.register_uri_resolver(&[uri_scheme], resolver.clone()) {
+                log::warn!("ignoring URI resolver {}: {}", type_name, err);
+            }
+        }
+    }
+
+    let package_resolver_types = lock(&PLUGIN_PACKAGE_RESOLVER_TYPES).clone();
+    for (type_name, extensions) in package_resolver_types {
+        let factory = lock(&PACKAGE_RESOLVER_FACTORIES).get(&type_name).copied();
+        let resolver: Arc<dyn PackageResolver + Send + Sync> = match factory {
+            Some(factory) => Arc::from(factory()),
+            None => {
+                log::warn!(
+                    "cannot create package resolver {}, no factory was defined for it",
+                    type_name
+                );
+                continue;
+            }
+        };
+
+        for extension in &extensions {
+            if let Err(err) = wrapper.register_package_resolver(&[extension], resolver.clone()) {
+                log::warn!("ignoring package resolver {}: {}", type_name, err);
+            }
+        }
+    }
+
+    // Built-in package resolvers, unless a plugin handles the extension.
+    for extension in ["usdz", "zip"] {
+        if wrapper.get_package_resolver(extension).is_some() {
+            continue;
+        }
+        let resolver = match extension {
+            "usdz" => ZipPackageResolver::usdz(),
+            _ => ZipPackageResolver::new(),
+        };
+        wrapper
+            .register_package_resolver(&[extension], Arc::new(resolver))
+            .expect("built-in package extensions are valid");
+    }
+
+    wrapper
+}
+
+/// Returns the configured asset resolver.
+///
+/// When first called, this function will determine the resolver type to use
+/// for asset resolution via the following process:
+///
+/// - If a preferred resolver has been set via [`set_preferred_resolver`], it
+///   will be selected.
+///
+/// - Otherwise, the first entry of [`get_available_resolvers`] is selected,
+///   i.e. the resolver type that sorts first by type name, or
+///   [`DefaultResolver`] if there are none.
+///
+/// - An instance of the selected type is constructed. If it cannot be
+///   constructed, a [`DefaultResolver`] is constructed instead.
+///
+/// Resolvers advertised for URI schemes are constructed as well, and calls
+/// on asset paths using those schemes are dispatched to them. The same goes
+/// for package resolvers and the package extensions they are advertised for.
+/// `.usdz` and `.zip` packages are handled by a [`ZipPackageResolver`]
+/// unless a plugin provides a package resolver for them.
+///
+/// The constructed resolvers are shared by the whole process. The returned
+/// [`ResolverWrapper`] is not the selected resolver itself, see
+/// [`get_underlying_resolver`] if access to it is needed.
+pub fn get_resolver() -> &'static ResolverWrapper {
+    RESOLVER.get_or_init(create_resolver_wrapper)
+}
+
+/// Returns the primary resolver instance used by [`get_resolver`].
+///
+/// This should typically not be used by consumers except in very specific
+/// cases. Consumers who want to perform asset resolution should use
+/// [`get_resolver`].
+pub fn get_underlying_resolver() -> &'static dyn Resolver {
+    get_resolver().get_primary_resolver()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_plugin_types() {
+        let metadata: RegistrationMetadata = serde_json::from_str(
+            r#"
+        {
+            "Type": "library",
+            "Name": "studioResolvers",
+            "Root": "/plugins",
+            "LibraryPath": "libstudio.so",
+            "Info": {
+                "Types": {
+                    "StudioResolver": {
+                        "bases": ["ArResolver"]
+                    },
+                    "StudioUriResolver": {
+                        "bases": ["ArResolver"],
+                        "uriSchemes": ["studio", "studio+dev"]
+                    },
+                    "StudioPackageResolver": {
+                        "bases": ["ArPackageResolver"],
+                        "extensions": ["spkg"]
+                    },
+                    "StudioFileFormat": {
+                        "bases": ["SdfFileFormat"]
+                    }
+                }
+            }
+        }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_plugin_types(&metadata, PACKAGE_RESOLVER_BASE_TYPE_NAME, "extensions"),
+            [("StudioPackageResolver".to_owned(), vec!["spkg".to_owned()])]
+        );
+        assert_eq!(
+            get_plugin_types(&metadata, RESOLVER_BASE_TYPE_NAME, "uriSchemes"),
+            [
+                ("StudioResolver".to_owned(), Vec::new()),
+                (
+                    "StudioUriResolver".to_owned(),
+                    vec!["studio".to_owned(), "studio+dev".to_owned()]
+                ),
+            ]
+        );
+    }
+}
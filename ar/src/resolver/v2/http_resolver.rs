@@ -0,0 +1,536 @@
+extern crate std;
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::time::Duration;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use super::http_utils::{
+    anchor_url, fnv1a, norm_url, read_body, RemoteAsset, RemoteContent, RequestError,
+};
+use super::{CacheScopeData, ResolveCache, Resolver, ResolverContext, WriteMode};
+use crate::filesystem::create_temporary_file;
+use crate::package_utils::split_package_path;
+use crate::resolver::default_resolution::open_filesystem_asset;
+use crate::{Asset, AssetError, ResolvedPath, ResolverError, WritableAsset};
+
+/// URI schemes served by [`HttpResolver`], to register it with
+/// [`crate::ResolverWrapper::register_uri_resolver`].
+pub const HTTP_URI_SCHEMES: &[&str] = &["http", "https"];
+
+/// Timeout for connecting and for each read or write of the requests made
+/// by an [`HttpResolver`], unless set with [`HttpResolver::with_timeout`].
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The `ETag` and `Last-Modified` headers of a response, identifying the
+/// version of the content it was for.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_response(response: &ureq::Response) -> Self {
+        Self {
+            etag: response.header("ETag").map(str::to_owned),
+            last_modified: response.header("Last-Modified").map(str::to_owned),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Returns a timestamp that changes when the content does: a hash of the
+    /// entity tag if there is one, otherwise the last modification time in
+    /// seconds since the Unix epoch.
+    fn get_timestamp(&self) -> Option<i64> {
+        if let Some(etag) = &self.etag {
+            return Some(fnv1a(etag) as i64);
+        }
+        let last_modified = httpdate::parse_http_date(self.last_modified.as_deref()?).ok()?;
+        let elapsed = last_modified.duration_since(UNIX_EPOCH).ok()?;
+        Some(elapsed.as_secs() as i64)
+    }
+
+    /// Makes `request` conditional on the content having changed.
+    fn if_modified(&self, mut request: ureq::Request) -> ureq::Request {
+        if let Some(etag) = &self.etag {
+            request = request.set("If-None-Match", etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            request = request.set("If-Modified-Since", last_modified);
+        }
+        request
+    }
+
+    /// Makes `request` conditional on the content being unchanged. Only
+    /// strong entity tags can be used for this, so the last modification
+    /// time is used instead if there is none.
+    fn if_unmodified(&self, request: ureq::Request) -> ureq::Request {
+        match (&self.etag, &self.last_modified) {
+            (Some(etag), _) if !etag.starts_with("W/") => request.set("If-Match", etag),
+            (_, Some(last_modified)) => request.set("If-Unmodified-Since", last_modified),
+            _ => request,
+        }
+    }
+}
+
+/// Response to a HEAD request made to open or resolve an asset.
+enum Head {
+    /// The content changed since the validators sent with the request, or
+    /// none were sent.
+    Modified {
+        size: Option<usize>,
+        validators: Validators,
+    },
+
+    /// The content is unchanged since the validators sent with the request.
+    NotModified,
+}
+
+/// Content of assets downloaded by an [`HttpResolver`], stored on disk along
+/// with their validators so they can be revalidated instead of downloaded
+/// again.
+#[derive(Clone, Debug)]
+struct DiskCache {
+    dir: PathBuf,
+}
+
+impl DiskCache {
+    fn get_entry_path(&self, url: &str, extension: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}", fnv1a(url)))
+            .with_extension(extension)
+    }
+
+    /// Returns the path to the cached content of `url`.
+    fn get_data_path(&self, url: &str) -> PathBuf {
+        self.get_entry_path(url, "data")
+    }
+
+    /// Returns the validators of the cached content of `url`, if any.
+    fn get_validators(&self, url: &str) -> Option<Validators> {
+        let meta = fs::read_to_string(self.get_entry_path(url, "meta")).ok()?;
+        let mut lines = meta.lines();
+        // Entries are keyed by hash, so check it is for the same URL.
+        if lines.next()? != url || !self.get_data_path(url).is_file() {
+            return None;
+        }
+        let mut next_header = || {
+            lines
+                .next()
+                .filter(|line| !line.is_empty())
+                .map(str::to_owned)
+        };
+        let validators = Validators {
+            etag: next_header(),
+            last_modified: next_header(),
+        };
+        Some(validators).filter(|validators| !validators.is_empty())
+    }
+
+    /// Stores `data` as the content of `url` with the given validators.
+    fn store(&self, url: &str, validators: &Validators, data: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let meta_path = self.get_entry_path(url, "meta");
+        // Remove the validators first so they never describe other content.
+        match fs::remove_file(&meta_path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+        write_atomically(&self.get_data_path(url), data)?;
+
+        let meta = format!(
+            "{}\n{}\n{}\n",
+            url,
+            validators.etag.as_deref().unwrap_or_default(),
+            validators.last_modified.as_deref().unwrap_or_default()
+        );
+        write_atomically(&meta_path, meta.as_bytes())
+    }
+}
+
+/// Writes `data` to a temporary file next to `path`, then moves it to
+/// `path`, so readers never see partially written files.
+fn write_atomically(path: &Path, data: &[u8]) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let (mut file, temporary_path) = create_temporary_file(dir, &format!(".{}.", file_name))?;
+    file.write_all(data)?;
+    drop(file);
+    fs::rename(&temporary_path, path)
+}
+
+/// [`Resolver`] implementation for `http:` and `https:` URLs, to register
+/// with [`crate::ResolverWrapper::register_uri_resolver`] for
+/// [`HTTP_URI_SCHEMES`]. Available with the `http` feature.
+///
+/// Identifiers are URLs with normalized paths. Relative asset paths are
+/// anchored to the directory of the anchoring URL, and absolute paths to its
+/// origin. A URL resolves to itself if a HEAD request for it succeeds.
+///
+/// Opened assets read ranges of their content with ranged GET requests, or
+/// download all of it when their whole buffer is requested. Downloaded
+/// content is kept in a cache directory on disk along with its `ETag` and
+/// `Last-Modified` headers, and is served from there as long as conditional
+/// requests find it unchanged. If the server cannot be reached, cached
+/// content is served without revalidation.
+///
+/// Modification timestamps are derived from the `ETag` header if there is
+/// one, and from the `Last-Modified` header otherwise.
+///
+/// HTTP assets are read-only, so new assets cannot be created.
+///
+/// # Examples
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use ar::{DefaultResolver, HttpResolver, Resolver, ResolverWrapper, HTTP_URI_SCHEMES};
+///
+/// let mut wrapper = ResolverWrapper::new(Box::new(DefaultResolver::new()));
+/// wrapper
+///     .register_uri_resolver(HTTP_URI_SCHEMES, Arc::new(HttpResolver::new("/tmp/ar-http-cache")))
+///     .unwrap();
+///
+/// let resolved_path = wrapper.resolve("https://assets.example.com/props/chair.usda");
+/// let asset = wrapper.open_asset(&resolved_path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct HttpResolver {
+    agent: ureq::Agent,
+    cache: DiskCache,
+    resolve_cache: ResolveCache,
+}
+
+impl HttpResolver {
+    /// Creates a resolver caching downloaded content in `cache_dir`, which
+    /// is created if needed.
+    pub fn new(cache_dir: impl AsRef<Path>) -> Self {
+        Self {
+            agent: Self::build_agent(DEFAULT_TIMEOUT),
+            cache: DiskCache {
+                dir: cache_dir.as_ref().to_path_buf(),
+            },
+            resolve_cache: ResolveCache::new(),
+        }
+    }
+
+    /// Sets the timeout for connecting to the server and for each read or
+    /// write on the connection. Transfers that keep making progress are not
+    /// cut off, however long they take. Defaults to 30 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.agent = Self::build_agent(timeout);
+        self
+    }
+
+    /// Returns the directory downloaded content is cached in.
+    pub fn get_cache_dir(&self) -> &Path {
+        &self.cache.dir
+    }
+
+    fn build_agent(timeout: Duration) -> ureq::Agent {
+        ureq::AgentBuilder::new()
+            .timeout_connect(timeout)
+            .timeout_read(timeout)
+            .timeout_write(timeout)
+            .build()
+    }
+
+    /// Sends a HEAD request for `url`, conditional on the content having
+    /// changed since `validators` if given.
+    fn head(&self, url: &str, validators: Option<&Validators>) -> Result<Head, RequestError> {
+        let mut request = self.agent.head(url);
+        if let Some(validators) = validators {
+            request = validators.if_modified(request);
+        }
+        let response = request.call()?;
+        if response.status() == 304 {
+            return Ok(Head::NotModified);
+        }
+        Ok(Head::Modified {
+            size: response
+                .header("Content-Length")
+                .and_then(|length| length.parse().ok()),
+            validators: Validators::from_response(&response),
+        })
+    }
+
+    fn resolve_url(&self, asset_path: &str) -> ResolvedPath {
+        // Assets inside a package exist if the package does.
+        let (url, _) = split_package_path(asset_path);
+        match self.head(url, None) {
+            Ok(_) => ResolvedPath::new(asset_path),
+            Err(err) if err.is_not_found() => ResolvedPath::default(),
+            Err(RequestError::Transport(reason)) if self.cache.get_validators(url).is_some() => {
+                log::warn!("resolving `{}` from cache: {}", url, reason);
+                ResolvedPath::new(asset_path)
+            }
+            Err(err) => {
+                log::warn!("failed to resolve `{}`: {}", url, err);
+                ResolvedPath::default()
+            }
+        }
+    }
+
+    fn open_cached(&self, url: &str) -> Result<Arc<dyn Asset>, ResolverError> {
+        open_filesystem_asset(&self.cache.get_data_path(url).to_string_lossy())
+    }
+}
+
+impl Resolver for HttpResolver {
+    fn create_identifier(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: Option<&ResolvedPath>,
+    ) -> String {
+        match anchor_asset_path {
+            Some(anchor) if !anchor.is_empty() => anchor_url(anchor.get_path_string(), asset_path),
+            _ => norm_url(asset_path),
+        }
+    }
+
+    fn create_identifier_for_new_asset(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: &ResolvedPath,
+    ) -> String {
+        self.create_identifier(asset_path, Some(anchor_asset_path))
+    }
+
+    fn resolve(&self, asset_path: &str) -> ResolvedPath {
+        if asset_path.is_empty() {
+            return ResolvedPath::default();
+        }
+
+        self.resolve_cache
+            .resolve(asset_path, &ResolverContext::new(), || {
+                self.resolve_url(asset_path)
+            })
+    }
+
+    fn resolve_for_new_asset(&self, _asset_path: &str) -> ResolvedPath {
+        ResolvedPath::default()
+    }
+
+    fn get_modification_timestamp(
+        &self,
+        _asset_path: &str,
+        resolved_path: &ResolvedPath,
+    ) -> Result<i64, ResolverError> {
+        let (url, _) = split_package_path(resolved_path.get_path_string());
+        let validators = match self.head(url, None) {
+            Ok(Head::Modified { validators, .. }) => Some(validators),
+            Ok(Head::NotModified) => None,
+            Err(RequestError::Transport(reason)) => {
+                log::warn!("using cached timestamp of `{}`: {}", url, reason);
+                self.cache.get_validators(url)
+            }
+            Err(_) => None,
+        };
+        validators
+            .and_then(|validators| validators.get_timestamp())
+            .ok_or(ResolverError::AssetMtimeError)
+    }
+
+    fn open_asset(&self, resolved_path: &ResolvedPath) -> Result<Arc<dyn Asset>, ResolverError> {
+        let url = resolved_path.get_path_string();
+        let cached = self.cache.get_validators(url);
+        match self.head(url, cached.as_ref()) {
+            Ok(Head::NotModified) => self.open_cached(url),
+            // Servers may ignore conditional requests.
+            Ok(Head::Modified { validators, .. }) if cached.as_ref() == Some(&validators) => {
+                self.open_cached(url)
+            }
+            Ok(Head::Modified { size, validators }) => {
+                let content = HttpContent {
+                    agent: self.agent.clone(),
+                    url: url.to_owned(),
+                    validators,
+                    cache: self.cache.clone(),
+                };
+                let asset = RemoteAsset::new(content, size.unwrap_or_default());
+                // Without a size, ranges cannot be checked, so the whole
+                // content is downloaded up front.
+                if size.is_none() {
+                    asset
+                        .download()
+                        .map_err(|err| ResolverError::InvalidAsset(url.to_owned(), err))?;
+                }
+                Ok(Arc::new(asset))
+            }
+            Err(RequestError::Transport(reason)) if cached.is_some() => {
+                log::warn!("opening `{}` from cache: {}", url, reason);
+                self.open_cached(url)
+            }
+            Err(err) => Err(ResolverError::OpenAssetError(format!("{}: {}", url, err))),
+        }
+    }
+
+    fn open_asset_for_write(
+        &self,
+        resolved_path: &ResolvedPath,
+        _write_mode: WriteMode,
+    ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+        Err(ResolverError::OpenAssetError(format!(
+            "{}: HTTP assets are read-only",
+            resolved_path
+        )))
+    }
+
+    fn begin_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        self.resolve_cache.begin_cache_scope(cache_scope_data);
+    }
+
+    fn end_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        self.resolve_cache.end_cache_scope(cache_scope_data);
+    }
+}
+
+/// Content served over HTTP, read by the [`RemoteAsset`] returned by
+/// [`HttpResolver::open_asset`] when the content isn't cached.
+#[derive(Debug)]
+struct HttpContent {
+    agent: ureq::Agent,
+    url: String,
+    validators: Validators,
+    cache: DiskCache,
+}
+
+impl HttpContent {
+    /// Sends a GET request for the content, or the given range of it,
+    /// conditional on the content being unchanged since the asset was
+    /// opened.
+    fn get(&self, range: Option<(usize, usize)>) -> Result<ureq::Response, AssetError> {
+        let mut request = self.validators.if_unmodified(self.agent.get(&self.url));
+        if let Some((offset, count)) = range {
+            request = request.set("Range", &format!("bytes={}-{}", offset, offset + count - 1));
+        }
+        request.call().map_err(|err| match err {
+            ureq::Error::Status(412, _) => {
+                AssetError::ReadError(format!("`{}` changed since it was opened", self.url))
+            }
+            err => AssetError::ReadError(format!("{}: {}", self.url, RequestError::from(err))),
+        })
+    }
+}
+
+impl RemoteContent for HttpContent {
+    fn get_url(&self) -> &str {
+        &self.url
+    }
+
+    fn get_range(&self, offset: usize, count: usize) -> Result<ureq::Response, AssetError> {
+        self.get(Some((offset, count)))
+    }
+
+    /// Downloads the whole content and stores it in the disk cache.
+    fn download(&self) -> Result<Vec<u8>, AssetError> {
+        let response = self.get(None)?;
+        let validators = Validators::from_response(&response);
+        let data = read_body(&self.url, response, 0)?;
+        if !validators.is_empty() {
+            if let Err(err) = self.cache.store(&self.url, &validators, &data) {
+                log::warn!("failed to cache `{}`: {}", self.url, err);
+            }
+        }
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anchor(anchor: &str, path: &str) -> String {
+        HttpResolver::new("/nonexistent").create_identifier(path, Some(&ResolvedPath::new(anchor)))
+    }
+
+    #[test]
+    fn test_create_identifier() {
+        assert_eq!(
+            anchor("http://host/a/b.usda", "./c.usda"),
+            "http://host/a/c.usda"
+        );
+        assert_eq!(
+            anchor("http://host/a/b.usda", "../c.usda"),
+            "http://host/c.usda"
+        );
+        assert_eq!(
+            anchor("http://host/a/b.usda", "c.usda"),
+            "http://host/a/c.usda"
+        );
+        assert_eq!(
+            anchor("http://host/a/b.usda", "/c.usda"),
+            "http://host/c.usda"
+        );
+        assert_eq!(
+            anchor("http://host/b.usda?v=2", "./c.usda"),
+            "http://host/c.usda"
+        );
+        assert_eq!(anchor("http://host", "./c.usda"), "http://host/c.usda");
+        assert_eq!(
+            anchor("http://host/a/b.usda", "https://other/./c.usda"),
+            "https://other/c.usda"
+        );
+        assert_eq!(
+            anchor("http://host/p.usdz[a/b.usda]", "./c.usda"),
+            "http://host/p.usdz[a/c.usda]"
+        );
+        assert_eq!(anchor("http://host/a/b.usda", ""), "");
+    }
+
+    #[test]
+    fn test_validators() {
+        let etag = Validators {
+            etag: Some("\"v1\"".into()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".into()),
+        };
+        let other_etag = Validators {
+            etag: Some("\"v2\"".into()),
+            ..etag.clone()
+        };
+        assert_ne!(etag.get_timestamp(), other_etag.get_timestamp());
+
+        let last_modified = Validators {
+            etag: None,
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".into()),
+        };
+        assert_eq!(last_modified.get_timestamp(), Some(1_445_412_480));
+        assert_eq!(Validators::default().get_timestamp(), None);
+    }
+
+    #[test]
+    fn test_disk_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache {
+            dir: dir.path().join("cache"),
+        };
+        let validators = Validators {
+            etag: Some("\"v1\"".into()),
+            last_modified: None,
+        };
+        assert_eq!(cache.get_validators("http://host/a.usda"), None);
+
+        cache
+            .store("http://host/a.usda", &validators, b"#usda 1.0")
+            .unwrap();
+        assert_eq!(cache.get_validators("http://host/a.usda"), Some(validators));
+        assert_eq!(
+            fs::read(cache.get_data_path("http://host/a.usda")).unwrap(),
+            b"#usda 1.0"
+        );
+        assert_eq!(cache.get_validators("http://host/b.usda"), None);
+    }
+}
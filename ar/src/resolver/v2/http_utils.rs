@@ -0,0 +1,297 @@
+//! Utilities shared by resolvers for assets served over HTTP.
+
+extern crate std;
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use std::io::Read;
+
+use crate::asset::check_read_bounds;
+use crate::package_utils::{find_innermost_packaged_path, split_package_path};
+use crate::path_utils::{cat_paths, get_path_name, is_relative_path, norm_path};
+use crate::resolver::default_resolution::anchor_to;
+use crate::sync::TryOnceLock;
+use crate::{Asset, AssetError};
+
+/// Returns the 64-bit FNV-1a hash of `s`, used for stable cache file names
+/// and timestamps.
+pub(crate) fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Splits `url` into its origin, e.g. `https://example.com:8080`, and the
+/// rest of it. Returns [`None`] if `url` doesn't start with a URI scheme
+/// followed by an authority.
+pub(crate) fn split_origin(url: &str) -> Option<(&str, &str)> {
+    let scheme_end = url.find("://")?;
+    let scheme = &url[..scheme_end];
+    if scheme.is_empty()
+        || !scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    {
+        return None;
+    }
+
+    let authority_start = scheme_end + 3;
+    let path_start = url[authority_start..]
+        .find(['/', '?', '#'])
+        .map_or(url.len(), |index| authority_start + index);
+    Some(url.split_at(path_start))
+}
+
+/// Normalizes the path of `url`, leaving any query and fragment unchanged.
+/// Strings that aren't URLs are returned as-is.
+pub(crate) fn norm_url(url: &str) -> String {
+    let (origin, rest) = match split_origin(url) {
+        Some(split) => split,
+        None => return url.to_string(),
+    };
+    let (path, suffix) = rest.split_at(rest.find(['?', '#']).unwrap_or(rest.len()));
+    let (package_path, packaged_path) = split_package_path(path);
+    let path = if package_path.is_empty() {
+        "/".to_string()
+    } else {
+        norm_path(package_path)
+    };
+    format!("{}{}{}{}", origin, path, packaged_path, suffix)
+}
+
+/// Anchors `path` to the URL `anchor`. Absolute paths are anchored to the
+/// anchor's origin, and if `anchor` is a package-relative path, relative
+/// paths are anchored inside its innermost package.
+pub(crate) fn anchor_url(anchor: &str, path: &str) -> String {
+    if path.is_empty() || split_origin(path).is_some() {
+        return norm_url(path);
+    }
+    let (origin, anchor_path) = match split_origin(anchor) {
+        Some(split) => split,
+        None => return path.to_string(),
+    };
+    if !is_relative_path(path) {
+        return norm_url(&format!("{}{}", origin, path));
+    }
+    if find_innermost_packaged_path(anchor).is_some() {
+        return anchor_to(anchor, path);
+    }
+
+    let anchor_path = &anchor_path[..anchor_path.find(['?', '#']).unwrap_or(anchor_path.len())];
+    let directory = match get_path_name(anchor_path) {
+        "" => "/",
+        directory => directory,
+    };
+    norm_url(&format!("{}{}", origin, cat_paths(directory, path)))
+}
+
+/// Errors from HTTP requests made by a resolver.
+#[derive(Debug)]
+pub(crate) enum RequestError {
+    /// The server responded with the given status code.
+    Status(u16),
+
+    /// The server couldn't be reached or the response couldn't be read.
+    Transport(String),
+}
+
+impl RequestError {
+    pub(crate) fn is_not_found(&self) -> bool {
+        matches!(self, RequestError::Status(404) | RequestError::Status(410))
+    }
+}
+
+impl From<ureq::Error> for RequestError {
+    fn from(err: ureq::Error) -> Self {
+        match err {
+            ureq::Error::Status(status, _) => RequestError::Status(status),
+            ureq::Error::Transport(err) => RequestError::Transport(err.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::Status(status) => write!(f, "server responded with status {}", status),
+            RequestError::Transport(reason) => write!(f, "request failed: {}", reason),
+        }
+    }
+}
+
+/// Reads the body of `response` to `url`, expecting `size` bytes unless
+/// `size` is 0.
+pub(crate) fn read_body(
+    url: &str,
+    response: ureq::Response,
+    size: usize,
+) -> Result<Vec<u8>, AssetError> {
+    let mut data = Vec::with_capacity(size);
+    response
+        .into_reader()
+        .read_to_end(&mut data)
+        .map_err(|err| AssetError::ReadError(format!("{}: {}", url, err)))?;
+    if size != 0 && data.len() != size {
+        return Err(AssetError::ReadError(format!(
+            "expected {} bytes from `{}`, got {}",
+            size,
+            url,
+            data.len()
+        )));
+    }
+    Ok(data)
+}
+
+/// Remote content read by a [`RemoteAsset`].
+pub(crate) trait RemoteContent: fmt::Debug + Send + Sync {
+    /// Returns the URL of the content, for error messages.
+    fn get_url(&self) -> &str;
+
+    /// Sends a GET request for `count` bytes of the content at `offset`.
+    /// Servers may ignore the range and send the whole content instead.
+    fn get_range(&self, offset: usize, count: usize) -> Result<ureq::Response, AssetError>;
+
+    /// Downloads the whole content.
+    fn download(&self) -> Result<Vec<u8>, AssetError>;
+}
+
+/// [`Asset`] implementation for remote content of a known size. Reads are
+/// served with ranged GET requests until the whole buffer is requested,
+/// which downloads the content once and serves later reads from memory.
+#[derive(Debug)]
+pub(crate) struct RemoteAsset<C> {
+    content: C,
+    size: usize,
+    buffer: TryOnceLock<Vec<u8>>,
+}
+
+impl<C: RemoteContent> RemoteAsset<C> {
+    /// Creates an asset for `content` of `size` bytes. A size of 0 means
+    /// the size is unknown, in which case [`RemoteAsset::download`] must be
+    /// called before reading.
+    pub(crate) fn new(content: C, size: usize) -> Self {
+        Self {
+            content,
+            size,
+            buffer: TryOnceLock::new(),
+        }
+    }
+
+    /// Downloads the whole content, once.
+    pub(crate) fn download(&self) -> Result<&[u8], AssetError> {
+        self.buffer
+            .get_or_try_init(|| {
+                let data = self.content.download()?;
+                if self.size != 0 && data.len() != self.size {
+                    return Err(AssetError::ReadError(format!(
+                        "expected {} bytes from `{}`, got {}",
+                        self.size,
+                        self.content.get_url(),
+                        data.len()
+                    )));
+                }
+                Ok(data)
+            })
+            .map(Vec::as_slice)
+    }
+}
+
+impl<C: RemoteContent> Asset for RemoteAsset<C> {
+    fn get_size(&self) -> usize {
+        self.buffer.get().map_or(self.size, Vec::len)
+    }
+
+    fn get_buffer(&self) -> Result<&[u8], AssetError> {
+        self.download()
+    }
+
+    fn read(&self, buffer: &mut [u8], count: usize, offset: usize) -> Result<usize, AssetError> {
+        let size = self.get_size();
+        check_read_bounds(buffer.len(), count, offset, size)?;
+        if count == 0 {
+            return Ok(0);
+        }
+
+        if let Some(data) = self.buffer.get() {
+            buffer[..count].copy_from_slice(&data[offset..offset + count]);
+            return Ok(count);
+        }
+
+        let url = self.content.get_url();
+        let response = self.content.get_range(offset, count)?;
+        if response.status() != 206 {
+            // The server ignored the range and sent the whole content.
+            let data = self
+                .buffer
+                .get_or_try_init(|| read_body(url, response, size))?;
+            buffer[..count].copy_from_slice(&data[offset..offset + count]);
+            return Ok(count);
+        }
+
+        response
+            .into_reader()
+            .read_exact(&mut buffer[..count])
+            .map_err(|err| AssetError::ReadError(format!("{}: {}", url, err)))?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_norm_url() {
+        assert_eq!(norm_url("http://host"), "http://host/");
+        assert_eq!(
+            norm_url("http://host/a/./b/../c.usda"),
+            "http://host/a/c.usda"
+        );
+        assert_eq!(
+            norm_url("https://host:8080/a//b.usda?v=1/../2"),
+            "https://host:8080/a/b.usda?v=1/../2"
+        );
+        assert_eq!(
+            norm_url("http://host/a/../p.usdz[b/../c.usda]"),
+            "http://host/p.usdz[b/../c.usda]"
+        );
+        assert_eq!(norm_url("s3://bucket/a/../b.usda"), "s3://bucket/b.usda");
+        assert_eq!(norm_url("a/b.usda"), "a/b.usda");
+    }
+
+    /// Content failing to download on the first attempt.
+    #[derive(Debug, Default)]
+    struct FlakyContent {
+        attempts: std::sync::Mutex<usize>,
+    }
+
+    impl RemoteContent for FlakyContent {
+        fn get_url(&self) -> &str {
+            "http://host/flaky.usda"
+        }
+
+        fn get_range(&self, _offset: usize, _count: usize) -> Result<ureq::Response, AssetError> {
+            unreachable!("reads are served from the downloaded content")
+        }
+
+        fn download(&self) -> Result<Vec<u8>, AssetError> {
+            let mut attempts = self.attempts.lock().unwrap();
+            *attempts += 1;
+            match *attempts {
+                1 => Err(AssetError::ReadError("connection reset".to_string())),
+                _ => Ok(b"#usda 1.0".to_vec()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_failed_download_is_retried() {
+        let asset = RemoteAsset::new(FlakyContent::default(), 9);
+        assert!(asset.get_buffer().is_err());
+        assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+        assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+        assert_eq!(*asset.content.attempts.lock().unwrap(), 2);
+    }
+}
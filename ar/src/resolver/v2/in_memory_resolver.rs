@@ -0,0 +1,377 @@
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::WriteMode;
+use crate::package_utils::split_package_path;
+use crate::resolver::default_resolution::{anchor_to, norm_asset_path};
+use crate::sync::{lock, Mutex};
+use crate::writable_asset::check_write_buffer;
+use crate::{
+    is_package_relative_path, Asset, InMemoryAsset, ResolvedPath, Resolver, ResolverError,
+    WritableAsset, WritableAssetError,
+};
+
+/// Contents of an asset held by an [`InMemoryResolver`], along with the
+/// revision at which they were last written.
+#[derive(Debug)]
+struct Entry {
+    data: Arc<[u8]>,
+    revision: i64,
+}
+
+/// Assets held by an [`InMemoryResolver`], shared with the writable assets
+/// it opens.
+#[derive(Debug, Default)]
+struct Store {
+    entries: BTreeMap<String, Entry>,
+    revision: i64,
+}
+
+impl Store {
+    fn insert(&mut self, identifier: &str, data: Arc<[u8]>) {
+        self.revision += 1;
+        let entry = Entry {
+            data,
+            revision: self.revision,
+        };
+        self.entries.insert(identifier.to_owned(), entry);
+    }
+}
+
+/// [`Resolver`] implementation serving assets held in memory, keyed by
+/// identifier.
+///
+/// No I/O is performed, so this resolver works without `std`, e.g. on
+/// wasm targets, and is convenient for tests.
+///
+/// Identifiers are normalized asset paths, with relative paths anchored to
+/// the anchor asset's directory like [`super::DefaultResolver`] does. An
+/// asset path resolves to itself if an asset is held for it.
+///
+/// Each time an asset is written, a revision counter shared by all assets of
+/// the resolver is incremented and recorded for that asset. The modification
+/// timestamp of an asset is the revision at which it was last written.
+///
+/// # Examples
+/// ```
+/// use ar::{InMemoryResolver, Resolver, WriteMode};
+///
+/// let resolver = InMemoryResolver::new();
+/// resolver.insert("/assets/a.usda", b"#usda 1.0");
+///
+/// let resolved_path = resolver.resolve("/assets/a.usda");
+/// let asset = resolver.open_asset(&resolved_path).unwrap();
+/// assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+///
+/// let resolved_path = resolver.resolve_for_new_asset("/assets/b.usda");
+/// let mut asset = resolver
+///     .open_asset_for_write(&resolved_path, WriteMode::Replace)
+///     .unwrap();
+/// asset.write(b"#usda 1.0", 9, 0).unwrap();
+/// asset.close().unwrap();
+/// assert!(resolver.contains("/assets/b.usda"));
+/// ```
+#[derive(Debug, Default)]
+pub struct InMemoryResolver {
+    store: Arc<Mutex<Store>>,
+}
+
+impl InMemoryResolver {
+    /// Creates a resolver without any assets.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores `data` as the contents of the asset `identifier`, replacing any
+    /// previous contents.
+    pub fn insert(&self, identifier: &str, data: impl Into<Vec<u8>>) {
+        lock(&self.store).insert(identifier, data.into().into());
+    }
+
+    /// Removes the asset `identifier`. Returns true if it existed.
+    pub fn remove(&self, identifier: &str) -> bool {
+        lock(&self.store).entries.remove(identifier).is_some()
+    }
+
+    /// Returns true if an asset is held for `identifier`.
+    pub fn contains(&self, identifier: &str) -> bool {
+        lock(&self.store).entries.contains_key(identifier)
+    }
+
+    /// Returns the identifiers of all assets held by this resolver, in
+    /// sorted order.
+    pub fn get_identifiers(&self) -> Vec<String> {
+        lock(&self.store).entries.keys().cloned().collect()
+    }
+}
+
+impl Resolver for InMemoryResolver {
+    fn create_identifier(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: Option<&ResolvedPath>,
+    ) -> String {
+        match anchor_asset_path {
+            Some(anchor) if !asset_path.is_empty() && !anchor.is_empty() => {
+                norm_asset_path(&anchor_to(anchor.get_path_string(), asset_path))
+            }
+            _ if asset_path.is_empty() => String::new(),
+            _ => norm_asset_path(asset_path),
+        }
+    }
+
+    fn create_identifier_for_new_asset(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: &ResolvedPath,
+    ) -> String {
+        self.create_identifier(asset_path, Some(anchor_asset_path))
+    }
+
+    fn resolve(&self, asset_path: &str) -> ResolvedPath {
+        // Assets inside a package exist if the package does.
+        let (package_path, _) = split_package_path(asset_path);
+        if self.contains(package_path) {
+            ResolvedPath::new(asset_path)
+        } else {
+            ResolvedPath::default()
+        }
+    }
+
+    fn resolve_for_new_asset(&self, asset_path: &str) -> ResolvedPath {
+        ResolvedPath::new(asset_path)
+    }
+
+    fn get_modification_timestamp(
+        &self,
+        _asset_path: &str,
+        resolved_path: &ResolvedPath,
+    ) -> Result<i64, ResolverError> {
+        let (package_path, _) = split_package_path(resolved_path.get_path_string());
+        lock(&self.store)
+            .entries
+            .get(package_path)
+            .map(|entry| entry.revision)
+            .ok_or(ResolverError::AssetMtimeError)
+    }
+
+    fn open_asset(&self, resolved_path: &ResolvedPath) -> Result<Arc<dyn Asset>, ResolverError> {
+        let path = resolved_path.get_path_string();
+        match lock(&self.store).entries.get(path) {
+            Some(entry) => Ok(Arc::new(InMemoryAsset::new(entry.data.clone()))),
+            None => Err(ResolverError::OpenAssetError(path.to_string())),
+        }
+    }
+
+    fn open_asset_for_write(
+        &self,
+        resolved_path: &ResolvedPath,
+        write_mode: WriteMode,
+    ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+        let path = resolved_path.get_path_string();
+        if path.is_empty() || is_package_relative_path(path) {
+            return Err(ResolverError::OpenAssetError(path.to_string()));
+        }
+
+        let data = match write_mode {
+            WriteMode::Update => lock(&self.store)
+                .entries
+                .get(path)
+                .map(|entry| entry.data.to_vec())
+                .unwrap_or_default(),
+            WriteMode::Replace => Vec::new(),
+        };
+        Ok(Box::new(InMemoryWritableAsset {
+            store: self.store.clone(),
+            identifier: path.to_owned(),
+            data: Some(data),
+        }))
+    }
+}
+
+/// [`WritableAsset`] implementation for an asset of an [`InMemoryResolver`].
+///
+/// Writes are buffered and only become visible to the resolver, as a new
+/// revision of the asset, when the asset is closed. Assets dropped without
+/// being closed leave the resolver's asset untouched, for both write modes.
+pub struct InMemoryWritableAsset {
+    store: Arc<Mutex<Store>>,
+    identifier: String,
+    data: Option<Vec<u8>>,
+}
+
+impl WritableAsset for InMemoryWritableAsset {
+    fn close(&mut self) -> Result<(), WritableAssetError> {
+        let data = self.data.take().ok_or_else(|| {
+            WritableAssetError::CloseFailed("asset is already closed".to_string())
+        })?;
+        lock(&self.store).insert(&self.identifier, data.into());
+        Ok(())
+    }
+
+    fn write(
+        &mut self,
+        buffer: &[u8],
+        count: usize,
+        offset: usize,
+    ) -> Result<usize, WritableAssetError> {
+        let data = self
+            .data
+            .as_mut()
+            .ok_or_else(|| WritableAssetError::WriteFailed("asset is closed".to_string()))?;
+        check_write_buffer(buffer.len(), count)?;
+        let end = offset
+            .checked_add(count)
+            .ok_or_else(|| WritableAssetError::WriteFailed("offset is too large".to_string()))?;
+
+        if data.len() < end {
+            data.resize(end, 0);
+        }
+        data[offset..end].copy_from_slice(&buffer[..count]);
+        Ok(count)
+    }
+}
+
+impl fmt::Debug for InMemoryWritableAsset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InMemoryWritableAsset")
+            .field("identifier", &self.identifier)
+            .field("closed", &self.data.is_none())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(resolver: &InMemoryResolver, identifier: &str) -> Vec<u8> {
+        let resolved_path = resolver.resolve(identifier);
+        let asset = resolver.open_asset(&resolved_path).unwrap();
+        asset.get_buffer().unwrap().to_vec()
+    }
+
+    fn write(
+        resolver: &InMemoryResolver,
+        identifier: &str,
+        write_mode: WriteMode,
+    ) -> Box<dyn WritableAsset> {
+        let resolved_path = resolver.resolve_for_new_asset(identifier);
+        resolver
+            .open_asset_for_write(&resolved_path, write_mode)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_create_identifier() {
+        let resolver = InMemoryResolver::new();
+        let anchor = ResolvedPath::new("/assets/dir/a.usda");
+        assert_eq!(
+            resolver.create_identifier("../b.usda", Some(&anchor)),
+            "/assets/b.usda"
+        );
+        assert_eq!(
+            resolver.create_identifier("/other/./b.usda", Some(&anchor)),
+            "/other/b.usda"
+        );
+        assert_eq!(
+            resolver.create_identifier("./b.usda", Some(&ResolvedPath::new("/a.usdz[dir/a.usda]"))),
+            "/a.usdz[dir/b.usda]"
+        );
+        assert_eq!(resolver.create_identifier("dir/../b.usda", None), "b.usda");
+        assert_eq!(resolver.create_identifier("", Some(&anchor)), "");
+    }
+
+    #[test]
+    fn test_resolve() {
+        let resolver = InMemoryResolver::new();
+        resolver.insert("/a.usda", b"a");
+        resolver.insert("/b.usdz", b"b");
+
+        assert_eq!(resolver.resolve("/a.usda"), ResolvedPath::new("/a.usda"));
+        assert_eq!(
+            resolver.resolve("/b.usdz[c.usda]"),
+            ResolvedPath::new("/b.usdz[c.usda]")
+        );
+        assert!(resolver.resolve("/c.usda").is_empty());
+        assert!(resolver.open_asset(&ResolvedPath::new("/c.usda")).is_err());
+
+        assert!(resolver.remove("/a.usda"));
+        assert!(!resolver.remove("/a.usda"));
+        assert!(resolver.resolve("/a.usda").is_empty());
+        assert_eq!(resolver.get_identifiers(), ["/b.usdz"]);
+    }
+
+    #[test]
+    fn test_write_replace() {
+        let resolver = InMemoryResolver::new();
+        resolver.insert("/a.usda", b"0123456789");
+        let opened = resolver.open_asset(&ResolvedPath::new("/a.usda")).unwrap();
+
+        let mut asset = write(&resolver, "/a.usda", WriteMode::Replace);
+        assert_eq!(asset.write(b"abc", 3, 2).unwrap(), 3);
+        // Writes are only visible once the asset is closed.
+        assert_eq!(read(&resolver, "/a.usda"), b"0123456789");
+        asset.close().unwrap();
+        assert_eq!(read(&resolver, "/a.usda"), b"\0\0abc");
+        // Assets opened before keep their contents.
+        assert_eq!(opened.get_buffer().unwrap(), b"0123456789");
+
+        assert!(asset.close().is_err());
+        assert!(asset.write(b"abc", 3, 0).is_err());
+    }
+
+    #[test]
+    fn test_write_update() {
+        let resolver = InMemoryResolver::new();
+        resolver.insert("/a.usda", b"0123456789");
+
+        let mut asset = write(&resolver, "/a.usda", WriteMode::Update);
+        asset.write(b"abc", 3, 8).unwrap();
+        asset.close().unwrap();
+        assert_eq!(read(&resolver, "/a.usda"), b"01234567abc");
+
+        let mut asset = write(&resolver, "/new.usda", WriteMode::Update);
+        asset.write(b"abc", 3, 0).unwrap();
+        asset.close().unwrap();
+        assert_eq!(read(&resolver, "/new.usda"), b"abc");
+
+        // Dropping an asset without closing it discards the writes.
+        let mut asset = write(&resolver, "/a.usda", WriteMode::Update);
+        asset.write(b"xyz", 3, 0).unwrap();
+        drop(asset);
+        assert_eq!(read(&resolver, "/a.usda"), b"01234567abc");
+
+        assert!(resolver
+            .open_asset_for_write(&ResolvedPath::new("/a.usdz[b.usda]"), WriteMode::Update)
+            .is_err());
+    }
+
+    #[test]
+    fn test_modification_timestamp() {
+        let resolver = InMemoryResolver::new();
+        let timestamp =
+            |path: &str| resolver.get_modification_timestamp(path, &ResolvedPath::new(path));
+
+        resolver.insert("/a.usda", b"a");
+        resolver.insert("/b.usdz", b"b");
+        let a = timestamp("/a.usda").unwrap();
+        let b = timestamp("/b.usdz").unwrap();
+        assert!(b > a);
+        assert_eq!(timestamp("/b.usdz[c.usda]").unwrap(), b);
+        assert!(matches!(
+            timestamp("/c.usda"),
+            Err(ResolverError::AssetMtimeError)
+        ));
+
+        let mut asset = write(&resolver, "/a.usda", WriteMode::Update);
+        asset.close().unwrap();
+        assert!(timestamp("/a.usda").unwrap() > b);
+        assert_eq!(timestamp("/b.usdz").unwrap(), b);
+    }
+}
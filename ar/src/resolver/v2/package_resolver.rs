@@ -0,0 +1,46 @@
+use alloc::string::String;
+use alloc::sync::Arc;
+
+use super::{CacheScopeData, Resolver};
+use crate::{Asset, ResolvedPath, ResolverError};
+
+/// Trait for resolvers that handle assets inside a package, such as a layer
+/// inside a `.usdz` archive.
+///
+/// Package resolvers are registered for the file extensions of the packages
+/// they handle, see [`super::ResolverWrapper::register_package_resolver`].
+/// For a package-relative path like `/dir/a.usdz[b.usd]`, the package path
+/// `/dir/a.usdz` is resolved by the regular resolvers and the packaged path
+/// `b.usd` by the package resolver registered for `usdz`.
+///
+/// Packages may be nested, in which case the resolved package path passed to
+/// these methods is itself a package-relative path. The `resolver` passed to
+/// these methods can be used to open the package asset in any case.
+pub trait PackageResolver {
+    /// Returns the resolved path for the asset located at `packaged_path` in
+    /// the package at `resolved_package_path`, or an empty string if it
+    /// does not exist.
+    fn resolve(
+        &self,
+        resolver: &dyn Resolver,
+        resolved_package_path: &ResolvedPath,
+        packaged_path: &str,
+    ) -> String;
+
+    /// Opens the asset at `resolved_packaged_path` in the package at
+    /// `resolved_package_path`.
+    fn open_asset(
+        &self,
+        resolver: &dyn Resolver,
+        resolved_package_path: &ResolvedPath,
+        resolved_packaged_path: &str,
+    ) -> Result<Arc<dyn Asset>, ResolverError>;
+
+    /// Mark the start of a resolution caching scope, see
+    /// [`Resolver::begin_cache_scope`].
+    fn begin_cache_scope(&self, _cache_scope_data: &mut CacheScopeData) {}
+
+    /// Mark the end of a resolution caching scope, see
+    /// [`Resolver::end_cache_scope`].
+    fn end_cache_scope(&self, _cache_scope_data: &mut CacheScopeData) {}
+}
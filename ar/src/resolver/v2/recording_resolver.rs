@@ -0,0 +1,979 @@
+use alloc::boxed::Box;
+use alloc::collections::{BTreeSet, VecDeque};
+use alloc::string::{String, ToString};
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
+use core::any::Any;
+use core::fmt;
+use core::marker::PhantomData;
+use core::time::Duration;
+
+use super::thread_local_stack::ThreadLocalStack;
+use super::{CacheScopeData, Resolver, ResolverContext, WriteMode};
+use crate::sync::{lock, Mutex};
+use crate::{Asset, AssetInfo, ResolvedPath, ResolverError, WritableAsset};
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "std")] {
+        extern crate std;
+
+        /// Clock used to time the calls to the wrapped resolver.
+        struct Stopwatch(std::time::Instant);
+
+        impl Stopwatch {
+            fn start() -> Self {
+                Self(std::time::Instant::now())
+            }
+
+            fn elapsed(&self) -> Option<Duration> {
+                Some(self.0.elapsed())
+            }
+        }
+    } else {
+        /// Without `std` there is no clock, so calls are not timed.
+        struct Stopwatch;
+
+        impl Stopwatch {
+            fn start() -> Self {
+                Self
+            }
+
+            fn elapsed(&self) -> Option<Duration> {
+                None
+            }
+        }
+    }
+}
+
+/// The [`Resolver`] calls recorded by a [`RecordingResolver`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ResolverOperation {
+    /// [`Resolver::create_identifier`]
+    CreateIdentifier,
+    /// [`Resolver::create_identifier_for_new_asset`]
+    CreateIdentifierForNewAsset,
+    /// [`Resolver::resolve`]
+    Resolve,
+    /// [`Resolver::resolve_for_new_asset`]
+    ResolveForNewAsset,
+    /// [`Resolver::bind_context`]
+    BindContext,
+    /// [`Resolver::unbind_context`]
+    UnbindContext,
+    /// [`Resolver::get_modification_timestamp`]
+    GetModificationTimestamp,
+    /// [`Resolver::open_asset`]
+    OpenAsset,
+    /// [`Resolver::open_asset_for_write`]
+    OpenAssetForWrite,
+}
+
+impl fmt::Display for ResolverOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ResolverOperation::CreateIdentifier => "create_identifier",
+            ResolverOperation::CreateIdentifierForNewAsset => "create_identifier_for_new_asset",
+            ResolverOperation::Resolve => "resolve",
+            ResolverOperation::ResolveForNewAsset => "resolve_for_new_asset",
+            ResolverOperation::BindContext => "bind_context",
+            ResolverOperation::UnbindContext => "unbind_context",
+            ResolverOperation::GetModificationTimestamp => "get_modification_timestamp",
+            ResolverOperation::OpenAsset => "open_asset",
+            ResolverOperation::OpenAssetForWrite => "open_asset_for_write",
+        })
+    }
+}
+
+/// A call to the resolver wrapped by a [`RecordingResolver`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResolverEvent {
+    operation: ResolverOperation,
+    asset_path: String,
+    identifier: Option<String>,
+    resolved_path: Option<ResolvedPath>,
+    context: ResolverContext,
+    elapsed: Option<Duration>,
+    error: Option<String>,
+}
+
+impl ResolverEvent {
+    fn new(operation: ResolverOperation, asset_path: &str) -> Self {
+        Self {
+            operation,
+            asset_path: asset_path.to_string(),
+            identifier: None,
+            resolved_path: None,
+            context: ResolverContext::new(),
+            elapsed: None,
+            error: None,
+        }
+    }
+
+    /// Returns the recorded call.
+    pub fn get_operation(&self) -> ResolverOperation {
+        self.operation
+    }
+
+    /// Returns the asset path passed to the call. For calls taking a
+    /// resolved path, this is the resolved path, and for calls binding or
+    /// unbinding a context, it is empty.
+    pub fn get_asset_path(&self) -> &str {
+        &self.asset_path
+    }
+
+    /// Returns the identifier created by the call, if any.
+    pub fn get_identifier(&self) -> Option<&str> {
+        self.identifier.as_deref()
+    }
+
+    /// Returns the resolved path returned or opened by the call, if any.
+    pub fn get_resolved_path(&self) -> Option<&ResolvedPath> {
+        self.resolved_path.as_ref()
+    }
+
+    /// Returns the context bound when the call was made, or the context
+    /// bound or unbound by the call.
+    pub fn get_context(&self) -> &ResolverContext {
+        &self.context
+    }
+
+    /// Returns how long the wrapped resolver took to handle the call. Calls
+    /// are only timed with the `std` feature.
+    pub fn get_elapsed(&self) -> Option<Duration> {
+        self.elapsed
+    }
+
+    /// Returns why the call failed, if it did. Resolving an asset path to
+    /// an empty resolved path counts as a failure.
+    pub fn get_error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Returns true if the call failed.
+    pub fn is_failure(&self) -> bool {
+        self.error.is_some()
+    }
+}
+
+impl fmt::Display for ResolverEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.operation)?;
+        if !self.asset_path.is_empty() {
+            write!(f, " `{}`", self.asset_path)?;
+        }
+        if let Some(identifier) = &self.identifier {
+            write!(f, " -> `{}`", identifier)?;
+        }
+        if let Some(resolved_path) = &self.resolved_path {
+            write!(f, " -> `{}`", resolved_path)?;
+        }
+        if !self.context.is_empty() {
+            write!(f, " in {:?}", self.context)?;
+        }
+        if let Some(elapsed) = self.elapsed {
+            write!(f, " ({:?})", elapsed)?;
+        }
+        if let Some(error) = &self.error {
+            write!(f, ": {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolved paths recorded for a thread or a dependency scope, linked to the
+/// set of the enclosing scope or thread.
+#[derive(Debug, Default)]
+struct DependencySet {
+    resolved_paths: Mutex<BTreeSet<ResolvedPath>>,
+    parent: Option<Arc<DependencySet>>,
+}
+
+impl DependencySet {
+    fn with_parent(parent: Arc<DependencySet>) -> Self {
+        Self {
+            resolved_paths: Mutex::default(),
+            parent: Some(parent),
+        }
+    }
+
+    fn insert(&self, resolved_path: &ResolvedPath) {
+        let mut set = Some(self);
+        while let Some(dependencies) = set {
+            lock(&dependencies.resolved_paths).insert(resolved_path.clone());
+            set = dependencies.parent.as_deref();
+        }
+    }
+
+    fn get(&self) -> BTreeSet<ResolvedPath> {
+        lock(&self.resolved_paths).clone()
+    }
+
+    fn clear(&self) {
+        lock(&self.resolved_paths).clear();
+    }
+}
+
+/// [`Resolver`] decorator forwarding every call to a wrapped resolver and
+/// recording which assets were resolved and opened.
+///
+/// Calls creating identifiers, resolving, binding or unbinding contexts,
+/// getting modification timestamps and opening assets are recorded as
+/// [`ResolverEvent`]s, along with the context bound at the time, how long
+/// the call took and whether it failed. Each event is also logged through
+/// the `log` crate, at debug level, or warn level for failures. To bound the
+/// memory used by long sessions, only the most recent events can be kept
+/// with [`RecordingResolver::with_event_capacity`], or only failures with
+/// [`RecordingResolver::with_failures_only`].
+///
+/// The resolved paths of the assets that were resolved or opened make up
+/// the dependency set. It is kept for each thread, see
+/// [`RecordingResolver::get_thread_dependencies`], and for each
+/// [`DependencyScope`] open on the thread. The sets of the threads are owned
+/// by the resolver and released with it, even if those threads keep
+/// running. The sets of threads that exited are released as other threads
+/// record their first dependencies. Without `std`, all threads share the
+/// same dependency set.
+///
+/// # Examples
+/// ```
+/// use ar::{InMemoryResolver, RecordingResolver, ResolvedPath, Resolver};
+///
+/// let resolver = InMemoryResolver::new();
+/// resolver.insert("/shot/layout.usda", b"#usda 1.0");
+/// let resolver = RecordingResolver::new(resolver);
+///
+/// let scope = resolver.begin_dependency_scope();
+/// resolver.resolve("/shot/layout.usda");
+/// resolver.resolve("/shot/missing.usda");
+/// assert_eq!(
+///     scope.get_dependencies().into_iter().collect::<Vec<_>>(),
+///     [ResolvedPath::new("/shot/layout.usda")]
+/// );
+/// assert_eq!(resolver.get_failures().len(), 1);
+/// ```
+pub struct RecordingResolver<R> {
+    resolver: R,
+    events: Mutex<VecDeque<ResolverEvent>>,
+    event_capacity: Option<usize>,
+    failures_only: bool,
+    /// Dependency sets of the threads that recorded any, until they exit.
+    thread_dependencies: Mutex<Vec<Arc<DependencySet>>>,
+    /// Each thread's own dependency set, owned by `thread_dependencies` so
+    /// it is released with the resolver. Sets are looked up here rather than
+    /// in `thread_dependencies` to avoid contention between threads.
+    thread_sets: ThreadLocalStack<Weak<DependencySet>>,
+    /// Dependency sets of the scopes open on each thread.
+    scopes: ThreadLocalStack<Arc<DependencySet>>,
+}
+
+impl<R: Resolver> RecordingResolver<R> {
+    /// Creates a decorator recording the calls to `resolver`.
+    pub fn new(resolver: R) -> Self {
+        Self {
+            resolver,
+            events: Mutex::default(),
+            event_capacity: None,
+            failures_only: false,
+            thread_dependencies: Mutex::default(),
+            thread_sets: ThreadLocalStack::new(),
+            scopes: ThreadLocalStack::new(),
+        }
+    }
+
+    /// Keeps only the `capacity` most recent events, discarding the oldest
+    /// ones as new events are recorded. With a capacity of 0, no event is
+    /// kept and only the dependency sets are recorded. By default, all
+    /// events are kept.
+    pub fn with_event_capacity(mut self, capacity: usize) -> Self {
+        self.event_capacity = Some(capacity);
+        self
+    }
+
+    /// Keeps only the events of failed calls. Dependency sets are not
+    /// affected.
+    pub fn with_failures_only(mut self) -> Self {
+        self.failures_only = true;
+        self
+    }
+
+    /// Returns the wrapped resolver.
+    pub fn get_resolver(&self) -> &R {
+        &self.resolver
+    }
+
+    /// Returns the wrapped resolver, dropping the recorded events.
+    pub fn into_inner(self) -> R {
+        self.resolver
+    }
+
+    /// Returns the recorded events of all threads, in the order the calls
+    /// completed.
+    pub fn get_events(&self) -> Vec<ResolverEvent> {
+        lock(&self.events).iter().cloned().collect()
+    }
+
+    /// Returns the recorded events for which `predicate` returns true.
+    pub fn get_events_matching(
+        &self,
+        mut predicate: impl FnMut(&ResolverEvent) -> bool,
+    ) -> Vec<ResolverEvent> {
+        lock(&self.events)
+            .iter()
+            .filter(|event| predicate(event))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the recorded events of failed calls.
+    pub fn get_failures(&self) -> Vec<ResolverEvent> {
+        self.get_events_matching(ResolverEvent::is_failure)
+    }
+
+    /// Discards the recorded events of all threads. Dependency sets are not
+    /// affected.
+    pub fn clear_events(&self) {
+        lock(&self.events).clear();
+    }
+
+    /// Returns the resolved paths of the assets resolved or opened on the
+    /// current thread, including in dependency scopes.
+    pub fn get_thread_dependencies(&self) -> BTreeSet<ResolvedPath> {
+        self.find_thread_dependencies()
+            .map(|dependencies| dependencies.get())
+            .unwrap_or_default()
+    }
+
+    /// Clears the dependency set of the current thread. The sets of open
+    /// dependency scopes are not affected.
+    pub fn clear_thread_dependencies(&self) {
+        if let Some(dependencies) = self.find_thread_dependencies() {
+            dependencies.clear();
+        }
+    }
+
+    /// Opens a dependency scope on the current thread, recording the
+    /// resolved paths of the assets resolved or opened on this thread until
+    /// it is dropped. Scopes can be nested, and assets are recorded in all
+    /// open scopes.
+    pub fn begin_dependency_scope(&self) -> DependencyScope<'_> {
+        let parent = self.thread_dependencies();
+        let dependencies = Arc::new(DependencySet::with_parent(parent));
+        self.scopes.push(dependencies.clone());
+        DependencyScope {
+            stack: &self.scopes,
+            dependencies,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the dependency set of the innermost scope open on the current
+    /// thread, or the thread's own set.
+    fn thread_dependencies(&self) -> Arc<DependencySet> {
+        if let Some(dependencies) = self.scopes.top() {
+            return dependencies;
+        }
+        if let Some(dependencies) = self.find_thread_dependencies() {
+            return dependencies;
+        }
+        let dependencies = Arc::new(DependencySet::default());
+        let mut thread_dependencies = lock(&self.thread_dependencies);
+        // The thread-local stack of a thread that exited no longer refers to
+        // its set.
+        thread_dependencies.retain(|dependencies| Arc::weak_count(dependencies) > 0);
+        thread_dependencies.push(dependencies.clone());
+        self.thread_sets.push(Arc::downgrade(&dependencies));
+        dependencies
+    }
+
+    /// Returns the current thread's own dependency set, if anything was
+    /// recorded on this thread.
+    fn find_thread_dependencies(&self) -> Option<Arc<DependencySet>> {
+        self.thread_sets
+            .top()
+            .and_then(|dependencies| dependencies.upgrade())
+    }
+
+    /// Calls `f` on the wrapped resolver, letting it fill in the event, then
+    /// logs and records the event. The resolved paths of successfully
+    /// resolved and opened assets are added to the dependency sets.
+    fn record<T>(
+        &self,
+        operation: ResolverOperation,
+        asset_path: &str,
+        f: impl FnOnce(&R, &mut ResolverEvent) -> T,
+    ) -> T {
+        let mut event = ResolverEvent::new(operation, asset_path);
+        let stopwatch = Stopwatch::start();
+        let result = f(&self.resolver, &mut event);
+        event.elapsed = stopwatch.elapsed();
+
+        let is_dependency = matches!(
+            operation,
+            ResolverOperation::Resolve
+                | ResolverOperation::OpenAsset
+                | ResolverOperation::OpenAssetForWrite
+        );
+        if let Some(resolved_path) = &event.resolved_path {
+            if is_dependency && event.error.is_none() {
+                self.thread_dependencies().insert(resolved_path);
+            }
+        }
+
+        let is_kept = self.event_capacity != Some(0) && (!self.failures_only || event.is_failure());
+        let is_binding = matches!(
+            operation,
+            ResolverOperation::BindContext | ResolverOperation::UnbindContext
+        );
+        // The other calls don't change the bound context, so it is only
+        // queried once they are done, and for the events that are kept.
+        if is_kept && !is_binding {
+            event.context = self.resolver.get_current_context();
+        }
+
+        if event.is_failure() {
+            log::warn!("{}", event);
+        } else {
+            log::debug!("{}", event);
+        }
+        if is_kept {
+            let mut events = lock(&self.events);
+            events.push_back(event);
+            if let Some(capacity) = self.event_capacity {
+                let excess = events.len().saturating_sub(capacity);
+                events.drain(..excess);
+            }
+        }
+        result
+    }
+}
+
+impl<R> fmt::Debug for RecordingResolver<R>
+where
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecordingResolver")
+            .field("resolver", &self.resolver)
+            .field("events", &lock(&self.events).len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[allow(deprecated)]
+impl<R: Resolver> Resolver for RecordingResolver<R> {
+    fn create_identifier(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: Option<&ResolvedPath>,
+    ) -> String {
+        self.record(
+            ResolverOperation::CreateIdentifier,
+            asset_path,
+            |resolver, event| {
+                let identifier = resolver.create_identifier(asset_path, anchor_asset_path);
+                event.identifier = Some(identifier.clone());
+                identifier
+            },
+        )
+    }
+
+    fn create_identifier_for_new_asset(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: &ResolvedPath,
+    ) -> String {
+        self.record(
+            ResolverOperation::CreateIdentifierForNewAsset,
+            asset_path,
+            |resolver, event| {
+                let identifier =
+                    resolver.create_identifier_for_new_asset(asset_path, anchor_asset_path);
+                event.identifier = Some(identifier.clone());
+                identifier
+            },
+        )
+    }
+
+    fn resolve(&self, asset_path: &str) -> ResolvedPath {
+        self.record(ResolverOperation::Resolve, asset_path, |resolver, event| {
+            let resolved_path = resolver.resolve(asset_path);
+            if resolved_path.is_empty() {
+                event.error = Some("asset not found".to_string());
+            }
+            event.resolved_path = Some(resolved_path.clone());
+            resolved_path
+        })
+    }
+
+    fn resolve_for_new_asset(&self, asset_path: &str) -> ResolvedPath {
+        self.record(
+            ResolverOperation::ResolveForNewAsset,
+            asset_path,
+            |resolver, event| {
+                let resolved_path = resolver.resolve_for_new_asset(asset_path);
+                if resolved_path.is_empty() {
+                    event.error = Some("cannot create asset".to_string());
+                }
+                event.resolved_path = Some(resolved_path.clone());
+                resolved_path
+            },
+        )
+    }
+
+    fn bind_context(&self, context: &ResolverContext, binding_data: &dyn Any) {
+        self.record(ResolverOperation::BindContext, "", |resolver, event| {
+            event.context = context.clone();
+            resolver.bind_context(context, binding_data);
+        })
+    }
+
+    fn unbind_context(&self, context: &ResolverContext, binding_data: &dyn Any) {
+        self.record(ResolverOperation::UnbindContext, "", |resolver, event| {
+            event.context = context.clone();
+            resolver.unbind_context(context, binding_data);
+        })
+    }
+
+    fn create_default_context(&self) -> ResolverContext {
+        self.resolver.create_default_context()
+    }
+
+    fn create_default_context_for_asset(&self, asset_path: &str) -> ResolverContext {
+        self.resolver.create_default_context_for_asset(asset_path)
+    }
+
+    fn create_context_from_string(&self, context_str: &str) -> ResolverContext {
+        self.resolver.create_context_from_string(context_str)
+    }
+
+    fn create_context_from_uri_and_string(
+        &self,
+        uri_scheme: &str,
+        context_str: &str,
+    ) -> ResolverContext {
+        self.resolver
+            .create_context_from_uri_and_string(uri_scheme, context_str)
+    }
+
+    fn create_context_from_strings(&self, context_strings: &[(&str, &str)]) -> ResolverContext {
+        self.resolver.create_context_from_strings(context_strings)
+    }
+
+    fn refresh_context(&self, context: &ResolverContext) {
+        self.resolver.refresh_context(context);
+    }
+
+    fn get_current_context(&self) -> ResolverContext {
+        self.resolver.get_current_context()
+    }
+
+    fn is_context_dependent_path(&self, asset_path: &str) -> bool {
+        self.resolver.is_context_dependent_path(asset_path)
+    }
+
+    fn get_extension(&self, asset_path: &str) -> String {
+        self.resolver.get_extension(asset_path)
+    }
+
+    fn get_asset_info(&self, asset_path: &str, resolved_path: &ResolvedPath) -> AssetInfo {
+        self.resolver.get_asset_info(asset_path, resolved_path)
+    }
+
+    fn get_modification_timestamp(
+        &self,
+        asset_path: &str,
+        resolved_path: &ResolvedPath,
+    ) -> Result<i64, ResolverError> {
+        self.record(
+            ResolverOperation::GetModificationTimestamp,
+            asset_path,
+            |resolver, event| {
+                event.resolved_path = Some(resolved_path.clone());
+                let timestamp = resolver.get_modification_timestamp(asset_path, resolved_path);
+                event.error = timestamp.as_ref().err().map(ToString::to_string);
+                timestamp
+            },
+        )
+    }
+
+    fn open_asset(&self, resolved_path: &ResolvedPath) -> Result<Arc<dyn Asset>, ResolverError> {
+        self.record(
+            ResolverOperation::OpenAsset,
+            resolved_path.get_path_string(),
+            |resolver, event| {
+                event.resolved_path = Some(resolved_path.clone());
+                let asset = resolver.open_asset(resolved_path);
+                event.error = asset.as_ref().err().map(ToString::to_string);
+                asset
+            },
+        )
+    }
+
+    fn open_asset_for_write(
+        &self,
+        resolved_path: &ResolvedPath,
+        write_mode: WriteMode,
+    ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+        self.record(
+            ResolverOperation::OpenAssetForWrite,
+            resolved_path.get_path_string(),
+            |resolver, event| {
+                event.resolved_path = Some(resolved_path.clone());
+                let asset = resolver.open_asset_for_write(resolved_path, write_mode);
+                event.error = asset.as_ref().err().map(ToString::to_string);
+                asset
+            },
+        )
+    }
+
+    fn begin_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        self.resolver.begin_cache_scope(cache_scope_data);
+    }
+
+    fn end_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        self.resolver.end_cache_scope(cache_scope_data);
+    }
+
+    fn configure_resolver_for_asset(&self, path: &str) {
+        self.resolver.configure_resolver_for_asset(path);
+    }
+
+    fn anchor_relative_path(&self, anchor_path: &str, path: &str) -> String {
+        self.resolver.anchor_relative_path(anchor_path, path)
+    }
+
+    fn is_relative_path(&self, path: &str) -> bool {
+        self.resolver.is_relative_path(path)
+    }
+
+    fn is_search_path(&self, path: &str) -> bool {
+        self.resolver.is_search_path(path)
+    }
+
+    fn is_repository_path(&self, path: &str) -> bool {
+        self.resolver.is_repository_path(path)
+    }
+
+    fn fetch_to_local_resolved_path(&self, path: &str, resolved_path: &str) -> bool {
+        self.resolver
+            .fetch_to_local_resolved_path(path, resolved_path)
+    }
+
+    fn create_path_for_layer(&self, path: &str) -> Result<(), ResolverError> {
+        self.resolver.create_path_for_layer(path)
+    }
+
+    fn can_write_layer_to_path(&self, path: &str) -> Result<(), ResolverError> {
+        self.resolver.can_write_layer_to_path(path)
+    }
+
+    fn can_create_new_layer_with_identifier(&self, identifier: &str) -> Result<(), ResolverError> {
+        self.resolver
+            .can_create_new_layer_with_identifier(identifier)
+    }
+}
+
+/// Scope recording the resolved paths of the assets resolved or opened on
+/// the current thread through a [`RecordingResolver`], created by
+/// [`RecordingResolver::begin_dependency_scope`].
+///
+/// The scope is closed when dropped. Scopes must be dropped in the reverse
+/// order they were opened, and on the thread that opened them, so they
+/// cannot be sent to another thread.
+pub struct DependencyScope<'a> {
+    stack: &'a ThreadLocalStack<Arc<DependencySet>>,
+    dependencies: Arc<DependencySet>,
+    // Scopes are bound to the creating thread and must be closed there.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl DependencyScope<'_> {
+    /// Returns the resolved paths of the assets resolved or opened since the
+    /// scope was opened or last cleared.
+    pub fn get_dependencies(&self) -> BTreeSet<ResolvedPath> {
+        self.dependencies.get()
+    }
+
+    /// Clears the dependency set of this scope. The sets of the enclosing
+    /// scopes and of the thread are not affected.
+    pub fn clear(&self) {
+        self.dependencies.clear();
+    }
+}
+
+impl Drop for DependencyScope<'_> {
+    fn drop(&mut self) {
+        if !self
+            .stack
+            .pop()
+            .is_some_and(|dependencies| Arc::ptr_eq(&dependencies, &self.dependencies))
+        {
+            log::warn!("closing dependency scope in unexpected order");
+        }
+    }
+}
+
+impl fmt::Debug for DependencyScope<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DependencyScope")
+            .field("dependencies", &self.dependencies.get())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    use crate::{ClientContext, InMemoryResolver, ResolverContextBinder};
+
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct ShotContext(&'static str);
+
+    impl ClientContext for ShotContext {}
+
+    fn resolver() -> RecordingResolver<InMemoryResolver> {
+        let resolver = InMemoryResolver::new();
+        resolver.insert("/shot/shot.usda", b"#usda 1.0");
+        resolver.insert("/shot/layout.usda", b"#usda 1.0");
+        RecordingResolver::new(resolver)
+    }
+
+    fn paths(paths: &[&str]) -> BTreeSet<ResolvedPath> {
+        paths.iter().map(|path| ResolvedPath::new(path)).collect()
+    }
+
+    #[test]
+    fn test_events() {
+        let resolver = resolver();
+        let anchor = resolver.resolve("/shot/shot.usda");
+        let identifier = resolver.create_identifier("./layout.usda", Some(&anchor));
+        assert_eq!(identifier, "/shot/layout.usda");
+        let asset = resolver.open_asset(&resolver.resolve(&identifier)).unwrap();
+        assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+
+        let events = resolver.get_events();
+        let operations: Vec<_> = events.iter().map(ResolverEvent::get_operation).collect();
+        assert_eq!(
+            operations,
+            [
+                ResolverOperation::Resolve,
+                ResolverOperation::CreateIdentifier,
+                ResolverOperation::Resolve,
+                ResolverOperation::OpenAsset,
+            ]
+        );
+        assert_eq!(events[1].get_asset_path(), "./layout.usda");
+        assert_eq!(events[1].get_identifier(), Some("/shot/layout.usda"));
+        assert_eq!(
+            events[2].get_resolved_path(),
+            Some(&ResolvedPath::new("/shot/layout.usda"))
+        );
+        assert!(events.iter().all(|event| !event.is_failure()));
+        assert_eq!(
+            events[3].to_string().split(" (").next(),
+            Some("open_asset `/shot/layout.usda` -> `/shot/layout.usda`")
+        );
+
+        resolver.clear_events();
+        assert!(resolver.get_events().is_empty());
+    }
+
+    #[test]
+    fn test_failures() {
+        let resolver = resolver();
+        assert!(resolver.resolve("/shot/missing.usda").is_empty());
+        assert!(resolver
+            .open_asset(&ResolvedPath::new("/shot/missing.usda"))
+            .is_err());
+
+        let failures = resolver.get_failures();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].get_error(), Some("asset not found"));
+        assert_eq!(failures[1].get_operation(), ResolverOperation::OpenAsset);
+        assert!(resolver.get_thread_dependencies().is_empty());
+    }
+
+    #[test]
+    fn test_contexts() {
+        let resolver = resolver();
+        let mut context = ResolverContext::new();
+        context.push(ShotContext("s01"));
+
+        {
+            let _binder = ResolverContextBinder::with_resolver(&resolver, context.clone());
+            resolver.resolve("/shot/shot.usda");
+        }
+
+        let events = resolver.get_events();
+        assert_eq!(events[0].get_operation(), ResolverOperation::BindContext);
+        assert_eq!(events[0].get_context(), &context);
+        assert_eq!(events[1].get_operation(), ResolverOperation::Resolve);
+        assert_eq!(events[2].get_operation(), ResolverOperation::UnbindContext);
+        assert_eq!(events[2].get_context(), &context);
+
+        let binds = resolver
+            .get_events_matching(|event| event.get_operation() == ResolverOperation::BindContext);
+        assert_eq!(binds.len(), 1);
+    }
+
+    #[test]
+    fn test_event_capacity() {
+        let resolver = resolver().with_event_capacity(2);
+        resolver.resolve("/shot/shot.usda");
+        resolver.resolve("/shot/layout.usda");
+        resolver.resolve("/shot/missing.usda");
+        let asset_paths: Vec<_> = resolver
+            .get_events()
+            .iter()
+            .map(|event| event.get_asset_path().to_string())
+            .collect();
+        assert_eq!(asset_paths, ["/shot/layout.usda", "/shot/missing.usda"]);
+
+        let resolver = self::resolver().with_event_capacity(0);
+        resolver.resolve("/shot/shot.usda");
+        assert!(resolver.get_events().is_empty());
+        assert_eq!(
+            resolver.get_thread_dependencies(),
+            paths(&["/shot/shot.usda"])
+        );
+    }
+
+    #[test]
+    fn test_failures_only() {
+        let resolver = resolver().with_failures_only();
+        resolver.resolve("/shot/shot.usda");
+        resolver.resolve("/shot/missing.usda");
+
+        let events = resolver.get_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].get_asset_path(), "/shot/missing.usda");
+        assert_eq!(
+            resolver.get_thread_dependencies(),
+            paths(&["/shot/shot.usda"])
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_timings() {
+        let resolver = resolver();
+        resolver.resolve("/shot/shot.usda");
+        assert!(resolver.get_events()[0].get_elapsed().is_some());
+    }
+
+    #[test]
+    fn test_dependency_scopes() {
+        let resolver = resolver();
+        resolver.resolve("/shot/shot.usda");
+        {
+            let outer = resolver.begin_dependency_scope();
+            resolver.resolve("/shot/layout.usda");
+            {
+                let inner = resolver.begin_dependency_scope();
+                assert!(inner.get_dependencies().is_empty());
+                resolver
+                    .open_asset(&ResolvedPath::new("/shot/shot.usda"))
+                    .unwrap();
+                assert_eq!(inner.get_dependencies(), paths(&["/shot/shot.usda"]));
+                inner.clear();
+                assert!(inner.get_dependencies().is_empty());
+            }
+            assert_eq!(
+                outer.get_dependencies(),
+                paths(&["/shot/layout.usda", "/shot/shot.usda"])
+            );
+        }
+        assert_eq!(
+            resolver.get_thread_dependencies(),
+            paths(&["/shot/layout.usda", "/shot/shot.usda"])
+        );
+
+        resolver.clear_thread_dependencies();
+        assert!(resolver.get_thread_dependencies().is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_thread_dependencies() {
+        let resolver = resolver();
+        resolver.resolve("/shot/shot.usda");
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                assert!(resolver.get_thread_dependencies().is_empty());
+                resolver.resolve("/shot/layout.usda");
+                assert_eq!(
+                    resolver.get_thread_dependencies(),
+                    paths(&["/shot/layout.usda"])
+                );
+            });
+        });
+        assert_eq!(
+            resolver.get_thread_dependencies(),
+            paths(&["/shot/shot.usda"])
+        );
+        // Events are recorded for all threads.
+        assert_eq!(resolver.get_events().len(), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_thread_dependencies_released_with_resolver() {
+        use std::sync::mpsc;
+
+        let resolver = Arc::new(resolver());
+        let (dependencies_sender, dependencies_receiver) = mpsc::channel();
+        let (done_sender, done_receiver) = mpsc::channel::<()>();
+        let worker = {
+            let resolver = resolver.clone();
+            std::thread::spawn(move || {
+                resolver.resolve("/shot/layout.usda");
+                // Only dependency scopes are kept on the thread's stack.
+                assert!(resolver.scopes.top().is_none());
+                let dependencies = Arc::downgrade(&resolver.thread_dependencies());
+                drop(resolver);
+                dependencies_sender.send(dependencies).unwrap();
+                done_receiver.recv().unwrap();
+            })
+        };
+
+        let dependencies = dependencies_receiver.recv().unwrap();
+        assert_eq!(
+            dependencies.upgrade().unwrap().get(),
+            paths(&["/shot/layout.usda"])
+        );
+        drop(resolver);
+        // The worker is still running, but its set went with the resolver.
+        assert!(dependencies.upgrade().is_none());
+        done_sender.send(()).unwrap();
+        worker.join().unwrap();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_thread_dependencies_released_with_thread() {
+        let resolver = Arc::new(resolver());
+        for _ in 0..3 {
+            // Unlike scoped threads, joined threads have released their
+            // thread-locals.
+            let resolver = resolver.clone();
+            std::thread::spawn(move || resolver.resolve("/shot/layout.usda"))
+                .join()
+                .unwrap();
+        }
+        // Each thread released the set of the one that exited before it.
+        let exited = Arc::as_ptr(&lock(&resolver.thread_dependencies)[0]);
+        assert_eq!(lock(&resolver.thread_dependencies).len(), 1);
+
+        resolver.resolve("/shot/shot.usda");
+        let thread_dependencies = lock(&resolver.thread_dependencies);
+        assert_eq!(thread_dependencies.len(), 1);
+        assert_ne!(Arc::as_ptr(&thread_dependencies[0]), exited);
+        drop(thread_dependencies);
+        assert_eq!(
+            resolver.get_thread_dependencies(),
+            paths(&["/shot/shot.usda"])
+        );
+    }
+}
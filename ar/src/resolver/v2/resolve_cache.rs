@@ -0,0 +1,301 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use super::thread_local_stack::ThreadLocalStack;
+use super::CacheScopeData;
+use crate::sync::{lock, Mutex};
+use crate::{ResolvedPath, ResolverContext};
+
+/// Resolved paths cached by a single cache scope, grouped by the context
+/// that was bound when they were resolved.
+#[derive(Debug)]
+struct ResolveCacheScope {
+    // Identifies the cache that opened the scope, so that the scope data of
+    // other caches is not mistaken for it.
+    cache_id: usize,
+    entries: Mutex<Vec<(ResolverContext, BTreeMap<String, ResolvedPath>)>>,
+}
+
+impl ResolveCacheScope {
+    fn new(cache_id: usize) -> Self {
+        Self {
+            cache_id,
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn get(&self, context: &ResolverContext, asset_path: &str) -> Option<ResolvedPath> {
+        lock(&self.entries)
+            .iter()
+            .find(|(entry_context, _)| entry_context == context)
+            .and_then(|(_, resolved_paths)| resolved_paths.get(asset_path).cloned())
+    }
+
+    fn insert(&self, context: &ResolverContext, asset_path: &str, resolved_path: ResolvedPath) {
+        let mut entries = lock(&self.entries);
+        let index = match entries
+            .iter()
+            .position(|(entry_context, _)| entry_context == context)
+        {
+            Some(index) => index,
+            None => {
+                entries.push((context.clone(), BTreeMap::new()));
+                entries.len() - 1
+            }
+        };
+        entries[index].1.insert(asset_path.into(), resolved_path);
+    }
+}
+
+/// Cache of resolved paths that resolvers can use to implement
+/// [`super::Resolver::begin_cache_scope`] and
+/// [`super::Resolver::end_cache_scope`].
+///
+/// While a cache scope is open on the current thread, [`Self::resolve`]
+/// memoizes results keyed by asset path and bound context. Scopes are
+/// tracked per thread and may be nested, in which case the nested scope
+/// shares the cache of the enclosing one. The data of a scope may be passed
+/// to [`Self::begin_cache_scope`] on other threads to share its cache with
+/// them.
+///
+/// # Examples
+/// ```
+/// use ar::{CacheScopeData, ResolveCache, ResolvedPath, ResolverContext};
+///
+/// let cache = ResolveCache::new();
+/// let context = ResolverContext::new();
+/// let mut calls = 0;
+/// let mut resolve = || {
+///     calls += 1;
+///     ResolvedPath::new("/assets/a.usd")
+/// };
+///
+/// let mut cache_scope_data = CacheScopeData::default();
+/// cache.begin_cache_scope(&mut cache_scope_data);
+/// cache.resolve("a.usd", &context, &mut resolve);
+/// cache.resolve("a.usd", &context, &mut resolve);
+/// cache.end_cache_scope(&mut cache_scope_data);
+/// assert_eq!(calls, 1);
+/// ```
+#[derive(Debug)]
+pub struct ResolveCache {
+    scopes: ThreadLocalStack<Arc<ResolveCacheScope>>,
+}
+
+impl ResolveCache {
+    /// Creates a cache with no open scopes.
+    pub fn new() -> Self {
+        Self {
+            scopes: ThreadLocalStack::new(),
+        }
+    }
+
+    /// Opens a cache scope on the current thread.
+    ///
+    /// If `cache_scope_data` holds the data of a scope opened by this cache,
+    /// possibly on another thread, that scope's cache is shared. Otherwise
+    /// the cache of the enclosing scope on this thread is shared, or a new
+    /// cache is created, and `cache_scope_data` is filled in with it. Data of
+    /// scopes opened by other caches is ignored and replaced.
+    pub fn begin_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        let cache_id = self.scopes.get_id();
+        let scope = cache_scope_data
+            .clone()
+            .and_then(|data| data.downcast::<ResolveCacheScope>().ok())
+            .filter(|scope| scope.cache_id == cache_id)
+            .or_else(|| self.scopes.top())
+            .unwrap_or_else(|| Arc::new(ResolveCacheScope::new(cache_id)));
+        *cache_scope_data = Some(scope.clone());
+        self.scopes.push(scope);
+    }
+
+    /// Closes the innermost cache scope opened on the current thread.
+    pub fn end_cache_scope(&self, _cache_scope_data: &mut CacheScopeData) {
+        self.scopes.pop();
+    }
+
+    /// Returns true if a cache scope is open on the current thread.
+    pub fn is_active(&self) -> bool {
+        self.scopes.top().is_some()
+    }
+
+    /// Returns the result of `resolve` for `asset_path` under `context`.
+    ///
+    /// If a cache scope is open, a result cached for the same asset path and
+    /// context is returned instead of calling `resolve`, and new results are
+    /// added to the cache. Otherwise `resolve` is always called.
+    pub fn resolve(
+        &self,
+        asset_path: &str,
+        context: &ResolverContext,
+        resolve: impl FnOnce() -> ResolvedPath,
+    ) -> ResolvedPath {
+        let scope = match self.scopes.top() {
+            Some(scope) => scope,
+            None => return resolve(),
+        };
+
+        if let Some(resolved_path) = scope.get(context, asset_path) {
+            return resolved_path;
+        }
+
+        // The cache isn't locked while resolving, so concurrent resolves of
+        // the same path may both run `resolve`.
+        let resolved_path = resolve();
+        scope.insert(context, asset_path, resolved_path.clone());
+        resolved_path
+    }
+}
+
+impl Default for ResolveCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClientContext, ResolverContext};
+
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct TestContext(usize);
+
+    impl ClientContext for TestContext {}
+
+    fn counting_resolve<'a>(
+        calls: &'a mut usize,
+        path: &'a str,
+    ) -> impl FnOnce() -> ResolvedPath + 'a {
+        move || {
+            *calls += 1;
+            ResolvedPath::new(path)
+        }
+    }
+
+    #[test]
+    fn test_no_caching_outside_scope() {
+        let cache = ResolveCache::new();
+        let context = ResolverContext::new();
+        let mut calls = 0;
+        cache.resolve("a.usd", &context, counting_resolve(&mut calls, "/a.usd"));
+        cache.resolve("a.usd", &context, counting_resolve(&mut calls, "/a.usd"));
+        assert_eq!(calls, 2);
+        assert!(!cache.is_active());
+    }
+
+    #[test]
+    fn test_cache_keyed_by_path_and_context() {
+        let cache = ResolveCache::new();
+        let empty = ResolverContext::new();
+        let mut other = ResolverContext::new();
+        other.push(TestContext(1));
+
+        let mut data = CacheScopeData::default();
+        cache.begin_cache_scope(&mut data);
+        assert!(data.is_some());
+
+        let mut calls = 0;
+        for _ in 0..2 {
+            assert_eq!(
+                cache.resolve("a.usd", &empty, counting_resolve(&mut calls, "/a.usd")),
+                ResolvedPath::new("/a.usd")
+            );
+            cache.resolve("b.usd", &empty, counting_resolve(&mut calls, "/b.usd"));
+            assert_eq!(
+                cache.resolve(
+                    "a.usd",
+                    &other,
+                    counting_resolve(&mut calls, "/other/a.usd")
+                ),
+                ResolvedPath::new("/other/a.usd")
+            );
+        }
+        assert_eq!(calls, 3);
+
+        cache.end_cache_scope(&mut data);
+        assert!(!cache.is_active());
+    }
+
+    #[test]
+    fn test_nested_scopes_share_cache() {
+        let cache = ResolveCache::new();
+        let context = ResolverContext::new();
+        let mut calls = 0;
+
+        let mut outer = CacheScopeData::default();
+        cache.begin_cache_scope(&mut outer);
+        cache.resolve("a.usd", &context, counting_resolve(&mut calls, "/a.usd"));
+        {
+            let mut inner = CacheScopeData::default();
+            cache.begin_cache_scope(&mut inner);
+            cache.resolve("a.usd", &context, counting_resolve(&mut calls, "/a.usd"));
+            cache.resolve("b.usd", &context, counting_resolve(&mut calls, "/b.usd"));
+            cache.end_cache_scope(&mut inner);
+        }
+        assert!(cache.is_active());
+        cache.resolve("b.usd", &context, counting_resolve(&mut calls, "/b.usd"));
+        cache.end_cache_scope(&mut outer);
+
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn test_scope_data_of_other_cache_ignored() {
+        let cache = ResolveCache::new();
+        let other = ResolveCache::new();
+        let context = ResolverContext::new();
+        let mut calls = 0;
+
+        let mut data = CacheScopeData::default();
+        cache.begin_cache_scope(&mut data);
+        cache.resolve("a.usd", &context, counting_resolve(&mut calls, "/a.usd"));
+
+        let mut other_data = data.clone();
+        other.begin_cache_scope(&mut other_data);
+        assert_eq!(
+            other.resolve("a.usd", &context, counting_resolve(&mut calls, "/b/a.usd")),
+            ResolvedPath::new("/b/a.usd")
+        );
+        assert!(!Arc::ptr_eq(
+            data.as_ref().unwrap(),
+            other_data.as_ref().unwrap()
+        ));
+        other.end_cache_scope(&mut other_data);
+
+        assert_eq!(
+            cache.resolve("a.usd", &context, counting_resolve(&mut calls, "/a.usd")),
+            ResolvedPath::new("/a.usd")
+        );
+        cache.end_cache_scope(&mut data);
+        assert_eq!(calls, 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_scope_data_shared_across_threads() {
+        let cache = ResolveCache::new();
+        let context = ResolverContext::new();
+
+        let mut data = CacheScopeData::default();
+        cache.begin_cache_scope(&mut data);
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let mut worker_data = data.clone();
+                cache.begin_cache_scope(&mut worker_data);
+                let mut calls = 0;
+                cache.resolve("a.usd", &context, counting_resolve(&mut calls, "/a.usd"));
+                cache.end_cache_scope(&mut worker_data);
+                assert_eq!(calls, 1);
+            });
+        });
+
+        let mut calls = 0;
+        cache.resolve("a.usd", &context, counting_resolve(&mut calls, "/a.usd"));
+        assert_eq!(calls, 0);
+        cache.end_cache_scope(&mut data);
+    }
+}
@@ -0,0 +1,137 @@
+use core::fmt;
+use core::marker::PhantomData;
+
+use super::{get_resolver, Resolver};
+use crate::ResolverContext;
+
+/// Helper object for managing the binding and unbinding of a
+/// [`ResolverContext`] with the configured resolver.
+///
+/// The context is bound with [`Resolver::bind_context`] when the binder is
+/// created and unbound with [`Resolver::unbind_context`] when it is dropped.
+/// Bindings are per-thread: nested binders restore the previously bound
+/// context when they go out of scope, and other threads are unaffected.
+/// Binders therefore cannot be sent to another thread.
+///
+/// # Examples
+/// ```
+/// use ar::{DefaultResolver, DefaultResolverContext, Resolver, ResolverContext, ResolverContextBinder};
+///
+/// let resolver = DefaultResolver::new();
+/// let mut context = ResolverContext::new();
+/// context.push(DefaultResolverContext::new(&["/assets"]));
+///
+/// {
+///     let _binder = ResolverContextBinder::with_resolver(&resolver, context.clone());
+///     assert_eq!(resolver.get_current_context(), context);
+/// }
+/// assert!(resolver.get_current_context().is_empty());
+/// ```
+pub struct ResolverContextBinder<'a> {
+    resolver: &'a dyn Resolver,
+    context: ResolverContext,
+    // Contexts are bound to the creating thread and must be unbound there.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ResolverContextBinder<'static> {
+    /// Binds `context` to the resolver returned by [`get_resolver`].
+    pub fn new(context: ResolverContext) -> Self {
+        Self::with_resolver(get_resolver(), context)
+    }
+}
+
+impl<'a> ResolverContextBinder<'a> {
+    /// Binds `context` to the given `resolver`.
+    pub fn with_resolver(resolver: &'a dyn Resolver, context: ResolverContext) -> Self {
+        resolver.bind_context(&context, &());
+        Self {
+            resolver,
+            context,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the context bound by this binder.
+    pub fn get_context(&self) -> &ResolverContext {
+        &self.context
+    }
+}
+
+impl Drop for ResolverContextBinder<'_> {
+    fn drop(&mut self) {
+        self.resolver.unbind_context(&self.context, &());
+    }
+}
+
+impl fmt::Debug for ResolverContextBinder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResolverContextBinder")
+            .field("context", &self.context)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DefaultResolver, DefaultResolverContext};
+
+    fn search_path_context(path: &str) -> ResolverContext {
+        let mut context = ResolverContext::new();
+        context.push(DefaultResolverContext::new(&[path]));
+        context
+    }
+
+    #[test]
+    fn test_nested_bindings() {
+        let resolver = DefaultResolver::new();
+        let outer = search_path_context("/outer");
+        let inner = search_path_context("/inner");
+
+        {
+            let _outer = ResolverContextBinder::with_resolver(&resolver, outer.clone());
+            assert_eq!(resolver.get_current_context(), outer);
+            {
+                let _inner = ResolverContextBinder::with_resolver(&resolver, inner.clone());
+                assert_eq!(resolver.get_current_context(), inner);
+            }
+            assert_eq!(resolver.get_current_context(), outer);
+        }
+        assert!(resolver.get_current_context().is_empty());
+    }
+
+    #[test]
+    fn test_bindings_are_per_resolver() {
+        let resolver = DefaultResolver::new();
+        let other = DefaultResolver::new();
+
+        let _binder = ResolverContextBinder::with_resolver(&resolver, search_path_context("/a"));
+        assert!(!resolver.get_current_context().is_empty());
+        assert!(other.get_current_context().is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_bindings_are_per_thread() {
+        use std::sync::Barrier;
+
+        let resolver = DefaultResolver::new();
+        let barrier = Barrier::new(2);
+
+        std::thread::scope(|scope| {
+            for path in ["/a", "/b"] {
+                let resolver = &resolver;
+                let barrier = &barrier;
+                scope.spawn(move || {
+                    let context = search_path_context(path);
+                    let _binder = ResolverContextBinder::with_resolver(resolver, context.clone());
+                    // Both threads have bound their context at this point.
+                    barrier.wait();
+                    assert_eq!(resolver.get_current_context(), context);
+                });
+            }
+        });
+        assert!(resolver.get_current_context().is_empty());
+    }
+}
@@ -0,0 +1,91 @@
+use core::fmt;
+use core::marker::PhantomData;
+
+use super::{get_resolver, CacheScopeData, Resolver};
+
+/// Helper object for managing asset resolver cache scopes.
+///
+/// A scoped resolution cache indicates to the resolver that results of
+/// calls to resolve should be cached for a certain scope. This is important
+/// for performance and also for consistency -- it ensures that repeated
+/// calls to resolve with the same parameters will return the same result.
+///
+/// A resolution cache scope is opened when this object is created and
+/// closed when it is dropped, see [`Resolver::begin_cache_scope`] and
+/// [`Resolver::end_cache_scope`]. Scopes are per-thread, so the object
+/// cannot be sent to another thread. To share a scope's cache with worker
+/// threads, pass the data returned by [`Self::get_cache_scope_data`] to
+/// [`Self::with_parent`] on each worker.
+///
+/// # Examples
+/// ```
+/// use ar::{DefaultResolver, Resolver, ResolverScopedCache};
+///
+/// let resolver = DefaultResolver::new();
+/// let cache = ResolverScopedCache::with_resolver(&resolver, None);
+/// let data = cache.get_cache_scope_data();
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         let _cache = ResolverScopedCache::with_resolver(&resolver, Some(&data));
+///         resolver.resolve("a.usd");
+///     });
+/// });
+/// ```
+pub struct ResolverScopedCache<'a> {
+    resolver: &'a dyn Resolver,
+    cache_scope_data: CacheScopeData,
+    // Cache scopes are opened on the creating thread and must be closed
+    // there.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ResolverScopedCache<'static> {
+    /// Opens a new cache scope on the resolver returned by [`get_resolver`].
+    // Not `Default`, since creating the object opens a scope.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self::with_resolver(get_resolver(), None)
+    }
+
+    /// Opens a cache scope on the resolver returned by [`get_resolver`] that
+    /// shares the cache of the scope that `parent` was taken from.
+    pub fn with_parent(parent: &CacheScopeData) -> Self {
+        Self::with_resolver(get_resolver(), Some(parent))
+    }
+}
+
+impl<'a> ResolverScopedCache<'a> {
+    /// Opens a cache scope on the given `resolver`, sharing the cache of
+    /// `parent` if given.
+    pub fn with_resolver(resolver: &'a dyn Resolver, parent: Option<&CacheScopeData>) -> Self {
+        let mut cache_scope_data = parent.cloned().unwrap_or_default();
+        resolver.begin_cache_scope(&mut cache_scope_data);
+        Self {
+            resolver,
+            cache_scope_data,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Returns the data of this cache scope, which can be sent to other
+    /// threads and passed to [`Self::with_parent`] to share this scope's
+    /// cache.
+    pub fn get_cache_scope_data(&self) -> CacheScopeData {
+        self.cache_scope_data.clone()
+    }
+}
+
+impl Drop for ResolverScopedCache<'_> {
+    fn drop(&mut self) {
+        self.resolver.end_cache_scope(&mut self.cache_scope_data);
+    }
+}
+
+impl fmt::Debug for ResolverScopedCache<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResolverScopedCache")
+            .field("cache_scope_data", &self.cache_scope_data)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,698 @@
+extern crate std;
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+use std::path::Path;
+
+use rusqlite::{params, Connection, DatabaseName, OptionalExtension, TransactionBehavior};
+
+use super::{Resolver, WriteMode};
+use crate::asset::check_read_bounds;
+use crate::package_utils::{find_innermost_packaged_path, split_package_path};
+use crate::path_utils::{self, get_base_name};
+use crate::resolver::default_resolution::{anchor_to, norm_asset_path};
+use crate::sync::{lock, Mutex, TryOnceLock};
+use crate::writable_asset::check_write_buffer;
+use crate::{
+    is_package_relative_path, Asset, AssetError, AssetInfo, ResolvedPath, ResolverError,
+    ResolverInfo, WritableAsset, WritableAssetError,
+};
+
+/// URI schemes served by [`SqliteResolver`], to register it with
+/// [`crate::ResolverWrapper::register_uri_resolver`].
+pub const SQLITE_URI_SCHEMES: &[&str] = &["db"];
+
+/// How long writers wait for other connections to the database to finish
+/// their transactions.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS assets (
+        id INTEGER PRIMARY KEY,
+        path TEXT NOT NULL UNIQUE
+    );
+    CREATE TABLE IF NOT EXISTS versions (
+        id INTEGER PRIMARY KEY,
+        asset_id INTEGER NOT NULL REFERENCES assets(id),
+        version INTEGER NOT NULL,
+        data BLOB NOT NULL,
+        UNIQUE (asset_id, version)
+    );
+    CREATE TABLE IF NOT EXISTS metadata (
+        asset_id INTEGER NOT NULL REFERENCES assets(id),
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (asset_id, key)
+    );
+";
+
+/// Splits the `db:` identifier `identifier` into the path of its asset and
+/// its version, if it has a `@v<number>` qualifier. Returns [`None`] if
+/// `identifier` isn't a `db:` identifier of an asset, or has another
+/// qualifier. Package-relative identifiers must be split beforehand.
+fn parse_identifier(identifier: &str) -> Option<(&str, Option<i64>)> {
+    let path = identifier.strip_prefix("db:")?;
+    let (path, version) = match path.rsplit_once('@') {
+        Some((path, qualifier)) if !qualifier.contains('/') => {
+            let version: i64 = qualifier.strip_prefix('v')?.parse().ok()?;
+            if version <= 0 {
+                return None;
+            }
+            (path, Some(version))
+        }
+        _ => (path, None),
+    };
+    if !path.starts_with('/') || path.ends_with('/') {
+        return None;
+    }
+    Some((path, version))
+}
+
+/// Returns the identifier of `version` of the asset at `path`.
+fn format_identifier(path: &str, version: Option<i64>) -> String {
+    match version {
+        Some(version) => format!("db:{}@v{}", path, version),
+        None => format!("db:{}", path),
+    }
+}
+
+/// Errors for the database operations of a [`SqliteResolver`].
+#[derive(Clone, Debug)]
+pub enum SqliteResolverError {
+    /// The given string is not a `db:` identifier, or has a version where
+    /// none is allowed.
+    InvalidIdentifier(String),
+
+    /// No asset was ever written for the given identifier.
+    UnknownAsset(String),
+
+    /// The database failed for the given reason.
+    Database(String),
+}
+
+impl From<rusqlite::Error> for SqliteResolverError {
+    fn from(err: rusqlite::Error) -> Self {
+        SqliteResolverError::Database(err.to_string())
+    }
+}
+
+impl fmt::Display for SqliteResolverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SqliteResolverError::InvalidIdentifier(identifier) => {
+                write!(f, "invalid database identifier `{}`", identifier)
+            }
+            SqliteResolverError::UnknownAsset(identifier) => {
+                write!(f, "cannot find database asset `{}`", identifier)
+            }
+            SqliteResolverError::Database(reason) => write!(f, "database error: {}", reason),
+        }
+    }
+}
+
+/// Metadata of an asset of a [`SqliteResolver`], attached to the
+/// [`AssetInfo`] returned by [`Resolver::get_asset_info`].
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SqliteAssetMetadata(pub BTreeMap<String, String>);
+
+impl ResolverInfo for SqliteAssetMetadata {}
+
+/// [`Resolver`] implementation for versioned assets stored in a SQLite
+/// database, identified by `db:` identifiers, to register with
+/// [`crate::ResolverWrapper::register_uri_resolver`] for
+/// [`SQLITE_URI_SCHEMES`]. Available with the `sqlite` feature.
+///
+/// Identifiers are absolute paths prefixed with `db:`, e.g.
+/// `db:/char/hero/model`, optionally qualified with a version, e.g.
+/// `db:/char/hero/model@v12`. Relative asset paths are anchored to the
+/// directory of the anchoring identifier, keeping their own qualifier.
+/// Identifiers without a version resolve to their latest version, and
+/// identifiers with one resolve to themselves if that version exists, so
+/// resolved paths always name the version that is read.
+///
+/// Each write of an asset is committed in a transaction as a new version
+/// when the asset is closed, numbered from 1. Versions are never modified,
+/// so the modification timestamp of an asset is its version number, and
+/// assets can only be opened for write through identifiers without a
+/// version. Opened assets read ranges of their version's blob directly from
+/// the database.
+///
+/// [`Resolver::get_asset_info`] returns the version of the resolved asset,
+/// its name, which is the last component of its path, the identifier of its
+/// latest version as its repository path and its metadata as a
+/// [`SqliteAssetMetadata`].
+///
+/// # Examples
+/// ```
+/// use ar::{Resolver, SqliteResolver, WriteMode};
+///
+/// let resolver = SqliteResolver::open_in_memory().unwrap();
+/// resolver.insert("db:/char/hero/model", b"#usda 1.0").unwrap();
+/// resolver.insert("db:/char/hero/model", b"#usda 1.0\n").unwrap();
+///
+/// let resolved_path = resolver.resolve("db:/char/hero/model");
+/// assert_eq!(resolved_path.get_path_string(), "db:/char/hero/model@v2");
+/// let info = resolver.get_asset_info("db:/char/hero/model", &resolved_path);
+/// assert_eq!(info.get_version(), Some("v2"));
+/// assert_eq!(info.get_asset_name(), Some("model"));
+///
+/// let resolved_path = resolver.resolve("db:/char/hero/model@v1");
+/// let asset = resolver.open_asset(&resolved_path).unwrap();
+/// assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+/// ```
+pub struct SqliteResolver {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteResolver {
+    /// Opens the database at `path`, creating it and its tables if needed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SqliteResolverError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Opens a new database held in memory.
+    pub fn open_in_memory() -> Result<Self, SqliteResolverError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, SqliteResolverError> {
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// Stores `data` as a new version of the asset `identifier`, which must
+    /// not have a version. Returns the new version.
+    pub fn insert(&self, identifier: &str, data: &[u8]) -> Result<String, SqliteResolverError> {
+        let path = match parse_identifier(identifier) {
+            Some((path, None)) => path,
+            _ => {
+                return Err(SqliteResolverError::InvalidIdentifier(
+                    identifier.to_owned(),
+                ))
+            }
+        };
+        let version = insert_version(&mut lock(&self.connection), path, data)?;
+        Ok(format!("v{}", version))
+    }
+
+    /// Returns the versions of the asset `identifier`, oldest first.
+    pub fn get_versions(&self, identifier: &str) -> Vec<String> {
+        let path = match parse_identifier(identifier) {
+            Some((path, _)) => path,
+            None => return Vec::new(),
+        };
+        let connection = lock(&self.connection);
+        let versions = connection
+            .prepare_cached(
+                "SELECT version FROM versions JOIN assets ON assets.id = asset_id
+                 WHERE path = ?1 ORDER BY version",
+            )
+            .and_then(|mut statement| {
+                statement
+                    .query_map([path], |row| row.get::<_, i64>(0))?
+                    .map(|version| version.map(|version| format!("v{}", version)))
+                    .collect()
+            });
+        versions.unwrap_or_else(|err| {
+            log::warn!("failed to query the versions of `{}`: {}", identifier, err);
+            Vec::new()
+        })
+    }
+
+    /// Sets the metadata `key` of the asset `identifier` to `value`. The
+    /// asset must have been written, and its metadata is shared by all its
+    /// versions.
+    pub fn set_metadata(
+        &self,
+        identifier: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), SqliteResolverError> {
+        let path = match parse_identifier(identifier) {
+            Some((path, _)) => path,
+            None => {
+                return Err(SqliteResolverError::InvalidIdentifier(
+                    identifier.to_owned(),
+                ))
+            }
+        };
+        let connection = lock(&self.connection);
+        let asset_id: i64 = connection
+            .query_row("SELECT id FROM assets WHERE path = ?1", [path], |row| {
+                row.get(0)
+            })
+            .optional()?
+            .ok_or_else(|| SqliteResolverError::UnknownAsset(identifier.to_owned()))?;
+        connection.execute(
+            "INSERT OR REPLACE INTO metadata (asset_id, key, value) VALUES (?1, ?2, ?3)",
+            params![asset_id, key, value],
+        )?;
+        Ok(())
+    }
+
+    /// Returns the metadata of the asset `identifier`.
+    pub fn get_metadata(&self, identifier: &str) -> BTreeMap<String, String> {
+        let path = match parse_identifier(identifier) {
+            Some((path, _)) => path,
+            None => return BTreeMap::new(),
+        };
+        let connection = lock(&self.connection);
+        let metadata = connection
+            .prepare_cached(
+                "SELECT key, value FROM metadata JOIN assets ON assets.id = asset_id
+                 WHERE path = ?1",
+            )
+            .and_then(|mut statement| {
+                statement
+                    .query_map([path], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect()
+            });
+        metadata.unwrap_or_else(|err| {
+            log::warn!("failed to query the metadata of `{}`: {}", identifier, err);
+            BTreeMap::new()
+        })
+    }
+
+    /// Returns the row ID of `version` of the asset at `path`, or of its
+    /// latest version, along with the version number.
+    fn find_version(
+        &self,
+        path: &str,
+        version: Option<i64>,
+    ) -> Result<Option<(i64, i64)>, rusqlite::Error> {
+        let connection = lock(&self.connection);
+        let mut statement = connection.prepare_cached(
+            "SELECT versions.id, version FROM versions JOIN assets ON assets.id = asset_id
+                 WHERE path = ?1 AND (?2 IS NULL OR version = ?2)
+                 ORDER BY version DESC LIMIT 1",
+        )?;
+        statement
+            .query_row(params![path, version], |row| Ok((row.get(0)?, row.get(1)?)))
+            .optional()
+    }
+}
+
+/// Stores `data` as a new version of the asset at `path` in a transaction,
+/// creating the asset if needed. Returns the new version.
+fn insert_version(
+    connection: &mut Connection,
+    path: &str,
+    data: &[u8],
+) -> Result<i64, rusqlite::Error> {
+    // Take the write lock up front, so concurrent writers cannot pick the
+    // same version.
+    let transaction = connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    transaction.execute("INSERT OR IGNORE INTO assets (path) VALUES (?1)", [path])?;
+    let asset_id: i64 =
+        transaction.query_row("SELECT id FROM assets WHERE path = ?1", [path], |row| {
+            row.get(0)
+        })?;
+    let version: i64 = transaction.query_row(
+        "SELECT COALESCE(MAX(version), 0) + 1 FROM versions WHERE asset_id = ?1",
+        [asset_id],
+        |row| row.get(0),
+    )?;
+    transaction.execute(
+        "INSERT INTO versions (asset_id, version, data) VALUES (?1, ?2, ?3)",
+        params![asset_id, version, data],
+    )?;
+    transaction.commit()?;
+    Ok(version)
+}
+
+impl Resolver for SqliteResolver {
+    fn create_identifier(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: Option<&ResolvedPath>,
+    ) -> String {
+        if let Some(path) = asset_path.strip_prefix("db:") {
+            return format!("db:{}", norm_asset_path(path));
+        }
+        let anchor = anchor_asset_path.map_or("", ResolvedPath::get_path_string);
+        match anchor.strip_prefix("db:") {
+            Some(_) if asset_path.is_empty() => String::new(),
+            Some(anchor) => format!("db:{}", norm_asset_path(&anchor_to(anchor, asset_path))),
+            None => asset_path.to_owned(),
+        }
+    }
+
+    fn create_identifier_for_new_asset(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: &ResolvedPath,
+    ) -> String {
+        self.create_identifier(asset_path, Some(anchor_asset_path))
+    }
+
+    fn resolve(&self, asset_path: &str) -> ResolvedPath {
+        // Assets inside a package exist if the package does.
+        let (package_path, packaged_path) = split_package_path(asset_path);
+        let (path, version) = match parse_identifier(package_path) {
+            Some(parsed) => parsed,
+            None => return ResolvedPath::default(),
+        };
+        match self.find_version(path, version) {
+            Ok(Some((_, version))) => {
+                ResolvedPath::new(&(format_identifier(path, Some(version)) + packaged_path))
+            }
+            Ok(None) => ResolvedPath::default(),
+            Err(err) => {
+                log::warn!("failed to resolve `{}`: {}", asset_path, err);
+                ResolvedPath::default()
+            }
+        }
+    }
+
+    fn resolve_for_new_asset(&self, asset_path: &str) -> ResolvedPath {
+        // New assets are always new versions.
+        match parse_identifier(asset_path) {
+            Some((_, None)) if !is_package_relative_path(asset_path) => {
+                ResolvedPath::new(asset_path)
+            }
+            _ => ResolvedPath::default(),
+        }
+    }
+
+    fn get_extension(&self, asset_path: &str) -> String {
+        // Version qualifiers aren't part of the extension.
+        let path = match find_innermost_packaged_path(asset_path) {
+            Some((begin, end)) => &asset_path[begin..end],
+            None => parse_identifier(asset_path).map_or(asset_path, |(path, _)| path),
+        };
+        path_utils::get_extension(path).to_string()
+    }
+
+    fn get_asset_info(&self, _asset_path: &str, resolved_path: &ResolvedPath) -> AssetInfo {
+        let (package_path, _) = split_package_path(resolved_path.get_path_string());
+        let (path, version) = match parse_identifier(package_path) {
+            Some(parsed) => parsed,
+            None => return AssetInfo::default(),
+        };
+        let repo_path = format_identifier(path, None);
+        let mut info = AssetInfo::new()
+            .with_asset_name(get_base_name(path))
+            .with_repo_path(&repo_path)
+            .with_resolver_info(SqliteAssetMetadata(self.get_metadata(&repo_path)));
+        if let Some(version) = version {
+            info = info.with_version(&format!("v{}", version));
+        }
+        info
+    }
+
+    fn get_modification_timestamp(
+        &self,
+        _asset_path: &str,
+        resolved_path: &ResolvedPath,
+    ) -> Result<i64, ResolverError> {
+        let (package_path, _) = split_package_path(resolved_path.get_path_string());
+        let (path, version) =
+            parse_identifier(package_path).ok_or(ResolverError::AssetMtimeError)?;
+        match self.find_version(path, version) {
+            Ok(Some((_, version))) => Ok(version),
+            _ => Err(ResolverError::AssetMtimeError),
+        }
+    }
+
+    fn open_asset(&self, resolved_path: &ResolvedPath) -> Result<Arc<dyn Asset>, ResolverError> {
+        let identifier = resolved_path.get_path_string();
+        let (path, version) = parse_identifier(identifier).ok_or_else(|| {
+            ResolverError::OpenAssetError(format!("{}: not a database asset", identifier))
+        })?;
+        let row_id = match self.find_version(path, version) {
+            Ok(Some((row_id, _))) => row_id,
+            Ok(None) => return Err(ResolverError::OpenAssetError(identifier.to_owned())),
+            Err(err) => {
+                return Err(ResolverError::OpenAssetError(format!(
+                    "{}: {}",
+                    identifier, err
+                )))
+            }
+        };
+
+        let size: i64 = lock(&self.connection)
+            .query_row(
+                "SELECT length(data) FROM versions WHERE id = ?1",
+                [row_id],
+                |row| row.get(0),
+            )
+            .map_err(|err| ResolverError::OpenAssetError(format!("{}: {}", identifier, err)))?;
+        Ok(Arc::new(SqliteAsset {
+            connection: self.connection.clone(),
+            identifier: identifier.to_owned(),
+            row_id,
+            size: size as usize,
+            buffer: TryOnceLock::new(),
+        }))
+    }
+
+    fn open_asset_for_write(
+        &self,
+        resolved_path: &ResolvedPath,
+        write_mode: WriteMode,
+    ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+        let identifier = resolved_path.get_path_string();
+        let path = match parse_identifier(identifier) {
+            Some((path, None)) if !is_package_relative_path(identifier) => path,
+            Some((_, Some(_))) => {
+                return Err(ResolverError::OpenAssetError(format!(
+                    "{}: versions cannot be modified",
+                    identifier
+                )))
+            }
+            _ => {
+                return Err(ResolverError::OpenAssetError(format!(
+                    "{}: not a database asset",
+                    identifier
+                )))
+            }
+        };
+
+        let data = match write_mode {
+            WriteMode::Update => match self.find_version(path, None) {
+                Ok(Some((row_id, _))) => {
+                    read_blob(&lock(&self.connection), row_id).map_err(|err| {
+                        ResolverError::OpenAssetError(format!("{}: {}", identifier, err))
+                    })?
+                }
+                Ok(None) => Vec::new(),
+                Err(err) => {
+                    return Err(ResolverError::OpenAssetError(format!(
+                        "{}: {}",
+                        identifier, err
+                    )))
+                }
+            },
+            WriteMode::Replace => Vec::new(),
+        };
+        Ok(Box::new(SqliteWritableAsset {
+            connection: self.connection.clone(),
+            path: path.to_owned(),
+            data: Some(data),
+        }))
+    }
+}
+
+impl fmt::Debug for SqliteResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let connection = lock(&self.connection);
+        f.debug_struct("SqliteResolver")
+            .field("path", &connection.path())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads the whole blob of the version with the given row ID.
+fn read_blob(connection: &Connection, row_id: i64) -> Result<Vec<u8>, rusqlite::Error> {
+    connection.query_row("SELECT data FROM versions WHERE id = ?1", [row_id], |row| {
+        row.get(0)
+    })
+}
+
+/// [`Asset`] implementation for a version of an asset of a
+/// [`SqliteResolver`], reading ranges of its blob from the database.
+struct SqliteAsset {
+    connection: Arc<Mutex<Connection>>,
+    identifier: String,
+    row_id: i64,
+    size: usize,
+    buffer: TryOnceLock<Vec<u8>>,
+}
+
+impl SqliteAsset {
+    fn read_error(&self, err: rusqlite::Error) -> AssetError {
+        AssetError::ReadError(format!("{}: {}", self.identifier, err))
+    }
+}
+
+impl Asset for SqliteAsset {
+    fn get_size(&self) -> usize {
+        self.size
+    }
+
+    fn get_buffer(&self) -> Result<&[u8], AssetError> {
+        self.buffer
+            .get_or_try_init(|| {
+                read_blob(&lock(&self.connection), self.row_id).map_err(|err| self.read_error(err))
+            })
+            .map(Vec::as_slice)
+    }
+
+    fn read(&self, buffer: &mut [u8], count: usize, offset: usize) -> Result<usize, AssetError> {
+        check_read_bounds(buffer.len(), count, offset, self.size)?;
+        if let Some(data) = self.buffer.get() {
+            buffer[..count].copy_from_slice(&data[offset..offset + count]);
+            return Ok(count);
+        }
+
+        let connection = lock(&self.connection);
+        let blob = connection
+            .blob_open(DatabaseName::Main, "versions", "data", self.row_id, true)
+            .map_err(|err| self.read_error(err))?;
+        blob.read_at_exact(&mut buffer[..count], offset)
+            .map_err(|err| self.read_error(err))?;
+        Ok(count)
+    }
+}
+
+impl fmt::Debug for SqliteAsset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqliteAsset")
+            .field("identifier", &self.identifier)
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// [`WritableAsset`] implementation for an asset of a [`SqliteResolver`].
+///
+/// Writes are buffered and committed as a new version of the asset when the
+/// asset is closed. Assets dropped without being closed leave the database
+/// untouched.
+struct SqliteWritableAsset {
+    connection: Arc<Mutex<Connection>>,
+    path: String,
+    data: Option<Vec<u8>>,
+}
+
+impl WritableAsset for SqliteWritableAsset {
+    fn close(&mut self) -> Result<(), WritableAssetError> {
+        let data = self.data.take().ok_or_else(|| {
+            WritableAssetError::CloseFailed("asset is already closed".to_string())
+        })?;
+        insert_version(&mut lock(&self.connection), &self.path, &data)
+            .map(|_| ())
+            .map_err(|err| {
+                WritableAssetError::CloseFailed(format!(
+                    "{}: {}",
+                    format_identifier(&self.path, None),
+                    err
+                ))
+            })
+    }
+
+    fn write(
+        &mut self,
+        buffer: &[u8],
+        count: usize,
+        offset: usize,
+    ) -> Result<usize, WritableAssetError> {
+        let data = self
+            .data
+            .as_mut()
+            .ok_or_else(|| WritableAssetError::WriteFailed("asset is closed".to_string()))?;
+        check_write_buffer(buffer.len(), count)?;
+        let end = offset
+            .checked_add(count)
+            .ok_or_else(|| WritableAssetError::WriteFailed("offset is too large".to_string()))?;
+
+        if data.len() < end {
+            data.resize(end, 0);
+        }
+        data[offset..end].copy_from_slice(&buffer[..count]);
+        Ok(count)
+    }
+}
+
+impl fmt::Debug for SqliteWritableAsset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqliteWritableAsset")
+            .field("path", &self.path)
+            .field("closed", &self.data.is_none())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_identifier() {
+        assert_eq!(
+            parse_identifier("db:/char/hero/model"),
+            Some(("/char/hero/model", None))
+        );
+        assert_eq!(
+            parse_identifier("db:/char/hero/model@v12"),
+            Some(("/char/hero/model", Some(12)))
+        );
+        assert_eq!(
+            parse_identifier("db:/char/hero@2/model"),
+            Some(("/char/hero@2/model", None))
+        );
+        assert_eq!(parse_identifier("db:/char/hero/model@v0"), None);
+        assert_eq!(parse_identifier("db:/char/hero/model@latest"), None);
+        assert_eq!(parse_identifier("db:char/hero/model"), None);
+        assert_eq!(parse_identifier("db:/"), None);
+        assert_eq!(parse_identifier("/char/hero/model"), None);
+    }
+
+    #[test]
+    fn test_create_identifier() {
+        let resolver = SqliteResolver::open_in_memory().unwrap();
+        let anchor = ResolvedPath::new("db:/char/hero/model@v12");
+        assert_eq!(
+            resolver.create_identifier("db:/char/./hero/../hero/rig@v3", None),
+            "db:/char/hero/rig@v3"
+        );
+        assert_eq!(
+            resolver.create_identifier("./rig", Some(&anchor)),
+            "db:/char/hero/rig"
+        );
+        assert_eq!(
+            resolver.create_identifier("../villain/model@v1", Some(&anchor)),
+            "db:/char/villain/model@v1"
+        );
+        assert_eq!(
+            resolver.create_identifier("/sets/city", Some(&anchor)),
+            "db:/sets/city"
+        );
+        assert_eq!(resolver.create_identifier("", Some(&anchor)), "");
+        assert_eq!(
+            resolver.create_identifier("./rig", Some(&ResolvedPath::new("/a/b.usda"))),
+            "./rig"
+        );
+    }
+
+    #[test]
+    fn test_get_extension() {
+        let resolver = SqliteResolver::open_in_memory().unwrap();
+        assert_eq!(resolver.get_extension("db:/props/chair.usdz@v3"), "usdz");
+        assert_eq!(resolver.get_extension("db:/props/chair.usdz"), "usdz");
+        assert_eq!(
+            resolver.get_extension("db:/props/chair.usdz@v3[chair.usda]"),
+            "usda"
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+type Stacks = BTreeMap<usize, Vec<Box<dyn Any + Send>>>;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "std")] {
+        extern crate std;
+        use core::cell::RefCell;
+
+        std::thread_local! {
+            static STACKS: RefCell<Stacks> = const { RefCell::new(BTreeMap::new()) };
+        }
+
+        fn with_stacks<R>(f: impl FnOnce(&mut Stacks) -> R) -> Option<R> {
+            // Fails only while the thread is being torn down.
+            STACKS.try_with(|stacks| f(&mut stacks.borrow_mut())).ok()
+        }
+    } else {
+        use crate::sync::{lock, Mutex};
+
+        // Without `std` there are no thread-locals, so all threads share the
+        // same stacks.
+        static STACKS: Mutex<Stacks> = Mutex::new(BTreeMap::new());
+
+        fn with_stacks<R>(f: impl FnOnce(&mut Stacks) -> R) -> Option<R> {
+            Some(f(&mut lock(&STACKS)))
+        }
+    }
+}
+
+static NEXT_STACK_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Stack owned by a single object, such as a resolver, whose contents are
+/// kept separately for each thread.
+#[derive(Debug)]
+pub(crate) struct ThreadLocalStack<T> {
+    id: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Clone + Send + 'static> ThreadLocalStack<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            id: NEXT_STACK_ID.fetch_add(1, Ordering::Relaxed),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the identifier of the stack, which is unique in the process.
+    pub(crate) fn get_id(&self) -> usize {
+        self.id
+    }
+
+    /// Pushes `value` onto the current thread's stack.
+    pub(crate) fn push(&self, value: T) {
+        with_stacks(|stacks| stacks.entry(self.id).or_default().push(Box::new(value)));
+    }
+
+    /// Pops the top of the current thread's stack.
+    pub(crate) fn pop(&self) -> Option<T> {
+        // The popped value is returned, and so dropped, outside of
+        // `with_stacks` in case dropping it touches the stacks again.
+        let popped = with_stacks(|stacks| {
+            let stack = stacks.get_mut(&self.id)?;
+            let popped = stack.pop();
+            if stack.is_empty() {
+                stacks.remove(&self.id);
+            }
+            popped
+        })
+        .flatten()?;
+        popped.downcast::<T>().ok().map(|value| *value)
+    }
+
+    /// Returns a copy of the top of the current thread's stack.
+    pub(crate) fn top(&self) -> Option<T> {
+        with_stacks(|stacks| {
+            stacks
+                .get(&self.id)
+                .and_then(|stack| stack.last())
+                .and_then(|value| value.downcast_ref::<T>())
+                .cloned()
+        })
+        .flatten()
+    }
+}
+
+impl<T> Drop for ThreadLocalStack<T> {
+    fn drop(&mut self) {
+        // Values still on other threads' stacks are released when those
+        // threads exit.
+        let _stack = with_stacks(|stacks| stacks.remove(&self.id));
+    }
+}
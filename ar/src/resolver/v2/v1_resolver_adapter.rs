@@ -0,0 +1,542 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::any::{Any, TypeId};
+
+use super::{CacheScopeData, Resolver, WriteMode};
+use crate::resolver::default_resolution::open_filesystem_asset_for_write;
+use crate::resolver::v1;
+use crate::sync::{into_inner, lock, Mutex};
+use crate::{
+    Asset, AssetInfo, ClientContext, ResolvedPath, ResolverContext, ResolverError, WritableAsset,
+};
+
+/// Converts an Ar 2.0 resolver context into an Ar 1.0 one, which holds a
+/// single context object. Only the object of type `context_type` is kept if
+/// it is given, otherwise contexts holding several objects are converted to
+/// an empty context.
+fn to_v1_context(context: &ResolverContext, context_type: Option<TypeId>) -> v1::ResolverContext {
+    let mut objects = context.clone().into_iter();
+    let object = match context_type {
+        Some(context_type) => objects.find(|object| (**object).as_any().type_id() == context_type),
+        None => {
+            let object = objects.next();
+            if objects.next().is_some() {
+                log::warn!(
+                    "ignoring resolver context holding several objects, \
+                     see V1ResolverAdapter::with_context_type"
+                );
+                return v1::ResolverContext::from_boxed(None);
+            }
+            object
+        }
+    };
+    v1::ResolverContext::from_boxed(object)
+}
+
+/// Converts an Ar 1.0 resolver context into an Ar 2.0 one.
+fn from_v1_context(context: v1::ResolverContext) -> ResolverContext {
+    let mut resolver_context = ResolverContext::new();
+    if let Some(context) = context.into_boxed() {
+        resolver_context.insert(context, true);
+    }
+    resolver_context
+}
+
+/// Presents an Ar 1.0 [`v1::Resolver`] as an Ar 2.0 [`Resolver`], so legacy
+/// resolvers can be used alongside, or registered with, Ar 2.0 code.
+///
+/// The calls are mapped as follows:
+/// - Identifiers are created by anchoring relative paths with
+///   [`v1::Resolver::anchor_relative_path`] and normalizing the result with
+///   [`v1::Resolver::compute_normalized_path`].
+/// - [`Resolver::resolve`] forwards to [`v1::Resolver::resolve`], and
+///   [`Resolver::resolve_for_new_asset`] to
+///   [`v1::Resolver::compute_local_path`].
+/// - Asset info is filled in by [`v1::Resolver::resolve_with_asset_info`].
+/// - Search paths are considered context-dependent.
+/// - Ar 1.0 contexts hold a single context object, so only the object of the
+///   type given to [`V1ResolverAdapter::with_context_type`] is passed on from
+///   a [`ResolverContext`]. If no type is given, contexts holding a single
+///   object are passed on as is and contexts holding several objects are
+///   ignored: an empty context is passed on instead.
+/// - Assets are written directly to the filesystem after
+///   [`v1::Resolver::can_write_layer_to_path`] and
+///   [`v1::Resolver::create_path_for_layer`], as Ar 1.0 resolvers don't
+///   provide writable assets.
+///
+/// Ar 1.0 resolvers take `&mut self` for binding contexts and cache scopes,
+/// so the wrapped resolver is kept behind a lock. They also keep a single
+/// stack of bound contexts, so unlike with Ar 2.0 resolvers, contexts bound
+/// through the adapter are process-wide rather than per-thread: they apply
+/// to every thread until they are unbound.
+#[derive(Debug)]
+pub struct V1ResolverAdapter<R> {
+    resolver: Mutex<R>,
+    context_type: Option<TypeId>,
+}
+
+impl<R: v1::Resolver> V1ResolverAdapter<R> {
+    /// Creates an adapter presenting `resolver` as an Ar 2.0 resolver.
+    pub fn new(resolver: R) -> Self {
+        Self {
+            resolver: Mutex::new(resolver),
+            context_type: None,
+        }
+    }
+
+    /// Sets the type of the context object expected by the wrapped resolver,
+    /// which is the only one passed on from the contexts given to the
+    /// adapter.
+    pub fn with_context_type<Context: ClientContext + 'static>(mut self) -> Self {
+        self.context_type = Some(TypeId::of::<Context>());
+        self
+    }
+
+    /// Returns the wrapped resolver.
+    pub fn into_inner(self) -> R {
+        into_inner(self.resolver)
+    }
+}
+
+impl<R: v1::Resolver> Resolver for V1ResolverAdapter<R> {
+    fn create_identifier(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: Option<&ResolvedPath>,
+    ) -> String {
+        let resolver = lock(&self.resolver);
+        match anchor_asset_path {
+            Some(anchor_asset_path) if !anchor_asset_path.is_empty() => {
+                let anchored_path =
+                    resolver.anchor_relative_path(anchor_asset_path.get_path_string(), asset_path);
+                resolver.compute_normalized_path(&anchored_path)
+            }
+            _ => resolver.compute_normalized_path(asset_path),
+        }
+    }
+
+    fn create_identifier_for_new_asset(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: &ResolvedPath,
+    ) -> String {
+        self.create_identifier(asset_path, Some(anchor_asset_path))
+    }
+
+    fn resolve(&self, asset_path: &str) -> ResolvedPath {
+        ResolvedPath::new(&lock(&self.resolver).resolve(asset_path))
+    }
+
+    fn resolve_for_new_asset(&self, asset_path: &str) -> ResolvedPath {
+        ResolvedPath::new(&lock(&self.resolver).compute_local_path(asset_path))
+    }
+
+    fn bind_context(&self, context: &ResolverContext, binding_data: &dyn Any) {
+        lock(&self.resolver).bind_context(&to_v1_context(context, self.context_type), binding_data);
+    }
+
+    fn unbind_context(&self, context: &ResolverContext, binding_data: &dyn Any) {
+        lock(&self.resolver)
+            .unbind_context(&to_v1_context(context, self.context_type), binding_data);
+    }
+
+    fn create_default_context(&self) -> ResolverContext {
+        from_v1_context(lock(&self.resolver).create_default_context())
+    }
+
+    fn create_default_context_for_asset(&self, asset_path: &str) -> ResolverContext {
+        from_v1_context(lock(&self.resolver).create_default_context_for_asset(asset_path))
+    }
+
+    fn refresh_context(&self, context: &ResolverContext) {
+        lock(&self.resolver).refresh_context(&to_v1_context(context, self.context_type));
+    }
+
+    fn get_current_context(&self) -> ResolverContext {
+        from_v1_context(lock(&self.resolver).get_current_context())
+    }
+
+    fn is_context_dependent_path(&self, asset_path: &str) -> bool {
+        lock(&self.resolver).is_search_path(asset_path)
+    }
+
+    fn get_extension(&self, asset_path: &str) -> String {
+        lock(&self.resolver).get_extension(asset_path)
+    }
+
+    fn get_asset_info(&self, asset_path: &str, _resolved_path: &ResolvedPath) -> AssetInfo {
+        let mut asset_info = AssetInfo::new();
+        lock(&self.resolver).resolve_with_asset_info(asset_path, Some(&mut asset_info));
+        asset_info
+    }
+
+    fn get_modification_timestamp(
+        &self,
+        asset_path: &str,
+        resolved_path: &ResolvedPath,
+    ) -> Result<i64, ResolverError> {
+        lock(&self.resolver).get_modification_timestamp(asset_path, resolved_path.get_path_string())
+    }
+
+    fn open_asset(&self, resolved_path: &ResolvedPath) -> Result<Arc<dyn Asset>, ResolverError> {
+        lock(&self.resolver).open_asset(resolved_path.get_path_string())
+    }
+
+    fn open_asset_for_write(
+        &self,
+        resolved_path: &ResolvedPath,
+        write_mode: WriteMode,
+    ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+        let path = resolved_path.get_path_string();
+        {
+            let resolver = lock(&self.resolver);
+            resolver.can_write_layer_to_path(path)?;
+            resolver.create_path_for_layer(path)?;
+        }
+        open_filesystem_asset_for_write(path, write_mode)
+    }
+
+    fn begin_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        lock(&self.resolver).begin_cache_scope(cache_scope_data);
+    }
+
+    fn end_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        lock(&self.resolver).end_cache_scope(cache_scope_data);
+    }
+
+    fn configure_resolver_for_asset(&self, path: &str) {
+        lock(&self.resolver).configure_resolver_for_asset(path);
+    }
+
+    fn anchor_relative_path(&self, anchor_path: &str, path: &str) -> String {
+        lock(&self.resolver).anchor_relative_path(anchor_path, path)
+    }
+
+    fn is_relative_path(&self, path: &str) -> bool {
+        lock(&self.resolver).is_relative_path(path)
+    }
+
+    fn is_search_path(&self, path: &str) -> bool {
+        lock(&self.resolver).is_search_path(path)
+    }
+
+    fn is_repository_path(&self, path: &str) -> bool {
+        lock(&self.resolver).is_repository_path(path)
+    }
+
+    fn fetch_to_local_resolved_path(&self, path: &str, resolved_path: &str) -> bool {
+        lock(&self.resolver).fetch_to_local_resolved_path(path, resolved_path)
+    }
+
+    fn create_path_for_layer(&self, path: &str) -> Result<(), ResolverError> {
+        lock(&self.resolver).create_path_for_layer(path)
+    }
+
+    fn can_write_layer_to_path(&self, path: &str) -> Result<(), ResolverError> {
+        lock(&self.resolver).can_write_layer_to_path(path)
+    }
+
+    fn can_create_new_layer_with_identifier(&self, identifier: &str) -> Result<(), ResolverError> {
+        lock(&self.resolver).can_create_new_layer_with_identifier(identifier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::BTreeMap;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    use crate::{ClientContext, InMemoryAsset};
+
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct ShotContext(&'static str);
+
+    impl ClientContext for ShotContext {}
+
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct UserContext(&'static str);
+
+    impl ClientContext for UserContext {}
+
+    /// Ar 1.0 resolver looking assets up in a fixed table, and recording the
+    /// calls made to it.
+    #[derive(Debug, Default)]
+    struct TableResolver {
+        assets: BTreeMap<&'static str, &'static str>,
+        bound_contexts: Vec<v1::ResolverContext>,
+        cache_scopes: usize,
+    }
+
+    impl v1::Resolver for TableResolver {
+        fn configure_resolver_for_asset(&mut self, _path: &str) {}
+
+        fn anchor_relative_path(&self, anchor_path: &str, path: &str) -> String {
+            format!("{}/../{}", anchor_path, path)
+        }
+
+        fn is_relative_path(&self, path: &str) -> bool {
+            !path.starts_with('/')
+        }
+
+        fn is_repository_path(&self, path: &str) -> bool {
+            path.starts_with("repo:")
+        }
+
+        fn is_search_path(&self, path: &str) -> bool {
+            !path.starts_with('/') && !path.starts_with('.')
+        }
+
+        fn get_extension(&self, path: &str) -> String {
+            path.rsplit('.').next().unwrap_or_default().to_string()
+        }
+
+        fn compute_normalized_path(&self, path: &str) -> String {
+            path.replace("/shot.usd/..", "")
+        }
+
+        fn compute_repository_path(&self, path: &str) -> String {
+            format!("repo:{}", path)
+        }
+
+        fn compute_local_path(&self, path: &str) -> String {
+            format!("/local/{}", path)
+        }
+
+        fn resolve(&self, path: &str) -> String {
+            self.resolve_with_asset_info(path, None)
+        }
+
+        fn bind_context(&mut self, context: &v1::ResolverContext, _binding_data: &dyn Any) {
+            self.bound_contexts.push(context.clone());
+        }
+
+        fn unbind_context(&mut self, context: &v1::ResolverContext, _binding_data: &dyn Any) {
+            assert_eq!(self.bound_contexts.pop().as_ref(), Some(context));
+        }
+
+        fn create_default_context(&self) -> v1::ResolverContext {
+            v1::ResolverContext::new(ShotContext("default"))
+        }
+
+        fn create_default_context_for_asset(&self, _file_path: &str) -> v1::ResolverContext {
+            v1::ResolverContext::new(ShotContext("asset"))
+        }
+
+        fn refresh_context(&mut self, _context: &v1::ResolverContext) {}
+
+        fn get_current_context(&self) -> v1::ResolverContext {
+            self.bound_contexts.last().cloned().unwrap_or_default()
+        }
+
+        fn resolve_with_asset_info(
+            &self,
+            path: &str,
+            asset_info: Option<&mut AssetInfo>,
+        ) -> String {
+            let resolved_path = self.assets.get(path).copied().unwrap_or_default();
+            if let Some(asset_info) = asset_info {
+                *asset_info = AssetInfo::new().with_repo_path(&self.compute_repository_path(path));
+            }
+            resolved_path.to_string()
+        }
+
+        fn update_asset_info(
+            &self,
+            _identifier: &str,
+            _file_path: &str,
+            _file_version: &str,
+            _asset_info: &mut AssetInfo,
+        ) {
+        }
+
+        fn get_modification_timestamp(
+            &self,
+            _path: &str,
+            resolved_path: &str,
+        ) -> Result<i64, ResolverError> {
+            Ok(resolved_path.len() as i64)
+        }
+
+        fn fetch_to_local_resolved_path(&self, _path: &str, _resolved_path: &str) -> bool {
+            true
+        }
+
+        fn open_asset(&self, resolved_path: &str) -> Result<Arc<dyn Asset>, ResolverError> {
+            match resolved_path {
+                "/assets/chair.usd" => Ok(Arc::new(InMemoryAsset::from(b"#usda 1.0".to_vec()))),
+                _ => Err(ResolverError::OpenAssetError(resolved_path.to_string())),
+            }
+        }
+
+        fn create_path_for_layer(&self, _path: &str) -> Result<(), ResolverError> {
+            Ok(())
+        }
+
+        fn can_write_layer_to_path(&self, path: &str) -> Result<(), ResolverError> {
+            Err(ResolverError::CannotWriteLayerToPath(
+                path.to_string(),
+                "read-only".to_string(),
+            ))
+        }
+
+        fn can_create_new_layer_with_identifier(
+            &self,
+            _identifier: &str,
+        ) -> Result<(), ResolverError> {
+            Ok(())
+        }
+
+        fn begin_cache_scope(&mut self, _cache_scope_data: &mut CacheScopeData) {
+            self.cache_scopes += 1;
+        }
+
+        fn end_cache_scope(&mut self, _cache_scope_data: &mut CacheScopeData) {
+            self.cache_scopes -= 1;
+        }
+    }
+
+    fn adapter() -> V1ResolverAdapter<TableResolver> {
+        let mut resolver = TableResolver::default();
+        resolver.assets.insert("chair.usd", "/assets/chair.usd");
+        resolver.assets.insert("table.usd", "/assets/table.usd");
+        V1ResolverAdapter::new(resolver)
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_resolve() {
+        let adapter = adapter();
+        assert_eq!(
+            adapter.resolve("chair.usd"),
+            ResolvedPath::new("/assets/chair.usd")
+        );
+        assert!(adapter.resolve("missing.usd").is_empty());
+        assert_eq!(
+            adapter.resolve_for_new_asset("new.usd"),
+            ResolvedPath::new("/local/new.usd")
+        );
+
+        let anchor = ResolvedPath::new("/shots/s01/shot.usd");
+        assert_eq!(
+            adapter.create_identifier("layout.usd", Some(&anchor)),
+            "/shots/s01/layout.usd"
+        );
+        assert_eq!(adapter.create_identifier("layout.usd", None), "layout.usd");
+        assert!(adapter.is_context_dependent_path("layout.usd"));
+        assert!(!adapter.is_context_dependent_path("/shots/s01/layout.usd"));
+        assert!(adapter.is_repository_path("repo:chair.usd"));
+        assert_eq!(adapter.get_extension("chair.usda"), "usda");
+
+        let resolved_path = adapter.resolve("table.usd");
+        assert_eq!(
+            adapter.get_asset_info("table.usd", &resolved_path),
+            AssetInfo::new().with_repo_path("repo:table.usd")
+        );
+        assert_eq!(
+            adapter
+                .get_modification_timestamp("table.usd", &resolved_path)
+                .unwrap(),
+            17
+        );
+        assert!(adapter.can_write_layer_to_path("/a.usd").is_err());
+    }
+
+    #[test]
+    fn test_open_asset() {
+        let adapter = adapter();
+        let asset = adapter
+            .open_asset(&ResolvedPath::new("/assets/chair.usd"))
+            .unwrap();
+        assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+        assert!(adapter
+            .open_asset(&ResolvedPath::new("/assets/table.usd"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_open_asset_for_write_checks_write_permission() {
+        // The table resolver refuses every write.
+        let adapter = adapter();
+        let err = adapter
+            .open_asset_for_write(&ResolvedPath::new("/assets/new.usd"), WriteMode::Replace)
+            .err()
+            .unwrap();
+        assert!(
+            matches!(err, ResolverError::CannotWriteLayerToPath(ref path, _) if path == "/assets/new.usd"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_contexts() {
+        let adapter = adapter();
+        let default_context = adapter.create_default_context();
+        assert_eq!(
+            default_context.get::<ShotContext>(),
+            Some(&ShotContext("default"))
+        );
+        assert_eq!(
+            adapter
+                .create_default_context_for_asset("/a.usd")
+                .get::<ShotContext>(),
+            Some(&ShotContext("asset"))
+        );
+
+        assert!(adapter.get_current_context().is_empty());
+        let mut context = ResolverContext::new();
+        context.push(ShotContext("s01"));
+        adapter.bind_context(&context, &());
+        assert_eq!(adapter.get_current_context(), context);
+        adapter.unbind_context(&context, &());
+        assert!(adapter.get_current_context().is_empty());
+    }
+
+    #[test]
+    fn test_context_type() {
+        let mut context = ResolverContext::new();
+        context.push(ShotContext("s01"));
+        context.push(UserContext("jane"));
+
+        // Without a context type, the adapter cannot choose an object.
+        let adapter = adapter();
+        adapter.bind_context(&context, &());
+        assert!(adapter.get_current_context().is_empty());
+        adapter.unbind_context(&context, &());
+
+        let adapter = adapter.with_context_type::<ShotContext>();
+        adapter.bind_context(&context, &());
+        let mut expected = ResolverContext::new();
+        expected.push(ShotContext("s01"));
+        assert_eq!(adapter.get_current_context(), expected);
+        adapter.unbind_context(&context, &());
+        assert!(adapter.get_current_context().is_empty());
+    }
+
+    #[test]
+    fn test_cache_scopes() {
+        let adapter = adapter();
+        let mut data: CacheScopeData = None;
+        adapter.begin_cache_scope(&mut data);
+        adapter.begin_cache_scope(&mut data);
+        assert_eq!(lock(&adapter.resolver).cache_scopes, 2);
+        adapter.end_cache_scope(&mut data);
+        adapter.end_cache_scope(&mut data);
+        assert_eq!(adapter.into_inner().cache_scopes, 0);
+    }
+
+    #[test]
+    fn test_default_resolver() {
+        let adapter = V1ResolverAdapter::new(v1::DefaultResolver::new());
+        let anchor = ResolvedPath::new("/shots/s01/shot.usd");
+        assert_eq!(
+            adapter.create_identifier("./layout.usd", Some(&anchor)),
+            "/shots/s01/layout.usd"
+        );
+        assert_eq!(adapter.create_identifier("/a//b.usd", None), "/a/b.usd");
+        assert!(adapter.resolve("/missing/a.usd").is_empty());
+    }
+}
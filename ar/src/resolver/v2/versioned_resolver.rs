@@ -0,0 +1,651 @@
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::fmt;
+
+use super::context_stack::ContextStack;
+use super::{CacheScopeData, Resolver, ResolverContext, WriteMode};
+use crate::package_utils::split_package_path;
+use crate::sync::{lock, Mutex};
+use crate::{
+    Asset, AssetInfo, LockedResolution, Lockfile, ResolvedPath, ResolverError, WritableAsset,
+};
+
+/// Version qualifier ending a versioned asset path, after an `@`, e.g.
+/// `char/hero.usd@latest`. See [`VersionedResolver`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum VersionQualifier {
+    /// `@latest`, the highest version of the asset.
+    Latest,
+    /// `@v<number>`, e.g. `@v12`, the version with the given number.
+    Number(u32),
+    /// Any other `@<tag>`, e.g. `@approved`, the version the tag points to.
+    /// Tags are made of ASCII letters, digits, `-` and `_`.
+    Tag(String),
+}
+
+impl VersionQualifier {
+    /// Parses the qualifier following the `@` of a versioned asset path.
+    /// Returns [`None`] if `qualifier` is not a valid qualifier.
+    pub fn parse(qualifier: &str) -> Option<Self> {
+        if qualifier == "latest" {
+            return Some(VersionQualifier::Latest);
+        }
+        if let Some(number) = qualifier.strip_prefix('v') {
+            if !number.is_empty() && number.bytes().all(|byte| byte.is_ascii_digit()) {
+                return number.parse().ok().map(VersionQualifier::Number);
+            }
+        }
+        let is_tag = !qualifier.is_empty()
+            && qualifier
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_');
+        if is_tag {
+            Some(VersionQualifier::Tag(qualifier.to_owned()))
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for VersionQualifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionQualifier::Latest => f.write_str("latest"),
+            VersionQualifier::Number(number) => write!(f, "v{}", number),
+            VersionQualifier::Tag(tag) => f.write_str(tag),
+        }
+    }
+}
+
+/// Splits the version qualifier off the end of `path`, returning the
+/// unqualified path and the qualifier, or [`None`] if `path` has no valid
+/// qualifier. `path` must not be a package-relative path.
+///
+/// # Examples
+/// ```
+/// use ar::{split_version_qualifier, VersionQualifier};
+///
+/// assert_eq!(
+///     split_version_qualifier("char/hero.usd@v12"),
+///     Some(("char/hero.usd", VersionQualifier::Number(12)))
+/// );
+/// assert_eq!(split_version_qualifier("char/hero.usd"), None);
+/// assert_eq!(split_version_qualifier("/home/user@host/hero.usd"), None);
+/// ```
+pub fn split_version_qualifier(path: &str) -> Option<(&str, VersionQualifier)> {
+    let (unqualified_path, qualifier) = path.rsplit_once('@')?;
+    if unqualified_path.is_empty() {
+        return None;
+    }
+    VersionQualifier::parse(qualifier).map(|qualifier| (unqualified_path, qualifier))
+}
+
+/// Source of the versions of assets, consulted by a [`VersionedResolver`] to
+/// resolve version qualifiers.
+///
+/// Assets are identified by their unqualified identifier, e.g.
+/// `char/hero.usd` for `char/hero.usd@latest`.
+pub trait VersionStore: Send + Sync {
+    /// Returns the version numbers of the asset `identifier`, in ascending
+    /// order, or an empty list if the asset is not versioned.
+    fn get_versions(&self, identifier: &str) -> Vec<u32>;
+
+    /// Returns the version number `tag` points to for the asset
+    /// `identifier`, if any.
+    fn get_tagged_version(&self, identifier: &str, tag: &str) -> Option<u32>;
+
+    /// Returns the asset path of the given version of the asset
+    /// `identifier`, resolved by the resolver wrapped by the
+    /// [`VersionedResolver`]. Only called for versions returned by
+    /// [`VersionStore::get_versions`].
+    fn get_version_path(&self, identifier: &str, version: u32) -> String;
+}
+
+/// Versions and tags of an asset held by an [`InMemoryVersionStore`].
+#[derive(Debug, Default)]
+struct AssetVersions {
+    paths: BTreeMap<u32, String>,
+    tags: BTreeMap<String, u32>,
+}
+
+/// [`VersionStore`] implementation holding versions in memory, e.g. loaded
+/// from an asset management system when a session starts.
+///
+/// # Examples
+/// ```
+/// use ar::{InMemoryVersionStore, VersionStore};
+///
+/// let versions = InMemoryVersionStore::new();
+/// versions.insert_version("char/hero.usd", 1, "/assets/char/hero/v1/hero.usd");
+/// versions.insert_version("char/hero.usd", 2, "/assets/char/hero/v2/hero.usd");
+/// versions.set_tag("char/hero.usd", "approved", 1);
+///
+/// assert_eq!(versions.get_versions("char/hero.usd"), [1, 2]);
+/// assert_eq!(versions.get_tagged_version("char/hero.usd", "approved"), Some(1));
+/// ```
+#[derive(Debug, Default)]
+pub struct InMemoryVersionStore {
+    assets: Mutex<BTreeMap<String, AssetVersions>>,
+}
+
+impl InMemoryVersionStore {
+    /// Creates a store without any versions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `version` of the asset `identifier`, stored at `asset_path`,
+    /// replacing any previous path of that version.
+    pub fn insert_version(&self, identifier: &str, version: u32, asset_path: &str) {
+        lock(&self.assets)
+            .entry(identifier.to_owned())
+            .or_default()
+            .paths
+            .insert(version, asset_path.to_owned());
+    }
+
+    /// Points `tag` to `version` of the asset `identifier`.
+    pub fn set_tag(&self, identifier: &str, tag: &str, version: u32) {
+        lock(&self.assets)
+            .entry(identifier.to_owned())
+            .or_default()
+            .tags
+            .insert(tag.to_owned(), version);
+    }
+}
+
+impl VersionStore for InMemoryVersionStore {
+    fn get_versions(&self, identifier: &str) -> Vec<u32> {
+        lock(&self.assets)
+            .get(identifier)
+            .map(|versions| versions.paths.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
+    fn get_tagged_version(&self, identifier: &str, tag: &str) -> Option<u32> {
+        lock(&self.assets)
+            .get(identifier)
+            .and_then(|versions| versions.tags.get(tag).copied())
+    }
+
+    fn get_version_path(&self, identifier: &str, version: u32) -> String {
+        lock(&self.assets)
+            .get(identifier)
+            .and_then(|versions| versions.paths.get(&version).cloned())
+            .unwrap_or_default()
+    }
+}
+
+impl<S: VersionStore + ?Sized> VersionStore for Arc<S> {
+    fn get_versions(&self, identifier: &str) -> Vec<u32> {
+        (**self).get_versions(identifier)
+    }
+
+    fn get_tagged_version(&self, identifier: &str, tag: &str) -> Option<u32> {
+        (**self).get_tagged_version(identifier, tag)
+    }
+
+    fn get_version_path(&self, identifier: &str, version: u32) -> String {
+        (**self).get_version_path(identifier, version)
+    }
+}
+
+/// Version selected by the qualifier of an asset path.
+enum VersionSelection {
+    /// The asset path has no qualifier, or the asset is not versioned.
+    Unversioned,
+    /// The asset has no version matching the qualifier.
+    Missing,
+    /// The selected version number, and the asset path of that version.
+    Version(u32, String),
+}
+
+/// [`Resolver`] decorator resolving version qualifiers in asset paths, and
+/// recording resolutions in a [`Lockfile`] so they can be replayed.
+///
+/// Asset paths ending with a [`VersionQualifier`], e.g.
+/// `char/hero.usd@latest`, `char/hero.usd@v12` or `char/hero.usd@approved`,
+/// select a version of the asset from a [`VersionStore`], and resolve to
+/// the resolved path of that version's asset path in the wrapped resolver.
+/// For package-relative paths, the qualifier ends the package path, e.g.
+/// `props/chair.usdz@latest[chair.usda]`. Qualified asset paths of assets
+/// that have no versions in the store are left to the wrapped resolver,
+/// which may handle qualifiers itself. Other calls are forwarded to the
+/// wrapped resolver.
+///
+/// [`Resolver::get_asset_info`] reports the version the resolved path was
+/// resolved from, e.g. `v12`, as [`AssetInfo::get_version`].
+///
+/// Every successful resolution is recorded in a lockfile, returned by
+/// [`VersionedResolver::get_lockfile`]. When a context holding a
+/// [`Lockfile`] is bound, identifiers recorded in it resolve to their
+/// recorded resolved path and version instead, so a session can be replayed
+/// with exactly the same resolutions. Identifiers missing from the bound
+/// lockfile are resolved as usual, with a warning.
+///
+/// # Examples
+/// ```
+/// use ar::{
+///     InMemoryResolver, InMemoryVersionStore, Resolver, ResolverContext,
+///     ResolverContextBinder, VersionedResolver,
+/// };
+///
+/// let resolver = InMemoryResolver::new();
+/// resolver.insert("/assets/hero/v1/hero.usda", b"#usda 1.0");
+/// let versions = InMemoryVersionStore::new();
+/// versions.insert_version("/assets/hero.usda", 1, "/assets/hero/v1/hero.usda");
+/// let resolver = VersionedResolver::new(resolver, versions);
+///
+/// let resolved_path = resolver.resolve("/assets/hero.usda@latest");
+/// assert_eq!(resolved_path.get_path_string(), "/assets/hero/v1/hero.usda");
+/// let info = resolver.get_asset_info("/assets/hero.usda@latest", &resolved_path);
+/// assert_eq!(info.get_version(), Some("v1"));
+///
+/// // Later versions are ignored when replaying the session's lockfile.
+/// let mut context = ResolverContext::new();
+/// context.push(resolver.get_lockfile());
+/// resolver.get_resolver().insert("/assets/hero/v2/hero.usda", b"#usda 1.0");
+/// resolver
+///     .get_version_store()
+///     .insert_version("/assets/hero.usda", 2, "/assets/hero/v2/hero.usda");
+///
+/// let _binder = ResolverContextBinder::with_resolver(&resolver, context);
+/// assert_eq!(resolver.resolve("/assets/hero.usda@latest"), resolved_path);
+/// ```
+pub struct VersionedResolver<R, S> {
+    resolver: R,
+    versions: S,
+    context_stack: ContextStack,
+    lockfile: Mutex<Lockfile>,
+}
+
+impl<R: Resolver, S: VersionStore> VersionedResolver<R, S> {
+    /// Creates a decorator resolving the versions held by `versions` with
+    /// `resolver`.
+    pub fn new(resolver: R, versions: S) -> Self {
+        Self {
+            resolver,
+            versions,
+            context_stack: ContextStack::new(),
+            lockfile: Mutex::default(),
+        }
+    }
+
+    /// Returns the wrapped resolver.
+    pub fn get_resolver(&self) -> &R {
+        &self.resolver
+    }
+
+    /// Returns the store holding the versions of assets.
+    pub fn get_version_store(&self) -> &S {
+        &self.versions
+    }
+
+    /// Returns the resolutions recorded on all threads since the resolver
+    /// was created or the lockfile was last cleared.
+    pub fn get_lockfile(&self) -> Lockfile {
+        lock(&self.lockfile).clone()
+    }
+
+    /// Discards the recorded resolutions.
+    pub fn clear_lockfile(&self) {
+        *lock(&self.lockfile) = Lockfile::new();
+    }
+
+    /// Returns the lockfile held by the context bound on the current thread,
+    /// if any.
+    fn get_bound_lockfile(&self) -> Option<Lockfile> {
+        self.context_stack
+            .top()
+            .and_then(|context| context.get::<Lockfile>().cloned())
+    }
+
+    /// Records the resolution of `asset_path` in the session's lockfile.
+    /// Resolutions the lockfile cannot hold are only logged.
+    fn record_resolution(&self, asset_path: &str, resolution: LockedResolution) {
+        if let Err(err) = lock(&self.lockfile).insert(asset_path, resolution) {
+            log::warn!("cannot record the resolution of `{}`: {}", asset_path, err);
+        }
+    }
+
+    /// Selects the version of the asset `asset_path` from its qualifier.
+    fn select_version(&self, asset_path: &str) -> VersionSelection {
+        let (package_path, packaged_path) = split_package_path(asset_path);
+        let (identifier, qualifier) = match split_version_qualifier(package_path) {
+            Some(split) => split,
+            None => return VersionSelection::Unversioned,
+        };
+        let versions = self.versions.get_versions(identifier);
+        if versions.is_empty() {
+            return VersionSelection::Unversioned;
+        }
+
+        let version = match qualifier {
+            VersionQualifier::Latest => versions.last().copied(),
+            VersionQualifier::Number(number) => Some(number),
+            VersionQualifier::Tag(tag) => self.versions.get_tagged_version(identifier, &tag),
+        };
+        match version.filter(|version| versions.contains(version)) {
+            Some(version) => {
+                let version_path = self.versions.get_version_path(identifier, version);
+                VersionSelection::Version(version, version_path + packaged_path)
+            }
+            None => VersionSelection::Missing,
+        }
+    }
+}
+
+impl<R, S> fmt::Debug for VersionedResolver<R, S>
+where
+    R: fmt::Debug,
+    S: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VersionedResolver")
+            .field("resolver", &self.resolver)
+            .field("versions", &self.versions)
+            .field("lockfile", &lock(&self.lockfile).len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[allow(deprecated)]
+impl<R: Resolver, S: VersionStore> Resolver for VersionedResolver<R, S> {
+    fn create_identifier(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: Option<&ResolvedPath>,
+    ) -> String {
+        // The qualifier is kept on the identifier of the unqualified path.
+        let (package_path, packaged_path) = split_package_path(asset_path);
+        let (unqualified_path, qualifier) = match split_version_qualifier(package_path) {
+            Some(split) => split,
+            None => {
+                return self
+                    .resolver
+                    .create_identifier(asset_path, anchor_asset_path)
+            }
+        };
+        let identifier = self.resolver.create_identifier(
+            &(unqualified_path.to_owned() + packaged_path),
+            anchor_asset_path,
+        );
+        let (package_identifier, packaged_identifier) = split_package_path(&identifier);
+        format!(
+            "{}@{}{}",
+            package_identifier, qualifier, packaged_identifier
+        )
+    }
+
+    fn create_identifier_for_new_asset(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: &ResolvedPath,
+    ) -> String {
+        self.resolver
+            .create_identifier_for_new_asset(asset_path, anchor_asset_path)
+    }
+
+    fn resolve(&self, asset_path: &str) -> ResolvedPath {
+        if let Some(lockfile) = self.get_bound_lockfile() {
+            if let Some(resolution) = lockfile.get(asset_path) {
+                self.record_resolution(asset_path, resolution.clone());
+                return resolution.get_resolved_path().clone();
+            }
+            log::warn!("`{}` is not in the bound lockfile", asset_path);
+        }
+
+        let (version, resolved_path) = match self.select_version(asset_path) {
+            VersionSelection::Unversioned => (None, self.resolver.resolve(asset_path)),
+            VersionSelection::Missing => return ResolvedPath::default(),
+            VersionSelection::Version(version, version_path) => (
+                Some(format!("v{}", version)),
+                self.resolver.resolve(&version_path),
+            ),
+        };
+        if !resolved_path.is_empty() {
+            let resolution = LockedResolution::new(version.as_deref(), resolved_path.clone());
+            self.record_resolution(asset_path, resolution);
+        }
+        resolved_path
+    }
+
+    fn resolve_for_new_asset(&self, asset_path: &str) -> ResolvedPath {
+        self.resolver.resolve_for_new_asset(asset_path)
+    }
+
+    fn bind_context(&self, context: &ResolverContext, binding_data: &dyn Any) {
+        self.context_stack.push(context);
+        self.resolver.bind_context(context, binding_data);
+    }
+
+    fn unbind_context(&self, context: &ResolverContext, binding_data: &dyn Any) {
+        self.resolver.unbind_context(context, binding_data);
+        self.context_stack.pop(context);
+    }
+
+    fn create_default_context(&self) -> ResolverContext {
+        self.resolver.create_default_context()
+    }
+
+    fn create_default_context_for_asset(&self, asset_path: &str) -> ResolverContext {
+        self.resolver.create_default_context_for_asset(asset_path)
+    }
+
+    fn create_context_from_string(&self, context_str: &str) -> ResolverContext {
+        self.resolver.create_context_from_string(context_str)
+    }
+
+    fn create_context_from_uri_and_string(
+        &self,
+        uri_scheme: &str,
+        context_str: &str,
+    ) -> ResolverContext {
+        self.resolver
+            .create_context_from_uri_and_string(uri_scheme, context_str)
+    }
+
+    fn create_context_from_strings(&self, context_strings: &[(&str, &str)]) -> ResolverContext {
+        self.resolver.create_context_from_strings(context_strings)
+    }
+
+    fn refresh_context(&self, context: &ResolverContext) {
+        self.resolver.refresh_context(context);
+    }
+
+    fn get_current_context(&self) -> ResolverContext {
+        self.resolver.get_current_context()
+    }
+
+    fn is_context_dependent_path(&self, asset_path: &str) -> bool {
+        self.resolver.is_context_dependent_path(asset_path)
+    }
+
+    fn get_extension(&self, asset_path: &str) -> String {
+        let (package_path, packaged_path) = split_package_path(asset_path);
+        match split_version_qualifier(package_path) {
+            Some((unqualified_path, _)) => self
+                .resolver
+                .get_extension(&(unqualified_path.to_owned() + packaged_path)),
+            None => self.resolver.get_extension(asset_path),
+        }
+    }
+
+    fn get_asset_info(&self, asset_path: &str, resolved_path: &ResolvedPath) -> AssetInfo {
+        let info = self.resolver.get_asset_info(asset_path, resolved_path);
+        // The version is the one of the resolution that produced
+        // `resolved_path`, which may no longer be the one selected by the
+        // qualifier if versions were published since.
+        let recorded_version = |lockfile: &Lockfile| {
+            lockfile
+                .get(asset_path)
+                .filter(|resolution| resolution.get_resolved_path() == resolved_path)
+                .map(|resolution| resolution.get_version().map(ToOwned::to_owned))
+        };
+        let locked_version = self
+            .get_bound_lockfile()
+            .and_then(|lockfile| recorded_version(&lockfile))
+            .or_else(|| recorded_version(&lock(&self.lockfile)));
+        let version = match locked_version {
+            Some(version) => version,
+            None => match self.select_version(asset_path) {
+                VersionSelection::Version(version, version_path)
+                    if self.resolver.resolve(&version_path) == *resolved_path =>
+                {
+                    Some(format!("v{}", version))
+                }
+                _ => None,
+            },
+        };
+        match version {
+            Some(version) => info.with_version(&version),
+            None => info,
+        }
+    }
+
+    fn get_modification_timestamp(
+        &self,
+        asset_path: &str,
+        resolved_path: &ResolvedPath,
+    ) -> Result<i64, ResolverError> {
+        self.resolver
+            .get_modification_timestamp(asset_path, resolved_path)
+    }
+
+    fn open_asset(&self, resolved_path: &ResolvedPath) -> Result<Arc<dyn Asset>, ResolverError> {
+        self.resolver.open_asset(resolved_path)
+    }
+
+    fn open_asset_for_write(
+        &self,
+        resolved_path: &ResolvedPath,
+        write_mode: WriteMode,
+    ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+        self.resolver
+            .open_asset_for_write(resolved_path, write_mode)
+    }
+
+    fn begin_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        self.resolver.begin_cache_scope(cache_scope_data);
+    }
+
+    fn end_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        self.resolver.end_cache_scope(cache_scope_data);
+    }
+
+    fn configure_resolver_for_asset(&self, path: &str) {
+        self.resolver.configure_resolver_for_asset(path);
+    }
+
+    fn anchor_relative_path(&self, anchor_path: &str, path: &str) -> String {
+        self.resolver.anchor_relative_path(anchor_path, path)
+    }
+
+    fn is_relative_path(&self, path: &str) -> bool {
+        self.resolver.is_relative_path(path)
+    }
+
+    fn is_search_path(&self, path: &str) -> bool {
+        self.resolver.is_search_path(path)
+    }
+
+    fn is_repository_path(&self, path: &str) -> bool {
+        self.resolver.is_repository_path(path)
+    }
+
+    fn fetch_to_local_resolved_path(&self, path: &str, resolved_path: &str) -> bool {
+        self.resolver
+            .fetch_to_local_resolved_path(path, resolved_path)
+    }
+
+    fn create_path_for_layer(&self, path: &str) -> Result<(), ResolverError> {
+        self.resolver.create_path_for_layer(path)
+    }
+
+    fn can_write_layer_to_path(&self, path: &str) -> Result<(), ResolverError> {
+        self.resolver.can_write_layer_to_path(path)
+    }
+
+    fn can_create_new_layer_with_identifier(&self, identifier: &str) -> Result<(), ResolverError> {
+        self.resolver
+            .can_create_new_layer_with_identifier(identifier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryResolver;
+
+    #[test]
+    fn test_parse_qualifier() {
+        assert_eq!(
+            VersionQualifier::parse("latest"),
+            Some(VersionQualifier::Latest)
+        );
+        assert_eq!(
+            VersionQualifier::parse("v12"),
+            Some(VersionQualifier::Number(12))
+        );
+        assert_eq!(
+            VersionQualifier::parse("approved"),
+            Some(VersionQualifier::Tag("approved".into()))
+        );
+        assert_eq!(
+            VersionQualifier::parse("v1x"),
+            Some(VersionQualifier::Tag("v1x".into()))
+        );
+        assert_eq!(VersionQualifier::parse(""), None);
+        assert_eq!(VersionQualifier::parse("v99999999999"), None);
+        assert_eq!(VersionQualifier::parse("a/b"), None);
+        assert_eq!(VersionQualifier::parse("a.usd"), None);
+    }
+
+    #[test]
+    fn test_create_identifier() {
+        let resolver = VersionedResolver::new(InMemoryResolver::new(), InMemoryVersionStore::new());
+        let anchor = ResolvedPath::new("/assets/shot/layout.usda");
+        assert_eq!(
+            resolver.create_identifier("../char/hero.usda@latest", Some(&anchor)),
+            "/assets/char/hero.usda@latest"
+        );
+        assert_eq!(
+            resolver.create_identifier("./props.usdz@v3[chair.usda]", Some(&anchor)),
+            "/assets/shot/props.usdz@v3[chair.usda]"
+        );
+        assert_eq!(
+            resolver.create_identifier("./user@host.usda", Some(&anchor)),
+            "/assets/shot/user@host.usda"
+        );
+        assert_eq!(resolver.get_extension("props.usdz@v3"), "usdz");
+    }
+
+    #[test]
+    fn test_asset_info_of_resolved_version() {
+        let resolver = InMemoryResolver::new();
+        resolver.insert("/a/v1.usda", b"#usda 1.0");
+        resolver.insert("/a/v2.usda", b"#usda 1.0");
+        let versions = InMemoryVersionStore::new();
+        versions.insert_version("/a.usda", 1, "/a/v1.usda");
+        let resolver = VersionedResolver::new(resolver, versions);
+
+        let resolved_path = resolver.resolve("/a.usda@latest");
+        assert_eq!(resolved_path.get_path_string(), "/a/v1.usda");
+        resolver
+            .get_version_store()
+            .insert_version("/a.usda", 2, "/a/v2.usda");
+        let info = resolver.get_asset_info("/a.usda@latest", &resolved_path);
+        assert_eq!(info.get_version(), Some("v1"));
+
+        let resolved_path = resolver.resolve("/a.usda@latest");
+        assert_eq!(resolved_path.get_path_string(), "/a/v2.usda");
+        let info = resolver.get_asset_info("/a.usda@latest", &resolved_path);
+        assert_eq!(info.get_version(), Some("v2"));
+    }
+}
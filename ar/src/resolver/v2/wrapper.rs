@@ -1,24 +1,1053 @@
+use alloc::borrow::ToOwned;
 use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::fmt;
 
-use super::Resolver;
+use super::context_stack::ContextStack;
+use super::{CacheScopeData, PackageResolver, Resolver, WriteMode};
+use crate::package_utils::find_innermost_packaged_path;
+use crate::path_utils;
+use crate::{
+    is_package_relative_path, join_package_relative_path, split_package_relative_path_inner,
+    split_package_relative_path_outer, Asset, AssetInfo, ResolvedPath, ResolverContext,
+    ResolverError, WritableAsset,
+};
 
-/// Private ArResolver implementation that owns and forwards calls to the
-/// plugin asset resolver implementation. This is used to overlay additional
-/// behaviors on top of the plugin resolver.
-pub(super) struct ResolverWrapper {
-    resolver: Box<dyn Resolver>,
+/// Returns true if `uri_scheme` is a valid URI scheme as defined by
+/// RFC 3986: a letter followed by any number of letters, digits, `+`, `-`
+/// or `.`.
+fn is_valid_uri_scheme(uri_scheme: &str) -> bool {
+    let mut chars = uri_scheme.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Returns the extension of the innermost packaged path of `path`, or of
+/// `path` itself if it is not a package-relative path.
+fn get_innermost_extension(path: &str) -> &str {
+    let path = match find_innermost_packaged_path(path) {
+        Some((begin, end)) => &path[begin..end],
+        None => path,
+    };
+    path_utils::get_extension(path)
+}
+
+/// Returns each distinct resolver in `resolvers`, keeping the first
+/// occurrence of a resolver that appears several times.
+fn dedup_resolvers<'a, T: ?Sized>(resolvers: impl Iterator<Item = &'a Arc<T>>) -> Vec<&'a Arc<T>> {
+    let mut unique: Vec<&Arc<T>> = Vec::new();
+    for resolver in resolvers {
+        if !unique
+            .iter()
+            .any(|existing| Arc::ptr_eq(existing, resolver))
+        {
+            unique.push(resolver);
+        }
+    }
+    unique
+}
+
+/// Cache scope data of a [`ResolverWrapper`], holding the data of each of
+/// its resolvers in the order returned by `get_all_resolvers`, followed by
+/// the data of each package resolver in the order returned by
+/// `get_all_package_resolvers`.
+struct WrapperCacheScopeData(Vec<CacheScopeData>);
+
+/// Returns the data of each resolver held by `cache_scope_data`, or empty
+/// data for each resolver if it doesn't hold any.
+fn get_resolver_cache_scope_data(
+    cache_scope_data: &CacheScopeData,
+    resolver_count: usize,
+) -> Vec<CacheScopeData> {
+    cache_scope_data
+        .as_ref()
+        .and_then(|data| data.downcast_ref::<WrapperCacheScopeData>())
+        .filter(|data| data.0.len() == resolver_count)
+        .map(|data| data.0.clone())
+        .unwrap_or_else(|| vec![None; resolver_count])
+}
+
+/// [`Resolver`] implementation that owns the primary resolver and any
+/// resolvers registered for URI schemes, and forwards each call to the
+/// appropriate one.
+///
+/// Calls involving an asset path are dispatched to the resolver registered
+/// for the path's URI scheme, e.g. `s3` for `s3://bucket/asset.usd`.
+/// Scheme matching is case-insensitive. Paths without a registered scheme
+/// are handled by the primary resolver.
+///
+/// Package-relative paths like `/dir/a.usdz[b.usd]` are split up: the
+/// outer package path is handled by the resolver for its URI scheme as
+/// above, and each packaged path by the [`PackageResolver`] registered for
+/// the extension of the package containing it. Extension matching is
+/// case-insensitive as well.
+///
+/// Context binding, cache scopes and context refreshes are forwarded to
+/// every resolver. The wrapper keeps its own per-thread stack of bound
+/// contexts, which [`Resolver::get_current_context`] returns the top of.
+pub struct ResolverWrapper {
+    resolver: Box<dyn Resolver + Send + Sync>,
+    uri_resolvers: BTreeMap<String, Arc<dyn Resolver + Send + Sync>>,
     max_uri_scheme_length: usize,
+    package_resolvers: BTreeMap<String, Arc<dyn PackageResolver + Send + Sync>>,
+    context_stack: ContextStack,
 }
 
 impl ResolverWrapper {
+    /// Creates a wrapper around the given primary `resolver` with no URI
+    /// or package resolvers registered.
+    pub fn new(resolver: Box<dyn Resolver + Send + Sync>) -> Self {
+        Self {
+            resolver,
+            uri_resolvers: BTreeMap::new(),
+            max_uri_scheme_length: 0,
+            package_resolvers: BTreeMap::new(),
+            context_stack: ContextStack::new(),
+        }
+    }
+
+    /// Registers `resolver` to handle asset paths using any of the given
+    /// `uri_schemes`.
+    ///
+    /// Returns [`ResolverError::InvalidUriScheme`] without registering
+    /// anything if a scheme is not valid per RFC 3986 or is already
+    /// registered.
+    pub fn register_uri_resolver(
+        &mut self,
+        uri_schemes: &[&str],
+        resolver: Arc<dyn Resolver + Send + Sync>,
+    ) -> Result<(), ResolverError> {
+        let mut normalized_schemes: Vec<String> = Vec::with_capacity(uri_schemes.len());
+        for uri_scheme in uri_schemes {
+            if !is_valid_uri_scheme(uri_scheme) {
+                return Err(ResolverError::InvalidUriScheme(
+                    (*uri_scheme).to_owned(),
+                    "scheme must start with a letter followed by letters, digits, '+', '-' or '.'"
+                        .to_owned(),
+                ));
+            }
+
+            let uri_scheme = uri_scheme.to_ascii_lowercase();
+            if self.uri_resolvers.contains_key(&uri_scheme)
+                || normalized_schemes.contains(&uri_scheme)
+            {
+                return Err(ResolverError::InvalidUriScheme(
+                    uri_scheme,
+                    "a resolver is already registered for this scheme".to_owned(),
+                ));
+            }
+            normalized_schemes.push(uri_scheme);
+        }
+
+        for uri_scheme in normalized_schemes {
+            self.max_uri_scheme_length = self.max_uri_scheme_length.max(uri_scheme.len());
+            self.uri_resolvers.insert(uri_scheme, resolver.clone());
+        }
+        Ok(())
+    }
+
+    /// Registers `resolver` to handle assets inside packages with any of the
+    /// given file `extensions`, given without the leading `.`.
     ///
+    /// Returns [`ResolverError::InvalidPackageExtension`] without registering
+    /// anything if an extension is empty, contains a `.` or is already
+    /// registered.
+    pub fn register_package_resolver(
+        &mut self,
+        extensions: &[&str],
+        resolver: Arc<dyn PackageResolver + Send + Sync>,
+    ) -> Result<(), ResolverError> {
+        let mut normalized_extensions: Vec<String> = Vec::with_capacity(extensions.len());
+        for extension in extensions {
+            if extension.is_empty() || extension.contains('.') {
+                return Err(ResolverError::InvalidPackageExtension(
+                    (*extension).to_owned(),
+                    "extension must be non-empty and must not contain '.'".to_owned(),
+                ));
+            }
+
+            let extension = extension.to_ascii_lowercase();
+            if self.package_resolvers.contains_key(&extension)
+                || normalized_extensions.contains(&extension)
+            {
+                return Err(ResolverError::InvalidPackageExtension(
+                    extension,
+                    "a package resolver is already registered for this extension".to_owned(),
+                ));
+            }
+            normalized_extensions.push(extension);
+        }
+
+        for extension in normalized_extensions {
+            self.package_resolvers.insert(extension, resolver.clone());
+        }
+        Ok(())
+    }
+
+    /// Returns the primary resolver.
     pub fn get_primary_resolver(&self) -> &dyn Resolver {
         self.resolver.as_ref()
     }
+
+    /// Returns the resolver registered for `uri_scheme`, if any. The lookup
+    /// is case-insensitive.
+    pub fn get_uri_resolver(&self, uri_scheme: &str) -> Option<&dyn Resolver> {
+        if uri_scheme.len() > self.max_uri_scheme_length {
+            return None;
+        }
+        self.uri_resolvers
+            .get(&uri_scheme.to_ascii_lowercase())
+            .map(|resolver| resolver.as_ref() as &dyn Resolver)
+    }
+
+    /// Returns the URI schemes with a registered resolver, in lowercase.
+    pub fn get_uri_schemes(&self) -> impl Iterator<Item = &str> {
+        self.uri_resolvers.keys().map(String::as_str)
+    }
+
+    /// Returns the package resolver registered for `extension`, if any. The
+    /// lookup is case-insensitive.
+    pub fn get_package_resolver(&self, extension: &str) -> Option<&dyn PackageResolver> {
+        self.package_resolvers
+            .get(&extension.to_ascii_lowercase())
+            .map(|resolver| resolver.as_ref() as &dyn PackageResolver)
+    }
+
+    /// Returns the package extensions with a registered package resolver, in
+    /// lowercase.
+    pub fn get_package_extensions(&self) -> impl Iterator<Item = &str> {
+        self.package_resolvers.keys().map(String::as_str)
+    }
+
+    /// Returns the resolver registered for the URI scheme of `asset_path`,
+    /// if it has one.
+    fn get_uri_resolver_for_path(&self, asset_path: &str) -> Option<&dyn Resolver> {
+        // Only look as far as the longest registered scheme so long paths
+        // without a scheme are rejected quickly.
+        let prefix_len = asset_path.len().min(self.max_uri_scheme_length + 1);
+        let index = asset_path.as_bytes()[..prefix_len]
+            .iter()
+            .position(|&c| c == b':')?;
+        let uri_scheme = &asset_path[..index];
+        if !is_valid_uri_scheme(uri_scheme) {
+            return None;
+        }
+        self.get_uri_resolver(uri_scheme)
+    }
+
+    /// Returns the resolver responsible for `asset_path`.
+    fn get_resolver_for_path(&self, asset_path: &str) -> &dyn Resolver {
+        self.get_uri_resolver_for_path(asset_path)
+            .unwrap_or_else(|| self.get_primary_resolver())
+    }
+
+    /// Returns the resolver responsible for `asset_path` anchored to
+    /// `anchor_asset_path`. Paths without a URI scheme of their own are
+    /// handled by the resolver for the anchor, so relative paths authored
+    /// in an asset served by a URI resolver are resolved by it as well.
+    fn get_resolver_for_anchored_path(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: Option<&str>,
+    ) -> &dyn Resolver {
+        self.get_uri_resolver_for_path(asset_path)
+            .or_else(|| anchor_asset_path.and_then(|anchor| self.get_uri_resolver_for_path(anchor)))
+            .unwrap_or_else(|| self.get_primary_resolver())
+    }
+
+    /// Returns the package resolver for the package at `package_path`, which
+    /// may itself be a package-relative path, based on the extension of the
+    /// innermost package. Extensions of outer packages are those reported by
+    /// the resolver for their path, which may not be a plain file extension.
+    fn get_package_resolver_for_path(&self, package_path: &str) -> Option<&dyn PackageResolver> {
+        self.get_package_resolver(&Resolver::get_extension(self, package_path))
+    }
+
+    /// Resolves the package-relative `asset_path`. The outer package path is
+    /// resolved with `resolve_package`, then each packaged path by the
+    /// package resolver for the package containing it.
+    fn resolve_package_relative_path(
+        &self,
+        asset_path: &str,
+        resolve_package: impl FnOnce(&dyn Resolver, &str) -> ResolvedPath,
+    ) -> ResolvedPath {
+        let (mut package_path, mut packaged_path) = split_package_relative_path_outer(asset_path);
+        let mut resolved_path =
+            resolve_package(self.get_resolver_for_path(&package_path), &package_path);
+        if resolved_path.is_empty() {
+            return resolved_path;
+        }
+
+        loop {
+            let package_resolver = match self.get_package_resolver_for_path(&package_path) {
+                Some(package_resolver) => package_resolver,
+                None => {
+                    log::warn!("no package resolver for package `{}`", package_path);
+                    return ResolvedPath::default();
+                }
+            };
+
+            let (inner_package_path, inner_packaged_path) =
+                split_package_relative_path_outer(&packaged_path);
+            let resolved_packaged_path =
+                package_resolver.resolve(self, &resolved_path, &inner_package_path);
+            if resolved_packaged_path.is_empty() {
+                return ResolvedPath::default();
+            }
+            resolved_path = ResolvedPath::new(&join_package_relative_path(&[
+                resolved_path.get_path_string(),
+                &resolved_packaged_path,
+            ]));
+
+            if inner_packaged_path.is_empty() {
+                return resolved_path;
+            }
+            package_path = inner_package_path;
+            packaged_path = inner_packaged_path;
+        }
+    }
+
+    /// Returns the primary resolver followed by each distinct URI resolver.
+    /// A resolver registered for several schemes is only returned once.
+    fn get_all_resolvers(&self) -> Vec<&dyn Resolver> {
+        let uri_resolvers = dedup_resolvers(self.uri_resolvers.values());
+
+        let mut resolvers: Vec<&dyn Resolver> = Vec::with_capacity(uri_resolvers.len() + 1);
+        resolvers.push(self.get_primary_resolver());
+        resolvers.extend(
+            uri_resolvers
+                .into_iter()
+                .map(|resolver| resolver.as_ref() as &dyn Resolver),
+        );
+        resolvers
+    }
+
+    /// Returns each distinct package resolver. A package resolver registered
+    /// for several extensions is only returned once.
+    fn get_all_package_resolvers(&self) -> Vec<&dyn PackageResolver> {
+        dedup_resolvers(self.package_resolvers.values())
+            .into_iter()
+            .map(|resolver| resolver.as_ref() as &dyn PackageResolver)
+            .collect()
+    }
 }
 
-// impl Resolver for ResolverWrapper {
-//     fn configure_resolver_for_asset(&mut self, path: &str) {}
-//
-//     fn create_context_from_string(&self, uri_scheme: &str, context_str: &str) -> ResolverContext {}
-// }
+impl fmt::Debug for ResolverWrapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResolverWrapper")
+            .field("uri_schemes", &self.uri_resolvers.keys())
+            .field("max_uri_scheme_length", &self.max_uri_scheme_length)
+            .field("package_extensions", &self.package_resolvers.keys())
+            .finish_non_exhaustive()
+    }
+}
+
+#[allow(deprecated)]
+impl Resolver for ResolverWrapper {
+    fn create_identifier(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: Option<&ResolvedPath>,
+    ) -> String {
+        if is_package_relative_path(asset_path) {
+            let (package_path, packaged_path) = split_package_relative_path_outer(asset_path);
+            let identifier = self.create_identifier(&package_path, anchor_asset_path);
+            return join_package_relative_path(&[&identifier, &packaged_path]);
+        }
+
+        let anchor = anchor_asset_path.map(ResolvedPath::get_path_string);
+        self.get_resolver_for_anchored_path(asset_path, anchor)
+            .create_identifier(asset_path, anchor_asset_path)
+    }
+
+    fn create_identifier_for_new_asset(
+        &self,
+        asset_path: &str,
+        anchor_asset_path: &ResolvedPath,
+    ) -> String {
+        if is_package_relative_path(asset_path) {
+            let (package_path, packaged_path) = split_package_relative_path_outer(asset_path);
+            let identifier = self.create_identifier_for_new_asset(&package_path, anchor_asset_path);
+            return join_package_relative_path(&[&identifier, &packaged_path]);
+        }
+
+        self.get_resolver_for_anchored_path(asset_path, Some(anchor_asset_path.get_path_string()))
+            .create_identifier_for_new_asset(asset_path, anchor_asset_path)
+    }
+
+    fn resolve(&self, asset_path: &str) -> ResolvedPath {
+        if is_package_relative_path(asset_path) {
+            return self.resolve_package_relative_path(asset_path, |resolver, package_path| {
+                resolver.resolve(package_path)
+            });
+        }
+
+        self.get_resolver_for_path(asset_path).resolve(asset_path)
+    }
+
+    fn resolve_for_new_asset(&self, asset_path: &str) -> ResolvedPath {
+        if is_package_relative_path(asset_path) {
+            return self.resolve_package_relative_path(asset_path, |resolver, package_path| {
+                resolver.resolve_for_new_asset(package_path)
+            });
+        }
+
+        self.get_resolver_for_path(asset_path)
+            .resolve_for_new_asset(asset_path)
+    }
+
+    fn bind_context(&self, context: &ResolverContext, binding_data: &dyn Any) {
+        self.context_stack.push(context);
+        for resolver in self.get_all_resolvers() {
+            resolver.bind_context(context, binding_data);
+        }
+    }
+
+    fn unbind_context(&self, context: &ResolverContext, binding_data: &dyn Any) {
+        for resolver in self.get_all_resolvers().into_iter().rev() {
+            resolver.unbind_context(context, binding_data);
+        }
+        self.context_stack.pop(context);
+    }
+
+    fn create_default_context(&self) -> ResolverContext {
+        let mut context = ResolverContext::new();
+        for resolver in self.get_all_resolvers() {
+            context += resolver.create_default_context();
+        }
+        context
+    }
+
+    fn create_default_context_for_asset(&self, asset_path: &str) -> ResolverContext {
+        if is_package_relative_path(asset_path) {
+            let (package_path, _) = split_package_relative_path_outer(asset_path);
+            return self.create_default_context_for_asset(&package_path);
+        }
+
+        self.get_resolver_for_path(asset_path)
+            .create_default_context_for_asset(asset_path)
+    }
+
+    fn create_context_from_string(&self, context_str: &str) -> ResolverContext {
+        self.get_primary_resolver()
+            .create_context_from_string(context_str)
+    }
+
+    fn create_context_from_uri_and_string(
+        &self,
+        uri_scheme: &str,
+        context_str: &str,
+    ) -> ResolverContext {
+        if uri_scheme.is_empty() {
+            return self.create_context_from_string(context_str);
+        }
+        match self.get_uri_resolver(uri_scheme) {
+            Some(resolver) => resolver.create_context_from_string(context_str),
+            None => ResolverContext::new(),
+        }
+    }
+
+    fn refresh_context(&self, context: &ResolverContext) {
+        for resolver in self.get_all_resolvers() {
+            resolver.refresh_context(context);
+        }
+    }
+
+    fn get_current_context(&self) -> ResolverContext {
+        self.context_stack.top().unwrap_or_default()
+    }
+
+    fn is_context_dependent_path(&self, asset_path: &str) -> bool {
+        if is_package_relative_path(asset_path) {
+            let (package_path, _) = split_package_relative_path_outer(asset_path);
+            return self.is_context_dependent_path(&package_path);
+        }
+
+        self.get_resolver_for_path(asset_path)
+            .is_context_dependent_path(asset_path)
+    }
+
+    fn get_extension(&self, asset_path: &str) -> String {
+        if is_package_relative_path(asset_path) {
+            return get_innermost_extension(asset_path).to_string();
+        }
+
+        self.get_resolver_for_path(asset_path)
+            .get_extension(asset_path)
+    }
+
+    fn get_asset_info(&self, asset_path: &str, resolved_path: &ResolvedPath) -> AssetInfo {
+        // Assets inside a package share the info of the outer package.
+        if is_package_relative_path(asset_path) {
+            let (package_path, _) = split_package_relative_path_outer(asset_path);
+            let (resolved_package_path, _) =
+                split_package_relative_path_outer(resolved_path.get_path_string());
+            return self.get_asset_info(&package_path, &ResolvedPath::new(&resolved_package_path));
+        }
+
+        self.get_resolver_for_path(asset_path)
+            .get_asset_info(asset_path, resolved_path)
+    }
+
+    fn get_modification_timestamp(
+        &self,
+        asset_path: &str,
+        resolved_path: &ResolvedPath,
+    ) -> Result<i64, ResolverError> {
+        // Assets inside a package change along with the outer package.
+        if is_package_relative_path(asset_path) {
+            let (package_path, _) = split_package_relative_path_outer(asset_path);
+            let (resolved_package_path, _) =
+                split_package_relative_path_outer(resolved_path.get_path_string());
+            return self.get_modification_timestamp(
+                &package_path,
+                &ResolvedPath::new(&resolved_package_path),
+            );
+        }
+
+        self.get_resolver_for_path(asset_path)
+            .get_modification_timestamp(asset_path, resolved_path)
+    }
+
+    fn open_asset(&self, resolved_path: &ResolvedPath) -> Result<Arc<dyn Asset>, ResolverError> {
+        let path = resolved_path.get_path_string();
+        if is_package_relative_path(path) {
+            let (package_path, packaged_path) = split_package_relative_path_inner(path);
+            let package_resolver = self
+                .get_package_resolver_for_path(&package_path)
+                .ok_or_else(|| {
+                    ResolverError::OpenAssetError(format!(
+                        "no package resolver for package `{}`",
+                        package_path
+                    ))
+                })?;
+            return package_resolver.open_asset(
+                self,
+                &ResolvedPath::new(&package_path),
+                &packaged_path,
+            );
+        }
+
+        self.get_resolver_for_path(path).open_asset(resolved_path)
+    }
+
+    fn open_asset_for_write(
+        &self,
+        resolved_path: &ResolvedPath,
+        write_mode: WriteMode,
+    ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+        let path = resolved_path.get_path_string();
+        if is_package_relative_path(path) {
+            return Err(ResolverError::OpenAssetError(format!(
+                "cannot write to `{}` inside a package",
+                path
+            )));
+        }
+
+        self.get_resolver_for_path(path)
+            .open_asset_for_write(resolved_path, write_mode)
+    }
+
+    fn begin_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        let resolvers = self.get_all_resolvers();
+        let package_resolvers = self.get_all_package_resolvers();
+        let mut resolver_data = get_resolver_cache_scope_data(
+            cache_scope_data,
+            resolvers.len() + package_resolvers.len(),
+        );
+        let (data, package_data) = resolver_data.split_at_mut(resolvers.len());
+        for (resolver, data) in resolvers.iter().zip(data.iter_mut()) {
+            resolver.begin_cache_scope(data);
+        }
+        for (resolver, data) in package_resolvers.iter().zip(package_data.iter_mut()) {
+            resolver.begin_cache_scope(data);
+        }
+        *cache_scope_data = Some(Arc::new(WrapperCacheScopeData(resolver_data)));
+    }
+
+    fn end_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        let resolvers = self.get_all_resolvers();
+        let package_resolvers = self.get_all_package_resolvers();
+        let mut resolver_data = get_resolver_cache_scope_data(
+            cache_scope_data,
+            resolvers.len() + package_resolvers.len(),
+        );
+        let (data, package_data) = resolver_data.split_at_mut(resolvers.len());
+        for (resolver, data) in package_resolvers.iter().zip(package_data.iter_mut()).rev() {
+            resolver.end_cache_scope(data);
+        }
+        for (resolver, data) in resolvers.iter().zip(data.iter_mut()).rev() {
+            resolver.end_cache_scope(data);
+        }
+    }
+
+    fn configure_resolver_for_asset(&self, path: &str) {
+        self.get_resolver_for_path(path)
+            .configure_resolver_for_asset(path)
+    }
+
+    fn anchor_relative_path(&self, anchor_path: &str, path: &str) -> String {
+        self.get_resolver_for_anchored_path(path, Some(anchor_path))
+            .anchor_relative_path(anchor_path, path)
+    }
+
+    fn is_relative_path(&self, path: &str) -> bool {
+        self.get_resolver_for_path(path).is_relative_path(path)
+    }
+
+    fn is_search_path(&self, path: &str) -> bool {
+        self.get_resolver_for_path(path).is_search_path(path)
+    }
+
+    fn is_repository_path(&self, path: &str) -> bool {
+        self.get_resolver_for_path(path).is_repository_path(path)
+    }
+
+    fn fetch_to_local_resolved_path(&self, path: &str, resolved_path: &str) -> bool {
+        self.get_resolver_for_path(path)
+            .fetch_to_local_resolved_path(path, resolved_path)
+    }
+
+    fn create_path_for_layer(&self, path: &str) -> Result<(), ResolverError> {
+        self.get_resolver_for_path(path).create_path_for_layer(path)
+    }
+
+    fn can_write_layer_to_path(&self, path: &str) -> Result<(), ResolverError> {
+        self.get_resolver_for_path(path)
+            .can_write_layer_to_path(path)
+    }
+
+    fn can_create_new_layer_with_identifier(&self, identifier: &str) -> Result<(), ResolverError> {
+        self.get_resolver_for_path(identifier)
+            .can_create_new_layer_with_identifier(identifier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::string::ToString;
+
+    #[cfg(feature = "std")]
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{AssetError, ClientContext};
+    #[cfg(feature = "std")]
+    use crate::{ResolveCache, ResolverScopedCache};
+
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct NamedContext(String);
+
+    impl ClientContext for NamedContext {}
+
+    /// Resolver that tags every result with its own name.
+    struct NamedResolver(&'static str);
+
+    impl Resolver for NamedResolver {
+        fn create_identifier(&self, asset_path: &str, _anchor: Option<&ResolvedPath>) -> String {
+            format!("{}:{}", self.0, asset_path)
+        }
+
+        fn create_identifier_for_new_asset(
+            &self,
+            asset_path: &str,
+            _anchor: &ResolvedPath,
+        ) -> String {
+            format!("{}:{}", self.0, asset_path)
+        }
+
+        fn resolve(&self, asset_path: &str) -> ResolvedPath {
+            ResolvedPath::new(&format!("{}|{}", self.0, asset_path))
+        }
+
+        fn resolve_for_new_asset(&self, asset_path: &str) -> ResolvedPath {
+            self.resolve(asset_path)
+        }
+
+        fn create_context_from_string(&self, context_str: &str) -> ResolverContext {
+            let mut context = ResolverContext::new();
+            context.push(NamedContext(format!("{}:{}", self.0, context_str)));
+            context
+        }
+
+        fn open_asset(
+            &self,
+            _resolved_path: &ResolvedPath,
+        ) -> Result<Arc<dyn Asset>, ResolverError> {
+            Err(ResolverError::OpenAssetError(self.0.to_string()))
+        }
+
+        fn open_asset_for_write(
+            &self,
+            _resolved_path: &ResolvedPath,
+            _write_mode: WriteMode,
+        ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+            Err(ResolverError::OpenAssetError(self.0.to_string()))
+        }
+    }
+
+    fn wrapper() -> ResolverWrapper {
+        let mut wrapper = ResolverWrapper::new(Box::new(NamedResolver("primary")));
+        wrapper
+            .register_uri_resolver(&["s3", "S3-Dev"], Arc::new(NamedResolver("s3")))
+            .unwrap();
+        wrapper
+            .register_uri_resolver(&["http"], Arc::new(NamedResolver("http")))
+            .unwrap();
+        wrapper
+    }
+
+    fn opened_by(result: Result<Arc<dyn Asset>, ResolverError>) -> String {
+        match result {
+            Err(ResolverError::OpenAssetError(name)) => name,
+            _ => panic!("unexpected result"),
+        }
+    }
+
+    #[test]
+    fn test_is_valid_uri_scheme() {
+        assert!(is_valid_uri_scheme("s3"));
+        assert!(is_valid_uri_scheme("svn+ssh"));
+        assert!(is_valid_uri_scheme("my-scheme.v2"));
+        assert!(!is_valid_uri_scheme(""));
+        assert!(!is_valid_uri_scheme("3s"));
+        assert!(!is_valid_uri_scheme("my_scheme"));
+        assert!(!is_valid_uri_scheme("a b"));
+    }
+
+    #[test]
+    fn test_register_uri_resolver() {
+        let mut wrapper = wrapper();
+        assert_eq!(
+            wrapper.get_uri_schemes().collect::<Vec<_>>(),
+            ["http", "s3", "s3-dev"]
+        );
+        assert!(matches!(
+            wrapper.register_uri_resolver(&["HTTP"], Arc::new(NamedResolver("other"))),
+            Err(ResolverError::InvalidUriScheme(scheme, _)) if scheme == "http"
+        ));
+        assert!(matches!(
+            wrapper.register_uri_resolver(&["ftp", "my_scheme"], Arc::new(NamedResolver("other"))),
+            Err(ResolverError::InvalidUriScheme(scheme, _)) if scheme == "my_scheme"
+        ));
+        // Failed registrations leave the table untouched.
+        assert!(wrapper.get_uri_resolver("ftp").is_none());
+    }
+
+    #[test]
+    fn test_dispatch_by_scheme() {
+        let wrapper = wrapper();
+        assert_eq!(
+            wrapper.resolve("s3://bucket/a.usd").get_path_string(),
+            "s3|s3://bucket/a.usd"
+        );
+        assert_eq!(
+            wrapper.resolve("S3://bucket/a.usd").get_path_string(),
+            "s3|S3://bucket/a.usd"
+        );
+        assert_eq!(
+            wrapper.resolve("s3-dev://bucket/a.usd").get_path_string(),
+            "s3|s3-dev://bucket/a.usd"
+        );
+        assert_eq!(
+            wrapper.resolve("/assets/a.usd").get_path_string(),
+            "primary|/assets/a.usd"
+        );
+        assert_eq!(
+            wrapper.resolve("ftp://host/a.usd").get_path_string(),
+            "primary|ftp://host/a.usd"
+        );
+        assert_eq!(
+            wrapper.resolve("C:/assets/a.usd").get_path_string(),
+            "primary|C:/assets/a.usd"
+        );
+
+        assert_eq!(
+            opened_by(wrapper.open_asset(&ResolvedPath::new("http://host/a.usd"))),
+            "http"
+        );
+        assert_eq!(
+            opened_by(wrapper.open_asset(&ResolvedPath::new("/assets/a.usd"))),
+            "primary"
+        );
+        assert!(matches!(
+            wrapper.open_asset_for_write(&ResolvedPath::new("s3://bucket/a.usd"), WriteMode::Replace),
+            Err(ResolverError::OpenAssetError(name)) if name == "s3"
+        ));
+    }
+
+    #[test]
+    fn test_create_identifier_uses_anchor_scheme() {
+        let wrapper = wrapper();
+        let anchor = ResolvedPath::new("http://host/dir/a.usd");
+        assert_eq!(
+            wrapper.create_identifier("b.usd", Some(&anchor)),
+            "http:b.usd"
+        );
+        assert_eq!(
+            wrapper.create_identifier("s3://bucket/b.usd", Some(&anchor)),
+            "s3:s3://bucket/b.usd"
+        );
+        assert_eq!(wrapper.create_identifier("b.usd", None), "primary:b.usd");
+        assert_eq!(
+            wrapper.create_identifier_for_new_asset("b.usd", &anchor),
+            "http:b.usd"
+        );
+    }
+
+    #[test]
+    fn test_create_context_from_uri_and_string() {
+        let wrapper = wrapper();
+        let context = wrapper.create_context_from_uri_and_string("HTTP", "ctx");
+        assert_eq!(
+            context.get::<NamedContext>(),
+            Some(&NamedContext("http:ctx".to_string()))
+        );
+
+        let context = wrapper.create_context_from_uri_and_string("", "ctx");
+        assert_eq!(
+            context.get::<NamedContext>(),
+            Some(&NamedContext("primary:ctx".to_string()))
+        );
+
+        assert!(wrapper
+            .create_context_from_uri_and_string("ftp", "ctx")
+            .is_empty());
+    }
+
+    /// Asset holding the package and packaged path it was opened with.
+    struct PackagedAsset(String);
+
+    impl Asset for PackagedAsset {
+        fn get_size(&self) -> usize {
+            self.0.len()
+        }
+
+        fn get_buffer(&self) -> Result<&[u8], AssetError> {
+            Ok(self.0.as_bytes())
+        }
+
+        fn read(
+            &self,
+            _buffer: &mut [u8],
+            _count: usize,
+            _offset: usize,
+        ) -> Result<usize, AssetError> {
+            Err(AssetError::ReadError(String::new()))
+        }
+    }
+
+    /// Package resolver containing every path except those starting with
+    /// `missing`.
+    struct TestPackageResolver;
+
+    impl PackageResolver for TestPackageResolver {
+        fn resolve(
+            &self,
+            _resolver: &dyn Resolver,
+            _resolved_package_path: &ResolvedPath,
+            packaged_path: &str,
+        ) -> String {
+            if packaged_path.starts_with("missing") {
+                return String::new();
+            }
+            packaged_path.to_string()
+        }
+
+        fn open_asset(
+            &self,
+            _resolver: &dyn Resolver,
+            resolved_package_path: &ResolvedPath,
+            resolved_packaged_path: &str,
+        ) -> Result<Arc<dyn Asset>, ResolverError> {
+            Ok(Arc::new(PackagedAsset(format!(
+                "{}|{}",
+                resolved_package_path, resolved_packaged_path
+            ))))
+        }
+    }
+
+    fn package_wrapper() -> ResolverWrapper {
+        let mut wrapper = wrapper();
+        wrapper
+            .register_package_resolver(&["pkg", "PKG2"], Arc::new(TestPackageResolver))
+            .unwrap();
+        wrapper
+    }
+
+    #[test]
+    fn test_register_package_resolver() {
+        let mut wrapper = package_wrapper();
+        assert_eq!(
+            wrapper.get_package_extensions().collect::<Vec<_>>(),
+            ["pkg", "pkg2"]
+        );
+        assert!(wrapper.get_package_resolver("Pkg").is_some());
+        assert!(matches!(
+            wrapper.register_package_resolver(&["zip", "PKG"], Arc::new(TestPackageResolver)),
+            Err(ResolverError::InvalidPackageExtension(extension, _)) if extension == "pkg"
+        ));
+        assert!(matches!(
+            wrapper.register_package_resolver(&[".zip"], Arc::new(TestPackageResolver)),
+            Err(ResolverError::InvalidPackageExtension(extension, _)) if extension == ".zip"
+        ));
+        assert!(wrapper.get_package_resolver("zip").is_none());
+    }
+
+    #[test]
+    fn test_resolve_package_relative_path() {
+        let wrapper = package_wrapper();
+        assert_eq!(
+            wrapper.resolve("a.pkg[b.usd]").get_path_string(),
+            "primary|a.pkg[b.usd]"
+        );
+        assert_eq!(
+            wrapper
+                .resolve("s3://bucket/a.pkg[b.PKG2[c.usd]]")
+                .get_path_string(),
+            "s3|s3://bucket/a.pkg[b.PKG2[c.usd]]"
+        );
+        assert_eq!(
+            wrapper.resolve("/\\[x\\].pkg[b.usd]").get_path_string(),
+            "primary|/\\[x\\].pkg[b.usd]"
+        );
+        assert!(wrapper.resolve("a.pkg[missing.usd]").is_empty());
+        assert!(wrapper.resolve("a.pkg[b.pkg[missing.usd]]").is_empty());
+        assert!(wrapper.resolve("a.zip[b.usd]").is_empty());
+        assert!(wrapper.resolve("a.pkg[b.zip[c.usd]]").is_empty());
+    }
+
+    #[test]
+    fn test_open_package_relative_asset() {
+        let wrapper = package_wrapper();
+        let asset = wrapper
+            .open_asset(&ResolvedPath::new("primary|a.pkg[b.pkg[c.usd]]"))
+            .unwrap();
+        assert_eq!(asset.get_buffer().unwrap(), b"primary|a.pkg[b.pkg]|c.usd");
+
+        let asset = wrapper
+            .open_asset(&ResolvedPath::new("/\\[x\\].pkg[b.usd]"))
+            .unwrap();
+        assert_eq!(asset.get_buffer().unwrap(), b"/[x].pkg|b.usd");
+
+        assert!(wrapper
+            .open_asset(&ResolvedPath::new("a.zip[b.usd]"))
+            .is_err());
+        assert!(wrapper
+            .open_asset_for_write(&ResolvedPath::new("a.pkg[b.usd]"), WriteMode::Replace)
+            .is_err());
+    }
+
+    #[test]
+    fn test_package_relative_identifiers() {
+        let wrapper = package_wrapper();
+        let anchor = ResolvedPath::new("http://host/dir/a.usd");
+        assert_eq!(
+            wrapper.create_identifier("b.pkg[c.usd]", Some(&anchor)),
+            "http:b.pkg[c.usd]"
+        );
+        assert_eq!(
+            wrapper.create_identifier("b.pkg[c.pkg[d.usd]]", None),
+            "primary:b.pkg[c.pkg[d.usd]]"
+        );
+        assert_eq!(
+            wrapper.create_identifier_for_new_asset("b.pkg[c.usd]", &anchor),
+            "http:b.pkg[c.usd]"
+        );
+        assert_eq!(wrapper.get_extension("s3://a.pkg[b.PKG2[c.usda]]"), "usda");
+    }
+
+    /// Resolver caching its resolves, which counts the uncached ones.
+    #[cfg(feature = "std")]
+    #[derive(Default)]
+    struct CountingResolver {
+        resolve_cache: ResolveCache,
+        resolves: AtomicUsize,
+    }
+
+    #[cfg(feature = "std")]
+    impl Resolver for CountingResolver {
+        fn create_identifier(&self, asset_path: &str, _anchor: Option<&ResolvedPath>) -> String {
+            asset_path.to_string()
+        }
+
+        fn create_identifier_for_new_asset(
+            &self,
+            asset_path: &str,
+            _anchor: &ResolvedPath,
+        ) -> String {
+            asset_path.to_string()
+        }
+
+        fn resolve(&self, asset_path: &str) -> ResolvedPath {
+            self.resolve_cache
+                .resolve(asset_path, &ResolverContext::new(), || {
+                    self.resolves.fetch_add(1, Ordering::Relaxed);
+                    ResolvedPath::new(asset_path)
+                })
+        }
+
+        fn resolve_for_new_asset(&self, asset_path: &str) -> ResolvedPath {
+            ResolvedPath::new(asset_path)
+        }
+
+        fn open_asset(
+            &self,
+            _resolved_path: &ResolvedPath,
+        ) -> Result<Arc<dyn Asset>, ResolverError> {
+            Err(ResolverError::OpenAssetError(String::new()))
+        }
+
+        fn open_asset_for_write(
+            &self,
+            _resolved_path: &ResolvedPath,
+            _write_mode: WriteMode,
+        ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+            Err(ResolverError::OpenAssetError(String::new()))
+        }
+
+        fn begin_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+            self.resolve_cache.begin_cache_scope(cache_scope_data);
+        }
+
+        fn end_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+            self.resolve_cache.end_cache_scope(cache_scope_data);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_cache_scope_shared_across_threads() {
+        let counting = Arc::new(CountingResolver::default());
+        let mut wrapper = ResolverWrapper::new(Box::new(NamedResolver("primary")));
+        wrapper
+            .register_uri_resolver(&["count", "count2"], counting.clone())
+            .unwrap();
+
+        let cache = ResolverScopedCache::with_resolver(&wrapper, None);
+        wrapper.resolve("count:a.usd");
+        wrapper.resolve("count:a.usd");
+
+        let data = cache.get_cache_scope_data();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let _cache = ResolverScopedCache::with_resolver(&wrapper, Some(&data));
+                wrapper.resolve("count:a.usd");
+            });
+        });
+        assert_eq!(counting.resolves.load(Ordering::Relaxed), 1);
+
+        drop(cache);
+        wrapper.resolve("count:a.usd");
+        assert_eq!(counting.resolves.load(Ordering::Relaxed), 2);
+    }
+}
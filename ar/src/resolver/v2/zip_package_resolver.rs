@@ -0,0 +1,226 @@
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+
+use super::thread_local_stack::ThreadLocalStack;
+use super::{CacheScopeData, PackageResolver, Resolver};
+use crate::sync::{lock, Mutex};
+use crate::{join_package_relative_path, Asset, AssetError, ResolvedPath, ResolverError, ZipFile};
+
+/// Alignment of the file data in `.usdz` packages required by the usdz
+/// specification.
+pub const USDZ_DATA_ALIGNMENT: usize = 64;
+
+/// Zip archives opened during a single cache scope, keyed by resolved
+/// package path.
+#[derive(Debug)]
+struct ZipFileCacheScope {
+    // Identifies the resolver that opened the scope, so that the scope data
+    // of other resolvers is not mistaken for it.
+    resolver_id: usize,
+    zip_files: Mutex<BTreeMap<String, Arc<ZipFile>>>,
+}
+
+impl ZipFileCacheScope {
+    fn new(resolver_id: usize) -> Self {
+        Self {
+            resolver_id,
+            zip_files: Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+/// [`PackageResolver`] implementation for zip archives, including `.usdz`
+/// packages.
+///
+/// Only files stored uncompressed in the archive can be opened. They are
+/// read directly from the archive's asset without copying, see
+/// [`crate::ZipFileAsset`]. Packages may require the data of each file to
+/// be aligned, as `.usdz` packages do, in which case opening a misaligned
+/// file fails with [`AssetError::MisalignedData`].
+///
+/// While a cache scope is open, each archive's central directory is only
+/// read once. Cache scope data filled in by other resolvers is neither
+/// reused nor closed.
+#[derive(Debug)]
+pub struct ZipPackageResolver {
+    alignment: usize,
+    scopes: ThreadLocalStack<Arc<ZipFileCacheScope>>,
+}
+
+impl ZipPackageResolver {
+    /// Creates a resolver for plain zip archives, whose file data may be
+    /// stored at any offset.
+    pub fn new() -> Self {
+        Self::with_alignment(1)
+    }
+
+    /// Creates a resolver for `.usdz` packages, whose file data must be
+    /// aligned to [`USDZ_DATA_ALIGNMENT`] bytes.
+    pub fn usdz() -> Self {
+        Self::with_alignment(USDZ_DATA_ALIGNMENT)
+    }
+
+    /// Creates a resolver for archives whose file data must be aligned to
+    /// `alignment` bytes.
+    pub fn with_alignment(alignment: usize) -> Self {
+        Self {
+            alignment: alignment.max(1),
+            scopes: ThreadLocalStack::new(),
+        }
+    }
+
+    /// Returns the scope held by `cache_scope_data` if it was opened by this
+    /// resolver.
+    fn get_own_scope(&self, cache_scope_data: &CacheScopeData) -> Option<Arc<ZipFileCacheScope>> {
+        cache_scope_data
+            .clone()
+            .and_then(|data| data.downcast::<ZipFileCacheScope>().ok())
+            .filter(|scope| scope.resolver_id == self.scopes.get_id())
+    }
+
+    /// Opens the archive at `resolved_package_path` with `resolver`, or
+    /// returns the one opened earlier in the current cache scope.
+    fn open_zip_file(
+        &self,
+        resolver: &dyn Resolver,
+        resolved_package_path: &ResolvedPath,
+    ) -> Result<Arc<ZipFile>, ResolverError> {
+        let scope = self.scopes.top();
+        let path = resolved_package_path.get_path_string();
+        if let Some(zip_file) = scope
+            .as_ref()
+            .and_then(|scope| lock(&scope.zip_files).get(path).cloned())
+        {
+            return Ok(zip_file);
+        }
+
+        let asset = resolver.open_asset(resolved_package_path)?;
+        let zip_file = ZipFile::open(asset)
+            .map(Arc::new)
+            .map_err(|err| ResolverError::InvalidAsset(path.to_owned(), err))?;
+        if let Some(scope) = scope {
+            lock(&scope.zip_files).insert(path.to_owned(), zip_file.clone());
+        }
+        Ok(zip_file)
+    }
+}
+
+impl Default for ZipPackageResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PackageResolver for ZipPackageResolver {
+    fn resolve(
+        &self,
+        resolver: &dyn Resolver,
+        resolved_package_path: &ResolvedPath,
+        packaged_path: &str,
+    ) -> String {
+        match self.open_zip_file(resolver, resolved_package_path) {
+            Ok(zip_file) if zip_file.find(packaged_path).is_some() => packaged_path.to_owned(),
+            Ok(_) => String::new(),
+            Err(err) => {
+                log::debug!("cannot resolve `{}`: {}", packaged_path, err);
+                String::new()
+            }
+        }
+    }
+
+    fn open_asset(
+        &self,
+        resolver: &dyn Resolver,
+        resolved_package_path: &ResolvedPath,
+        resolved_packaged_path: &str,
+    ) -> Result<Arc<dyn Asset>, ResolverError> {
+        let resolved_path = || {
+            join_package_relative_path(&[
+                resolved_package_path.get_path_string(),
+                resolved_packaged_path,
+            ])
+        };
+
+        let zip_file = self.open_zip_file(resolver, resolved_package_path)?;
+        if let Some(info) = zip_file.find(resolved_packaged_path) {
+            if info.data_offset % self.alignment != 0 {
+                return Err(ResolverError::InvalidAsset(
+                    resolved_path(),
+                    AssetError::MisalignedData(
+                        resolved_packaged_path.to_owned(),
+                        info.data_offset,
+                        self.alignment,
+                    ),
+                ));
+            }
+        }
+
+        match zip_file.open_file(resolved_packaged_path) {
+            Ok(asset) => Ok(Arc::new(asset)),
+            Err(err) => Err(ResolverError::InvalidAsset(resolved_path(), err)),
+        }
+    }
+
+    fn begin_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        // Data of scopes opened by other resolvers is ignored and replaced.
+        let scope = self
+            .get_own_scope(cache_scope_data)
+            .or_else(|| self.scopes.top())
+            .unwrap_or_else(|| Arc::new(ZipFileCacheScope::new(self.scopes.get_id())));
+        *cache_scope_data = Some(scope.clone());
+        self.scopes.push(scope);
+    }
+
+    fn end_cache_scope(&self, cache_scope_data: &mut CacheScopeData) {
+        // Only scopes opened by this resolver are closed.
+        if self.get_own_scope(cache_scope_data).is_some() {
+            self.scopes.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zip_file::build_zip;
+    use crate::InMemoryResolver;
+
+    #[test]
+    fn test_scope_data_of_other_resolver_ignored() {
+        let resolver = InMemoryResolver::new();
+        resolver.insert("/a.zip", build_zip(&[("a.usda", b"#usda 1.0", 0)], 1));
+        let package_path = ResolvedPath::new("/a.zip");
+        let first = ZipPackageResolver::new();
+        let second = ZipPackageResolver::new();
+
+        let mut first_data = CacheScopeData::default();
+        first.begin_cache_scope(&mut first_data);
+        assert_eq!(first.resolve(&resolver, &package_path, "a.usda"), "a.usda");
+
+        let mut second_data = first_data.clone();
+        second.begin_cache_scope(&mut second_data);
+        assert!(!Arc::ptr_eq(
+            first_data.as_ref().unwrap(),
+            second_data.as_ref().unwrap()
+        ));
+
+        // Only the first resolver still sees the archive it cached.
+        resolver.insert("/a.zip", build_zip(&[("b.usda", b"#usda 1.0", 0)], 1));
+        assert_eq!(first.resolve(&resolver, &package_path, "a.usda"), "a.usda");
+        assert_eq!(second.resolve(&resolver, &package_path, "a.usda"), "");
+
+        // Ending a scope with the data of another resolver does nothing.
+        second.end_cache_scope(&mut first_data);
+        first.end_cache_scope(&mut second_data);
+        assert_eq!(first.resolve(&resolver, &package_path, "a.usda"), "a.usda");
+        assert_eq!(second.resolve(&resolver, &package_path, "b.usda"), "b.usda");
+
+        second.end_cache_scope(&mut second_data);
+        first.end_cache_scope(&mut first_data);
+        assert_eq!(first.resolve(&resolver, &package_path, "a.usda"), "");
+        assert!(first.scopes.top().is_none());
+        assert!(second.scopes.top().is_none());
+    }
+}
@@ -1,12 +1,22 @@
 mod client_context;
 pub use client_context::*;
 
-cfg_if::cfg_if! {
-    if #[cfg(feature = "resolver_v1")] {
-        mod resolver_context_v1;
-        pub use resolver_context_v1::ResolverContext;
-    } else if #[cfg(feature = "resolver_v2")] {
-        mod resolver_context_v2;
-        pub use resolver_context_v2::ResolverContext;
-    }
-}
+#[cfg(feature = "resolver_v2")]
+mod context_registry;
+#[cfg(feature = "resolver_v2")]
+pub use context_registry::{register_client_context, ContextError, SerializableClientContext};
+
+#[cfg(any(feature = "resolver_v1", feature = "resolver_v2"))]
+mod default_resolver_context;
+#[cfg(any(feature = "resolver_v1", feature = "resolver_v2"))]
+pub use default_resolver_context::*;
+
+#[cfg(feature = "resolver_v2")]
+mod lockfile;
+#[cfg(feature = "resolver_v2")]
+pub use lockfile::*;
+
+#[cfg(feature = "resolver_v1")]
+pub(crate) mod resolver_context_v1;
+#[cfg(feature = "resolver_v2")]
+pub(crate) mod resolver_context_v2;
@@ -1,10 +1,16 @@
 use alloc::boxed::Box;
-use core::any::Any;
+use core::any::{Any, TypeId};
 use core::cmp::Ordering;
 use core::fmt::Debug;
+use core::hash::{Hash, Hasher};
 
 /// The trait for Client Contexts
-pub trait ClientContext: ClientContextClone + Debug + ClientContextCmp {}
+///
+/// Client contexts must be `Send` and `Sync` so that a [`crate::ResolverContext`]
+/// holding them can be bound and resolved against from any thread. They must
+/// also be totally ordered and hashable, since resolver contexts are used as
+/// keys in caches and maps.
+pub trait ClientContext: ClientContextClone + Debug + ClientContextCmp + Send + Sync {}
 
 #[doc(hidden)]
 /// Trait to clone a [`ClientContext`]
@@ -29,7 +35,7 @@ impl Clone for Box<dyn ClientContext> {
 }
 
 #[doc(hidden)]
-/// Trait to compare two Client Contexts
+/// Trait to compare and hash two Client Contexts
 pub trait ClientContextCmp {
     /// An &Any can be cast to a reference to a concrete type.
     fn as_any(&self) -> &dyn Any;
@@ -37,13 +43,16 @@ pub trait ClientContextCmp {
     /// Perform the equality tests.
     fn eq_box(&self, other: &dyn ClientContextCmp) -> bool;
 
-    /// Perform the partial comparison tests
-    fn partial_cmp_box(&self, other: &dyn ClientContextCmp) -> Option<Ordering>;
+    /// Perform the comparison tests.
+    fn cmp_box(&self, other: &dyn ClientContextCmp) -> Ordering;
+
+    /// Feed the context into `state`.
+    fn hash_box(&self, state: &mut dyn Hasher);
 }
 
 impl<Context> ClientContextCmp for Context
 where
-    Context: 'static + PartialEq + PartialOrd,
+    Context: 'static + Eq + Ord + Hash,
 {
     fn as_any(&self) -> &dyn Any {
         self
@@ -55,35 +64,48 @@ where
         other
             .as_any()
             .downcast_ref::<Self>()
-            .map_or(false, |ctx| ctx == self)
+            .is_some_and(|ctx| ctx == self)
     }
 
-    fn partial_cmp_box(&self, other: &dyn ClientContextCmp) -> Option<Ordering> {
-        // Do a type-safe casting. If the types are different,
-        // return None, otherwise tests the values for order.
-        other
-            .as_any()
-            .downcast_ref::<Self>()
-            .and_then(|ctx| self.partial_cmp_box(ctx))
+    fn cmp_box(&self, other: &dyn ClientContextCmp) -> Ordering {
+        // Contexts of different types are ordered by type, otherwise by
+        // value, so that any two contexts can be compared.
+        match other.as_any().downcast_ref::<Self>() {
+            Some(ctx) => self.cmp(ctx),
+            None => TypeId::of::<Self>().cmp(&other.as_any().type_id()),
+        }
     }
-}
 
-impl PartialOrd for Box<dyn ClientContext> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.partial_cmp_box(other)
+    fn hash_box(&self, mut state: &mut dyn Hasher) {
+        TypeId::of::<Self>().hash(&mut state);
+        self.hash(&mut state);
     }
 }
 
-// impl Ord for Box<dyn ClientContext> {
-//     fn cmp(&self, other: &Self) -> Ordering {
-//         self.cmp(other)
-//     }
-// }
-
 impl PartialEq for Box<dyn ClientContext> {
     fn eq(&self, other: &Self) -> bool {
-        self.eq_box(other)
+        // Compare the boxed contexts rather than the boxes themselves, which
+        // would otherwise dispatch back into this implementation.
+        self.as_ref().eq_box(other.as_ref())
     }
 }
 
 impl Eq for Box<dyn ClientContext> {}
+
+impl PartialOrd for Box<dyn ClientContext> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Box<dyn ClientContext> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_ref().cmp_box(other.as_ref())
+    }
+}
+
+impl Hash for Box<dyn ClientContext> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().hash_box(state);
+    }
+}
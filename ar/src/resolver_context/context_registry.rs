@@ -0,0 +1,198 @@
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use core::any::TypeId;
+use core::fmt;
+
+use super::{ClientContext, DefaultResolverContext, Lockfile};
+use crate::sync::{lock, Mutex, MutexGuard};
+
+/// Errors from serializing and deserializing client contexts
+#[derive(Clone, Debug)]
+pub enum ContextError {
+    /// No client context type is registered for the given type tag
+    UnregisteredTypeTag(String),
+
+    /// The type of the given client context is not registered
+    UnregisteredContext(String),
+
+    /// The given type tag is already registered for another client context
+    /// type
+    DuplicateTypeTag(String),
+
+    /// The client context with the given type tag cannot be deserialized
+    InvalidContext(String, String),
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContextError::UnregisteredTypeTag(tag) => {
+                write!(f, "no client context type is registered for tag `{}`", tag)
+            }
+            ContextError::UnregisteredContext(context) => {
+                write!(f, "client context type is not registered: {}", context)
+            }
+            ContextError::DuplicateTypeTag(tag) => {
+                write!(
+                    f,
+                    "cannot register client context type: tag `{}` is already registered",
+                    tag
+                )
+            }
+            ContextError::InvalidContext(tag, reason) => {
+                write!(f, "invalid client context `{}`: {}", tag, reason)
+            }
+        }
+    }
+}
+
+/// The trait for client contexts that can be serialized, so that a
+/// [`crate::ResolverContext`] holding them can be persisted and rebuilt in
+/// another process.
+///
+/// Each type is identified by a stable [`TYPE_TAG`], which must be unique
+/// across all registered types, and must be registered with
+/// [`register_client_context`] before resolver contexts holding it are
+/// serialized or deserialized.
+///
+/// [`TYPE_TAG`]: SerializableClientContext::TYPE_TAG
+///
+/// # Examples
+/// ```
+/// use ar::{register_client_context, ClientContext, SerializableClientContext};
+///
+/// #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// struct ShotContext(String);
+///
+/// impl ClientContext for ShotContext {}
+///
+/// impl SerializableClientContext for ShotContext {
+///     const TYPE_TAG: &'static str = "StudioShotContext";
+///
+///     fn to_context_string(&self) -> String {
+///         self.0.clone()
+///     }
+///
+///     fn from_context_string(context_str: &str) -> Result<Self, String> {
+///         Ok(ShotContext(context_str.into()))
+///     }
+/// }
+///
+/// register_client_context::<ShotContext>().unwrap();
+/// ```
+pub trait SerializableClientContext: ClientContext + Sized + 'static {
+    /// Tag identifying this type in serialized resolver contexts.
+    const TYPE_TAG: &'static str;
+
+    /// Returns the string representation of this context.
+    fn to_context_string(&self) -> String;
+
+    /// Creates a context from the string returned by
+    /// [`SerializableClientContext::to_context_string`].
+    fn from_context_string(context_str: &str) -> Result<Self, String>;
+}
+
+impl SerializableClientContext for DefaultResolverContext {
+    const TYPE_TAG: &'static str = "ArDefaultResolverContext";
+
+    fn to_context_string(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_context_string(context_str: &str) -> Result<Self, String> {
+        Ok(Self::from_path_list(context_str))
+    }
+}
+
+struct ContextType {
+    type_id: TypeId,
+    serialize: fn(&dyn ClientContext) -> Option<String>,
+    deserialize: fn(&str) -> Result<Box<dyn ClientContext>, String>,
+}
+
+impl ContextType {
+    fn of<Context: SerializableClientContext>() -> Self {
+        Self {
+            type_id: TypeId::of::<Context>(),
+            serialize: |context| {
+                context
+                    .as_any()
+                    .downcast_ref::<Context>()
+                    .map(Context::to_context_string)
+            },
+            deserialize: |context_str| {
+                Context::from_context_string(context_str)
+                    .map(|context| Box::new(context) as Box<dyn ClientContext>)
+            },
+        }
+    }
+}
+
+/// Registered client context types, keyed by type tag.
+static CONTEXT_TYPES: Mutex<BTreeMap<String, ContextType>> = Mutex::new(BTreeMap::new());
+
+/// Locks the registered client context types, registering the built-in
+/// ones on first use.
+fn context_types() -> MutexGuard<'static, BTreeMap<String, ContextType>> {
+    let mut context_types = lock(&CONTEXT_TYPES);
+    if context_types.is_empty() {
+        context_types.insert(
+            DefaultResolverContext::TYPE_TAG.to_owned(),
+            ContextType::of::<DefaultResolverContext>(),
+        );
+        context_types.insert(Lockfile::TYPE_TAG.to_owned(), ContextType::of::<Lockfile>());
+    }
+    context_types
+}
+
+/// Registers `Context` so resolver contexts holding it can be serialized and
+/// deserialized. Registering the same type more than once has no effect.
+///
+/// [`DefaultResolverContext`] and [`Lockfile`] are always registered.
+///
+/// # Errors
+/// Fails with [`ContextError::DuplicateTypeTag`] if the type tag of
+/// `Context` is already registered for another type.
+pub fn register_client_context<Context: SerializableClientContext>() -> Result<(), ContextError> {
+    let mut context_types = context_types();
+    match context_types.get(Context::TYPE_TAG) {
+        Some(context_type) if context_type.type_id == TypeId::of::<Context>() => Ok(()),
+        Some(_) => Err(ContextError::DuplicateTypeTag(Context::TYPE_TAG.to_owned())),
+        None => {
+            context_types.insert(Context::TYPE_TAG.to_owned(), ContextType::of::<Context>());
+            Ok(())
+        }
+    }
+}
+
+/// Returns the type tag and string representation of `context`.
+pub(crate) fn serialize_client_context(
+    context: &dyn ClientContext,
+) -> Result<(String, String), ContextError> {
+    // Copy the function out so the registry isn't locked while it runs.
+    let type_id = context.as_any().type_id();
+    let (tag, serialize) = context_types()
+        .iter()
+        .find(|(_, context_type)| context_type.type_id == type_id)
+        .map(|(tag, context_type)| (tag.clone(), context_type.serialize))
+        .ok_or_else(|| ContextError::UnregisteredContext(format!("{:?}", context)))?;
+    serialize(context)
+        .map(|context_str| (tag, context_str))
+        .ok_or_else(|| ContextError::UnregisteredContext(format!("{:?}", context)))
+}
+
+/// Creates the client context of the type registered for `tag` from its
+/// string representation.
+pub(crate) fn deserialize_client_context(
+    tag: &str,
+    context_str: &str,
+) -> Result<Box<dyn ClientContext>, ContextError> {
+    // Copy the function out so the registry isn't locked while it runs.
+    let deserialize = context_types()
+        .get(tag)
+        .map(|context_type| context_type.deserialize)
+        .ok_or_else(|| ContextError::UnregisteredTypeTag(tag.to_owned()))?;
+    deserialize(context_str).map_err(|reason| ContextError::InvalidContext(tag.to_owned(), reason))
+}
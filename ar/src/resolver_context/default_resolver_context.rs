@@ -0,0 +1,84 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::filesystem;
+use crate::ClientContext;
+
+/// Separator between entries of a search path list, e.g. in the strings
+/// accepted by [`DefaultResolverContext::from_path_list`].
+#[cfg(windows)]
+pub const PATH_LIST_SEPARATOR: char = ';';
+
+/// Separator between entries of a search path list, e.g. in the strings
+/// accepted by [`DefaultResolverContext::from_path_list`].
+#[cfg(not(windows))]
+pub const PATH_LIST_SEPARATOR: char = ':';
+
+/// Resolver context object that specifies a search path to be used during
+/// asset resolution by [`crate::DefaultResolver`]. Search-path style asset
+/// paths like `props/chair.usd` are looked up relative to each directory in
+/// the search path, in order.
+///
+/// # Examples
+/// ```
+/// use ar::DefaultResolverContext;
+///
+/// let context = DefaultResolverContext::new(&["/assets", "", "/shared/assets"]);
+/// assert_eq!(context.get_search_path(), &["/assets", "/shared/assets"]);
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DefaultResolverContext {
+    search_path: Vec<String>,
+}
+
+impl DefaultResolverContext {
+    /// Creates a context with the given `search_path`. Relative directories
+    /// are made absolute and empty entries are ignored.
+    pub fn new(search_path: &[&str]) -> Self {
+        Self {
+            search_path: search_path
+                .iter()
+                .filter(|path| !path.is_empty())
+                .map(|path| filesystem::abs_path(path))
+                .collect(),
+        }
+    }
+
+    /// Creates a context from a list of directories separated by
+    /// [`PATH_LIST_SEPARATOR`].
+    ///
+    /// # Examples
+    /// ```
+    /// use ar::DefaultResolverContext;
+    ///
+    /// # #[cfg(not(windows))]
+    /// # {
+    /// let context = DefaultResolverContext::from_path_list("/assets:/shared/assets");
+    /// assert_eq!(context.get_search_path(), &["/assets", "/shared/assets"]);
+    /// # }
+    /// ```
+    pub fn from_path_list(path_list: &str) -> Self {
+        let search_path: Vec<&str> = path_list.split(PATH_LIST_SEPARATOR).collect();
+        Self::new(&search_path)
+    }
+
+    /// Returns the search path held by this context.
+    pub fn get_search_path(&self) -> &[String] {
+        &self.search_path
+    }
+}
+
+impl ClientContext for DefaultResolverContext {}
+
+impl fmt::Display for DefaultResolverContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, path) in self.search_path.iter().enumerate() {
+            if index > 0 {
+                write!(f, "{}", PATH_LIST_SEPARATOR)?;
+            }
+            f.write_str(path)?;
+        }
+        Ok(())
+    }
+}
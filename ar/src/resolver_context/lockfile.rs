@@ -0,0 +1,228 @@
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+
+use super::{ClientContext, ContextError, SerializableClientContext};
+use crate::ResolvedPath;
+
+/// Resolution of an identifier recorded in a [`Lockfile`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct LockedResolution {
+    version: Option<String>,
+    resolved_path: ResolvedPath,
+}
+
+impl LockedResolution {
+    /// Creates a resolution to `resolved_path`, for the given version of the
+    /// asset if it is versioned.
+    pub fn new(version: Option<&str>, resolved_path: ResolvedPath) -> Self {
+        Self {
+            version: version.map(ToOwned::to_owned),
+            resolved_path,
+        }
+    }
+
+    /// Returns the version the identifier resolved to, if the asset is
+    /// versioned.
+    pub fn get_version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Returns the path the identifier resolved to.
+    pub fn get_resolved_path(&self) -> &ResolvedPath {
+        &self.resolved_path
+    }
+}
+
+/// Client context recording the resolution of identifiers, so that they can
+/// be replayed later. See [`crate::VersionedResolver`], which records the
+/// resolutions made during a session in a lockfile and replays the
+/// resolutions of the lockfile bound in its context.
+///
+/// Lockfiles are serialized as one line per identifier, sorted by
+/// identifier, holding the identifier, the version and the resolved path,
+/// separated by tabs. The version is empty for unversioned assets. As a
+/// consequence, identifiers, versions and resolved paths cannot hold tabs or
+/// line breaks, and [`Lockfile::insert`] rejects resolutions that do.
+///
+/// Cloning a lockfile is cheap, its entries are shared until one of the
+/// copies is modified.
+///
+/// # Examples
+/// ```
+/// use ar::{LockedResolution, Lockfile, ResolvedPath, SerializableClientContext};
+///
+/// let mut lockfile = Lockfile::new();
+/// let resolution =
+///     LockedResolution::new(Some("v12"), ResolvedPath::new("/assets/char/hero/v12/hero.usd"));
+/// lockfile.insert("char/hero.usd@latest", resolution).unwrap();
+/// assert_eq!(
+///     lockfile.to_context_string(),
+///     "char/hero.usd@latest\tv12\t/assets/char/hero/v12/hero.usd\n"
+/// );
+/// assert_eq!(
+///     Lockfile::from_context_string(&lockfile.to_context_string()),
+///     Ok(lockfile)
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Lockfile {
+    entries: Arc<BTreeMap<String, LockedResolution>>,
+}
+
+impl Lockfile {
+    /// Creates an empty lockfile.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the resolution of `identifier`, replacing any previous one.
+    ///
+    /// Fails with [`ContextError::InvalidContext`] if the identifier, the
+    /// version or the resolved path holds a tab or a line break, since the
+    /// lockfile could not be serialized.
+    pub fn insert(
+        &mut self,
+        identifier: &str,
+        resolution: LockedResolution,
+    ) -> Result<(), ContextError> {
+        let fields = [
+            identifier,
+            resolution.get_version().unwrap_or_default(),
+            resolution.get_resolved_path().get_path_string(),
+        ];
+        if let Some(field) = fields
+            .iter()
+            .find(|field| field.contains(['\t', '\n', '\r']))
+        {
+            return Err(ContextError::InvalidContext(
+                Self::TYPE_TAG.to_owned(),
+                format!("`{}` holds a tab or a line break", field.escape_default()),
+            ));
+        }
+        Arc::make_mut(&mut self.entries).insert(identifier.to_owned(), resolution);
+        Ok(())
+    }
+
+    /// Returns the recorded resolution of `identifier`, if any.
+    pub fn get(&self, identifier: &str) -> Option<&LockedResolution> {
+        self.entries.get(identifier)
+    }
+
+    /// Returns the recorded identifiers and their resolution, sorted by
+    /// identifier.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &LockedResolution)> {
+        self.entries
+            .iter()
+            .map(|(identifier, resolution)| (identifier.as_str(), resolution))
+    }
+
+    /// Returns the number of recorded identifiers.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no identifier is recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl ClientContext for Lockfile {}
+
+impl SerializableClientContext for Lockfile {
+    const TYPE_TAG: &'static str = "ArLockfile";
+
+    fn to_context_string(&self) -> String {
+        let mut context_str = String::new();
+        for (identifier, resolution) in self.iter() {
+            context_str += &format!(
+                "{}\t{}\t{}\n",
+                identifier,
+                resolution.get_version().unwrap_or_default(),
+                resolution.get_resolved_path()
+            );
+        }
+        context_str
+    }
+
+    fn from_context_string(context_str: &str) -> Result<Self, String> {
+        let mut lockfile = Lockfile::new();
+        for line in context_str.lines().filter(|line| !line.is_empty()) {
+            let mut fields = line.splitn(3, '\t');
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some(identifier), Some(version), Some(resolved_path))
+                    if !identifier.is_empty() && !resolved_path.is_empty() =>
+                {
+                    let version = Some(version).filter(|version| !version.is_empty());
+                    let resolution =
+                        LockedResolution::new(version, ResolvedPath::new(resolved_path));
+                    lockfile
+                        .insert(identifier, resolution)
+                        .map_err(|err| err.to_string())?;
+                }
+                _ => return Err(format!("invalid lockfile entry `{}`", line)),
+            }
+        }
+        Ok(lockfile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_string() {
+        let mut lockfile = Lockfile::new();
+        lockfile
+            .insert(
+                "/shot/layout.usda",
+                LockedResolution::new(None, ResolvedPath::new("/shot/layout.usda")),
+            )
+            .unwrap();
+        lockfile
+            .insert(
+                "char/hero.usd@approved",
+                LockedResolution::new(Some("v3"), ResolvedPath::new("/assets/hero/v3/hero.usd")),
+            )
+            .unwrap();
+
+        let context_str = lockfile.to_context_string();
+        assert_eq!(
+            context_str,
+            "/shot/layout.usda\t\t/shot/layout.usda\n\
+             char/hero.usd@approved\tv3\t/assets/hero/v3/hero.usd\n"
+        );
+        let parsed = Lockfile::from_context_string(&context_str).unwrap();
+        assert_eq!(parsed, lockfile);
+        assert_eq!(parsed.get("/shot/layout.usda").unwrap().get_version(), None);
+
+        assert!(Lockfile::from_context_string("char/hero.usd@latest\tv3").is_err());
+        assert!(Lockfile::from_context_string("char/hero.usd@latest\tv3\t").is_err());
+        assert_eq!(Lockfile::from_context_string(""), Ok(Lockfile::new()));
+        assert!(Lockfile::from_context_string("char/hero.usd@latest\tv3\t/a\rb").is_err());
+    }
+
+    #[test]
+    fn test_unserializable_entries() {
+        let mut lockfile = Lockfile::new();
+        let resolution = |version, path| LockedResolution::new(version, ResolvedPath::new(path));
+        assert!(matches!(
+            lockfile.insert("char/hero\t.usd", resolution(None, "/assets/hero.usd")),
+            Err(ContextError::InvalidContext(tag, _)) if tag == Lockfile::TYPE_TAG
+        ));
+        assert!(lockfile
+            .insert(
+                "char/hero.usd",
+                resolution(Some("v1\n"), "/assets/hero.usd")
+            )
+            .is_err());
+        assert!(lockfile
+            .insert("char/hero.usd", resolution(None, "/assets/hero\r\n.usd"))
+            .is_err());
+        assert!(lockfile.is_empty());
+    }
+}
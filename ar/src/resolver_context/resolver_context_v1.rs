@@ -12,10 +12,10 @@ use crate::ClientContext;
 /// A client-defined context object must implement the following traits:
 ///   - [`std::clone::Clone`]
 ///   - [`std::fmt::Debug`]
-///   - [`std::cmp::PartialOrd`]
-///   - [`std::cmp::PartialEq`]
-///   - ['std::hash::Hash`]
-#[derive(Clone, Debug, Eq, PartialEq, PartialOrd)]
+///   - [`std::cmp::Eq`] and [`std::cmp::PartialEq`]
+///   - [`std::cmp::Ord`] and [`std::cmp::PartialOrd`]
+///   - [`std::hash::Hash`]
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct ResolverContext {
     context: Option<Box<dyn ClientContext>>,
 }
@@ -25,9 +25,10 @@ impl ResolverContext {
     ///
     /// # Examples
     /// ```
-    /// use ar::{ClientContext, ResolverContext};
+    /// use ar::v1::ResolverContext;
+    /// use ar::ClientContext;
     ///
-    /// #[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+    /// #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     /// struct Context1 {
     ///     id: usize,
     /// }
@@ -48,16 +49,17 @@ impl ResolverContext {
     ///
     /// # Examples
     /// ```
-    /// use ar::{ClientContext, ResolverContext};
+    /// use ar::v1::ResolverContext;
+    /// use ar::ClientContext;
     ///
-    /// #[derive(Clone, Debug, PartialOrd, PartialEq, Hash)]
+    /// #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     /// struct Context1 {
     ///     id: usize,
     /// }
     ///
     /// impl ClientContext for Context1 {}
     ///
-    /// #[derive(Clone, Debug, PartialOrd, PartialEq, Hash)]
+    /// #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
     /// struct Context2 {
     ///     id: usize,
     /// }
@@ -67,27 +69,36 @@ impl ResolverContext {
     /// let client_ctx = Context1 { id: 1 };
     /// let resolver = ResolverContext::new(client_ctx);
     /// assert!(resolver.get::<Context2>().is_none());
-    /// assert!(resolver.get::<Context1>().is_some());
+    /// assert_eq!(resolver.get::<Context1>(), Some(&Context1 { id: 1 }));
     /// ```
-    pub fn get<Context>(&self) -> Option<&dyn ClientContext>
+    pub fn get<Context>(&self) -> Option<&Context>
     where
         Context: ClientContext + 'static,
     {
-        if let Some(context) = &self.context {
-            if context
-                .as_ref()
-                .as_any()
-                .downcast_ref::<Context>()
-                .is_some()
-            {
-                return Some(context.as_ref());
-            }
-        }
-        None
+        self.context
+            .as_ref()
+            .and_then(|context| context.as_ref().as_any().downcast_ref())
+    }
+
+    /// Creates a resolver context holding `context`, if any.
+    pub(crate) fn from_boxed(context: Option<Box<dyn ClientContext>>) -> Self {
+        Self { context }
+    }
+
+    /// Returns the context object held in this asset resolver context.
+    #[cfg(feature = "resolver_v2")]
+    pub(crate) fn into_boxed(self) -> Option<Box<dyn ClientContext>> {
+        self.context
     }
 
     /// Returns whether this resolver context is empty.
     pub fn is_empty(&self) -> bool {
-        self.context.is_some()
+        self.context.is_none()
+    }
+}
+
+impl Default for ResolverContext {
+    fn default() -> Self {
+        Self::from_boxed(None)
     }
 }
@@ -1,11 +1,12 @@
 use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
 use alloc::vec::{IntoIter, Vec};
-use core::cmp::PartialOrd;
-use core::fmt::Debug;
-use core::hash::Hash;
+use core::any::TypeId;
 use core::ops::AddAssign;
 
-use super::ClientContext;
+use super::context_registry::{deserialize_client_context, serialize_client_context};
+use super::{ClientContext, ContextError};
 
 /// An asset resolver context allows clients to provide additional data
 /// to the resolver for use during resolution. Clients may provide this
@@ -13,7 +14,21 @@ use super::ClientContext;
 /// An ArResolverContext is simply a wrapper around these objects that
 /// allows it to be treated as a single type. Note that an ArResolverContext
 /// may not hold multiple context objects with the same type.
-#[derive(Clone, Debug, Eq, PartialEq, PartialOrd)]
+///
+/// Resolver contexts are hashable and totally ordered, so they can be used
+/// as keys in a `BTreeMap` or `HashMap`. Two resolver contexts are equal if
+/// they hold equal context objects, regardless of the order in which the
+/// objects were added.
+///
+/// Resolver contexts holding only [`crate::SerializableClientContext`]s can be
+/// serialized with [`ResolverContext::serialize_contexts`], and with the
+/// `serde` feature, through `serde`. They are represented as a map from the
+/// type tag of each context to its string representation, e.g. in JSON:
+///
+/// ```json
+/// {"ArDefaultResolverContext": "/shots/s01:/assets"}
+/// ```
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct ResolverContext {
     contexts: Vec<Box<dyn ClientContext>>,
 }
@@ -31,15 +46,45 @@ impl ResolverContext {
         Self { contexts: vec![] }
     }
 
-    /// Push a client context
+    /// Push a client context. If this resolver context already holds a
+    /// context of the same type, it is replaced by `context`.
+    ///
+    /// # Examples
+    /// ```
+    /// use ar::{ClientContext, ResolverContext};
+    ///
+    /// #[derive(Clone, Debug, PartialOrd, PartialEq, Eq, Ord, Hash)]
+    /// struct Context(usize);
+    ///
+    /// impl ClientContext for Context {}
+    ///
+    /// let mut resolver = ResolverContext::new();
+    /// resolver.push(Context(1)).push(Context(2));
+    /// assert_eq!(resolver.get::<Context>(), Some(&Context(2)));
+    /// ```
     pub fn push<Context>(&mut self, context: Context) -> &mut Self
     where
-        Context: 'static + ClientContext + Debug + PartialOrd + PartialEq + Hash,
+        Context: 'static + ClientContext,
     {
-        self.contexts.push(Box::new(context));
+        self.insert(Box::new(context), true);
         self
     }
 
+    /// Adds `context`, keeping the contexts sorted by type. If a context of
+    /// the same type is already held, it is only replaced if `replace` is
+    /// set.
+    pub(crate) fn insert(&mut self, context: Box<dyn ClientContext>, replace: bool) {
+        let type_id = context.as_ref().as_any().type_id();
+        match self
+            .contexts
+            .binary_search_by(|held| held.as_ref().as_any().type_id().cmp(&type_id))
+        {
+            Ok(index) if replace => self.contexts[index] = context,
+            Ok(_) => {}
+            Err(index) => self.contexts.insert(index, context),
+        }
+    }
+
     /// Return pointer to the context object held in this asset resolver
     /// context
     ///
@@ -65,23 +110,66 @@ impl ResolverContext {
     /// let ctx1 = Context1 { id: 1 };
     /// resolver.push(ctx1);
     /// assert!(resolver.get::<Context2>().is_none());
-    /// assert!(resolver.get::<Context1>().is_some());
+    /// assert_eq!(resolver.get::<Context1>(), Some(&Context1 { id: 1 }));
     /// ```
-    pub fn get<Context>(&self) -> Option<&dyn ClientContext>
+    pub fn get<Context>(&self) -> Option<&Context>
     where
         Context: 'static + ClientContext,
     {
-        for context in &self.contexts {
-            if context
-                .as_ref()
-                .as_any()
-                .downcast_ref::<Context>()
-                .is_some()
-            {
-                return Some(context.as_ref());
-            }
+        let type_id = TypeId::of::<Context>();
+        self.contexts
+            .binary_search_by(|held| held.as_ref().as_any().type_id().cmp(&type_id))
+            .ok()
+            .and_then(|index| self.contexts[index].as_ref().as_any().downcast_ref())
+    }
+
+    /// Returns the type tag and string representation of each context held
+    /// by this resolver context.
+    ///
+    /// # Errors
+    /// Fails with [`ContextError::UnregisteredContext`] if the type of a
+    /// context is not registered with [`crate::register_client_context`].
+    ///
+    /// # Examples
+    /// ```
+    /// use ar::{DefaultResolverContext, ResolverContext};
+    ///
+    /// # #[cfg(not(windows))]
+    /// # {
+    /// let mut context = ResolverContext::new();
+    /// context.push(DefaultResolverContext::new(&["/shots/s01", "/assets"]));
+    ///
+    /// let serialized = context.serialize_contexts().unwrap();
+    /// assert_eq!(serialized["ArDefaultResolverContext"], "/shots/s01:/assets");
+    ///
+    /// let deserialized = ResolverContext::deserialize_contexts(
+    ///     serialized.iter().map(|(tag, context)| (tag.as_str(), context.as_str())),
+    /// );
+    /// assert_eq!(deserialized.unwrap(), context);
+    /// # }
+    /// ```
+    pub fn serialize_contexts(&self) -> Result<BTreeMap<String, String>, ContextError> {
+        self.contexts
+            .iter()
+            .map(|context| serialize_client_context(context.as_ref()))
+            .collect()
+    }
+
+    /// Creates a resolver context from the type tags and string
+    /// representations returned by [`ResolverContext::serialize_contexts`].
+    ///
+    /// # Errors
+    /// Fails with [`ContextError::UnregisteredTypeTag`] if no context type is
+    /// registered for a tag, or with [`ContextError::InvalidContext`] if a
+    /// context cannot be created from its string representation.
+    pub fn deserialize_contexts<'a>(
+        contexts: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, ContextError> {
+        let mut resolver_context = Self::new();
+        for (tag, context_str) in contexts {
+            resolver_context.insert(deserialize_client_context(tag, context_str)?, true);
         }
-        None
+        Ok(resolver_context)
     }
 
     /// Returns whether this resolver context is empty.
@@ -107,6 +195,12 @@ impl ResolverContext {
     }
 }
 
+impl Default for ResolverContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl IntoIterator for ResolverContext {
     type Item = Box<dyn ClientContext>;
     type IntoIter = IntoIter<Box<dyn ClientContext>>;
@@ -116,8 +210,251 @@ impl IntoIterator for ResolverContext {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for ResolverContext {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.serialize_contexts()
+            .map_err(serde::ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ResolverContext {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let contexts = BTreeMap::<String, String>::deserialize(deserializer)?;
+        Self::deserialize_contexts(
+            contexts
+                .iter()
+                .map(|(tag, context_str)| (tag.as_str(), context_str.as_str())),
+        )
+        .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Adds the contexts held by `rhs` whose types are not already held by this
+/// resolver context; the contexts already held take precedence.
 impl AddAssign for ResolverContext {
     fn add_assign(&mut self, rhs: Self) {
-        self.contexts.extend(rhs.contexts);
+        for context in rhs.contexts {
+            self.insert(context, false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{register_client_context, DefaultResolverContext, SerializableClientContext};
+    use core::cmp::Ordering;
+    use core::hash::{Hash, Hasher};
+
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct SearchContext(String);
+
+    impl ClientContext for SearchContext {}
+
+    impl SerializableClientContext for SearchContext {
+        const TYPE_TAG: &'static str = "TestSearchContext";
+
+        fn to_context_string(&self) -> String {
+            self.0.clone()
+        }
+
+        fn from_context_string(context_str: &str) -> Result<Self, String> {
+            if context_str.is_empty() {
+                return Err("empty search context".into());
+            }
+            Ok(SearchContext(context_str.into()))
+        }
+    }
+
+    /// Context whose tag clashes with [`SearchContext`].
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct ClashingContext;
+
+    impl ClientContext for ClashingContext {}
+
+    impl SerializableClientContext for ClashingContext {
+        const TYPE_TAG: &'static str = "TestSearchContext";
+
+        fn to_context_string(&self) -> String {
+            String::new()
+        }
+
+        fn from_context_string(_context_str: &str) -> Result<Self, String> {
+            Ok(ClashingContext)
+        }
+    }
+
+    #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    struct VersionContext(usize);
+
+    impl ClientContext for VersionContext {}
+
+    /// Order-sensitive FNV-1a hasher, so the tests don't need std.
+    struct TestHasher(u64);
+
+    impl Hasher for TestHasher {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+    }
+
+    fn hash(context: &ResolverContext) -> u64 {
+        let mut hasher = TestHasher(0xcbf2_9ce4_8422_2325);
+        context.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn context(search: Option<&str>, version: Option<usize>) -> ResolverContext {
+        let mut context = ResolverContext::new();
+        if let Some(search) = search {
+            context.push(SearchContext(search.into()));
+        }
+        if let Some(version) = version {
+            context.push(VersionContext(version));
+        }
+        context
+    }
+
+    #[test]
+    fn test_push_replaces_same_type() {
+        let mut context = context(Some("a"), Some(1));
+        context.push(VersionContext(2));
+        assert_eq!(context.get::<VersionContext>(), Some(&VersionContext(2)));
+        assert_eq!(
+            context.get::<SearchContext>(),
+            Some(&SearchContext("a".into()))
+        );
+        assert_eq!(context.into_iter().count(), 2);
+    }
+
+    #[test]
+    fn test_add_assign_keeps_existing() {
+        let mut context = context(Some("a"), None);
+        context += self::context(Some("b"), Some(1));
+        assert_eq!(context, self::context(Some("a"), Some(1)));
+    }
+
+    #[test]
+    fn test_eq_and_hash_ignore_push_order() {
+        let mut first = ResolverContext::new();
+        first
+            .push(SearchContext("a".into()))
+            .push(VersionContext(1));
+        let mut second = ResolverContext::new();
+        second
+            .push(VersionContext(1))
+            .push(SearchContext("a".into()));
+
+        assert_eq!(first, second);
+        assert_eq!(first.cmp(&second), Ordering::Equal);
+        assert_eq!(hash(&first), hash(&second));
+
+        assert_ne!(first, context(Some("a"), Some(2)));
+        assert_ne!(first, context(Some("a"), None));
+        assert_ne!(context(Some("a"), None), context(None, Some(1)));
+        assert_ne!(hash(&first), hash(&context(Some("a"), Some(2))));
+    }
+
+    #[test]
+    fn test_total_order() {
+        let contexts = [
+            ResolverContext::new(),
+            context(Some("a"), None),
+            context(Some("b"), None),
+            context(None, Some(1)),
+            context(None, Some(2)),
+            context(Some("a"), Some(1)),
+            context(Some("a"), Some(2)),
+            context(Some("b"), Some(1)),
+        ];
+        for (i, lhs) in contexts.iter().enumerate() {
+            for (j, rhs) in contexts.iter().enumerate() {
+                assert_eq!(lhs.cmp(rhs) == Ordering::Equal, i == j);
+                assert_eq!(lhs.cmp(rhs), rhs.cmp(lhs).reverse());
+                assert_eq!(lhs.partial_cmp(rhs), Some(lhs.cmp(rhs)));
+                for other in &contexts {
+                    if lhs < rhs && rhs < other {
+                        assert!(lhs < other);
+                    }
+                }
+            }
+        }
+
+        let map: BTreeMap<_, _> = contexts.iter().cloned().zip(0..).collect();
+        assert_eq!(map.len(), contexts.len());
+        assert_eq!(map[&context(Some("a"), Some(2))], 6);
+    }
+
+    fn round_trip(context: &ResolverContext) -> Result<ResolverContext, ContextError> {
+        let serialized = context.serialize_contexts()?;
+        ResolverContext::deserialize_contexts(
+            serialized
+                .iter()
+                .map(|(tag, context_str)| (tag.as_str(), context_str.as_str())),
+        )
+    }
+
+    #[test]
+    fn test_serialize_contexts() {
+        register_client_context::<SearchContext>().unwrap();
+        register_client_context::<SearchContext>().unwrap();
+        assert!(matches!(
+            register_client_context::<ClashingContext>(),
+            Err(ContextError::DuplicateTypeTag(tag)) if tag == "TestSearchContext"
+        ));
+
+        let mut context = context(Some("a"), None);
+        context.push(DefaultResolverContext::new(&["/assets"]));
+        let serialized = context.serialize_contexts().unwrap();
+        assert_eq!(serialized["TestSearchContext"], "a");
+        assert_eq!(round_trip(&context).unwrap(), context);
+        assert_eq!(
+            round_trip(&ResolverContext::new()).unwrap(),
+            ResolverContext::new()
+        );
+
+        assert!(matches!(
+            round_trip(&self::context(Some("a"), Some(1))),
+            Err(ContextError::UnregisteredContext(_))
+        ));
+        assert!(matches!(
+            ResolverContext::deserialize_contexts([("TestMissingContext", "")]),
+            Err(ContextError::UnregisteredTypeTag(tag)) if tag == "TestMissingContext"
+        ));
+        assert!(matches!(
+            ResolverContext::deserialize_contexts([("TestSearchContext", "")]),
+            Err(ContextError::InvalidContext(tag, _)) if tag == "TestSearchContext"
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use alloc::string::ToString;
+
+        register_client_context::<SearchContext>().unwrap();
+
+        let context = context(Some("a"), None);
+        let json = serde_json::to_string(&context).unwrap();
+        assert_eq!(json, r#"{"TestSearchContext":"a"}"#);
+        assert_eq!(
+            serde_json::from_str::<ResolverContext>(&json).unwrap(),
+            context
+        );
+
+        let err = serde_json::from_str::<ResolverContext>(r#"{"TestMissingContext":""}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("TestMissingContext"), "{}", err);
+        assert!(serde_json::to_string(&self::context(None, Some(1))).is_err());
     }
 }
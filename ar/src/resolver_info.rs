@@ -0,0 +1,120 @@
+use alloc::boxed::Box;
+use core::any::{Any, TypeId};
+use core::cmp::Ordering;
+use core::fmt::Debug;
+use core::hash::{Hash, Hasher};
+
+/// The trait for resolver-specific information carried by an
+/// [`crate::AssetInfo`].
+///
+/// Resolvers can attach any clonable, totally ordered and hashable type, e.g.
+/// the record id of an asset in an asset management database, and consumers
+/// can retrieve it with [`crate::AssetInfo::get_resolver_info_as`].
+///
+/// # Examples
+/// ```
+/// use ar::{AssetInfo, ResolverInfo};
+///
+/// #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// struct RecordId(u64);
+///
+/// impl ResolverInfo for RecordId {}
+///
+/// let info = AssetInfo::new().with_resolver_info(RecordId(42));
+/// assert_eq!(info.get_resolver_info_as::<RecordId>(), Some(&RecordId(42)));
+/// ```
+pub trait ResolverInfo: ResolverInfoClone + Debug + ResolverInfoCmp + Send + Sync {}
+
+#[doc(hidden)]
+/// Trait to clone a [`ResolverInfo`]
+pub trait ResolverInfoClone {
+    /// Method to clone a [`ResolverInfo`]
+    fn clone_box(&self) -> Box<dyn ResolverInfo>;
+}
+
+impl<Info> ResolverInfoClone for Info
+where
+    Info: 'static + ResolverInfo + Clone,
+{
+    fn clone_box(&self) -> Box<dyn ResolverInfo> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn ResolverInfo> {
+    fn clone(&self) -> Box<dyn ResolverInfo> {
+        self.clone_box()
+    }
+}
+
+#[doc(hidden)]
+/// Trait to compare and hash two [`ResolverInfo`]s
+pub trait ResolverInfoCmp {
+    /// An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Perform the equality tests.
+    fn eq_box(&self, other: &dyn ResolverInfoCmp) -> bool;
+
+    /// Perform the comparison tests.
+    fn cmp_box(&self, other: &dyn ResolverInfoCmp) -> Ordering;
+
+    /// Feed the info into `state`.
+    fn hash_box(&self, state: &mut dyn Hasher);
+}
+
+impl<Info> ResolverInfoCmp for Info
+where
+    Info: 'static + Eq + Ord + Hash,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_box(&self, other: &dyn ResolverInfoCmp) -> bool {
+        // Infos of different types are never equal.
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .is_some_and(|info| info == self)
+    }
+
+    fn cmp_box(&self, other: &dyn ResolverInfoCmp) -> Ordering {
+        // Infos of different types are ordered by type, otherwise by value.
+        match other.as_any().downcast_ref::<Self>() {
+            Some(info) => self.cmp(info),
+            None => TypeId::of::<Self>().cmp(&other.as_any().type_id()),
+        }
+    }
+
+    fn hash_box(&self, mut state: &mut dyn Hasher) {
+        TypeId::of::<Self>().hash(&mut state);
+        self.hash(&mut state);
+    }
+}
+
+impl PartialEq for Box<dyn ResolverInfo> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_ref().eq_box(other.as_ref())
+    }
+}
+
+impl Eq for Box<dyn ResolverInfo> {}
+
+impl PartialOrd for Box<dyn ResolverInfo> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Box<dyn ResolverInfo> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_ref().cmp_box(other.as_ref())
+    }
+}
+
+impl Hash for Box<dyn ResolverInfo> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().hash_box(state);
+    }
+}
@@ -0,0 +1,98 @@
+//! Locking primitives shared by the `std` and `no_std` builds. With `std`
+//! these are the standard library's, otherwise spin locks are used.
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "std")] {
+        extern crate std;
+        pub(crate) use std::sync::{Mutex, MutexGuard};
+
+        /// Locks `mutex`, ignoring poisoning. State guarded by these mutexes
+        /// is always left consistent, so a panic on another thread while it
+        /// held the lock is not a reason to fail.
+        pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+            mutex.lock().unwrap_or_else(|err| err.into_inner())
+        }
+
+        /// Consumes `mutex`, returning its data and ignoring poisoning.
+        #[cfg(all(feature = "resolver_v1", feature = "resolver_v2"))]
+        pub(crate) fn into_inner<T>(mutex: Mutex<T>) -> T {
+            mutex.into_inner().unwrap_or_else(|err| err.into_inner())
+        }
+    } else {
+        pub(crate) use spin::{Mutex, MutexGuard};
+
+        /// Locks `mutex`.
+        pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+            mutex.lock()
+        }
+
+        /// Consumes `mutex`, returning its data.
+        #[cfg(all(feature = "resolver_v1", feature = "resolver_v2"))]
+        pub(crate) fn into_inner<T>(mutex: Mutex<T>) -> T {
+            mutex.into_inner()
+        }
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(all(feature = "std", feature = "resolver_v2"))] {
+        pub(crate) use std::sync::OnceLock;
+    } else if #[cfg(feature = "resolver_v2")] {
+        /// Cell initialized once, exposing the subset of the standard
+        /// library's `OnceLock` used by the crate. Threads racing to
+        /// initialize it spin until the first one is done.
+        pub(crate) struct OnceLock<T>(spin::Once<T>);
+
+        impl<T> OnceLock<T> {
+            pub(crate) const fn new() -> Self {
+                Self(spin::Once::new())
+            }
+
+            pub(crate) fn get(&self) -> Option<&T> {
+                self.0.get()
+            }
+
+            pub(crate) fn get_or_init(&self, init: impl FnOnce() -> T) -> &T {
+                self.0.call_once(init)
+            }
+        }
+    }
+}
+
+/// Cell initialized once by a function that may fail, e.g. to download the
+/// content of an asset. Threads racing to initialize it block until the
+/// first one is done, and try again if it failed or panicked.
+#[cfg(any(feature = "http", feature = "s3", feature = "sqlite"))]
+#[derive(Debug)]
+pub(crate) struct TryOnceLock<T> {
+    value: std::sync::OnceLock<T>,
+    init_lock: Mutex<()>,
+}
+
+#[cfg(any(feature = "http", feature = "s3", feature = "sqlite"))]
+impl<T> TryOnceLock<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            value: std::sync::OnceLock::new(),
+            init_lock: Mutex::new(()),
+        }
+    }
+
+    pub(crate) fn get(&self) -> Option<&T> {
+        self.value.get()
+    }
+
+    /// Returns the value, initializing it with `init` if it isn't yet.
+    /// Returns the error of `init` if it fails, leaving the cell empty.
+    pub(crate) fn get_or_try_init<E>(&self, init: impl FnOnce() -> Result<T, E>) -> Result<&T, E> {
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+        let _init_guard = lock(&self.init_lock);
+        if let Some(value) = self.value.get() {
+            return Ok(value);
+        }
+        let value = init()?;
+        Ok(self.value.get_or_init(|| value))
+    }
+}
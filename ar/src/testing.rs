@@ -0,0 +1,554 @@
+//! Conformance suite for [`Resolver`] implementations.
+//!
+//! [`ConformanceSuite`] checks that a resolver honors the contracts
+//! documented on the [`Resolver`] trait, so resolver authors don't have to
+//! write these tests themselves. It is available with the `testing` feature
+//! and is meant to be used from tests:
+//!
+//! ```
+//! use ar::testing::ConformanceSuite;
+//! use ar::InMemoryResolver;
+//!
+//! ConformanceSuite::new("/conformance", InMemoryResolver::new).run();
+//! ```
+
+extern crate std;
+
+use alloc::borrow::ToOwned;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+use crate::package_utils::split_package_path;
+use crate::{
+    ClientContext, ResolvedPath, Resolver, ResolverContext, ResolverContextBinder,
+    ResolverScopedCache, ResolverWrapper, WriteMode, ZipPackageResolver,
+};
+
+/// Client context bound by [`ConformanceSuite::check_context_binding`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct ConformanceContext(u32);
+
+impl ClientContext for ConformanceContext {}
+
+/// Generic test suite checking that resolvers created by a factory honor the
+/// contracts documented on [`Resolver`].
+///
+/// Each check creates a new resolver with the factory and works with asset
+/// paths under `root`. The assets the checks need are written through
+/// [`Resolver::open_asset_for_write`] unless another fixture writer is set
+/// with [`ConformanceSuite::with_fixture_writer`]. `root` must be an
+/// absolute asset path that new assets can be written under, e.g. a
+/// temporary directory for filesystem-based resolvers. Each check writes to
+/// assets of its own, so resolvers created by the factory may share their
+/// assets.
+///
+/// The checks panic with a message describing the violated contract, so they
+/// can be called directly from tests. [`ConformanceSuite::run`] runs all of
+/// them.
+///
+/// # Examples
+/// ```
+/// use ar::testing::ConformanceSuite;
+/// use ar::DefaultResolver;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// ConformanceSuite::new(dir.path().to_str().unwrap(), DefaultResolver::new).run();
+/// ```
+pub struct ConformanceSuite<F> {
+    root: String,
+    factory: F,
+    modification_delay: Duration,
+    fixture_writer: Box<FixtureWriter>,
+}
+
+/// Function writing the contents of an asset, given the resolver under test
+/// and the asset's identifier.
+type FixtureWriter = dyn Fn(&dyn Resolver, &str, &[u8]);
+
+impl<F, R> ConformanceSuite<F>
+where
+    F: Fn() -> R,
+    R: Resolver + Send + Sync + 'static,
+{
+    /// Creates a suite checking the resolvers created by `factory`, using
+    /// asset paths under `root`.
+    pub fn new(root: &str, factory: F) -> Self {
+        Self {
+            root: root.trim_end_matches('/').to_owned(),
+            factory,
+            modification_delay: Duration::from_millis(50),
+            fixture_writer: Box::new(write_asset),
+        }
+    }
+
+    /// Sets how long [`ConformanceSuite::check_modification_timestamps`]
+    /// waits before modifying an asset, for resolvers whose timestamps have
+    /// a coarse resolution. Defaults to 50 milliseconds.
+    pub fn with_modification_delay(mut self, modification_delay: Duration) -> Self {
+        self.modification_delay = modification_delay;
+        self
+    }
+
+    /// Sets the function writing the assets the checks read, given the
+    /// resolver under test, the identifier of the asset and its contents.
+    /// This lets read-only resolvers be checked, e.g. by writing the assets
+    /// to the storage they read from. Defaults to writing through
+    /// [`Resolver::open_asset_for_write`].
+    ///
+    /// [`ConformanceSuite::check_new_assets`] always writes through the
+    /// resolver, so read-only resolvers should call the other checks rather
+    /// than [`ConformanceSuite::run`].
+    pub fn with_fixture_writer(
+        mut self,
+        fixture_writer: impl Fn(&dyn Resolver, &str, &[u8]) + 'static,
+    ) -> Self {
+        self.fixture_writer = Box::new(fixture_writer);
+        self
+    }
+
+    /// Runs all checks.
+    ///
+    /// # Panics
+    /// Panics if the resolver violates any of the checked contracts.
+    pub fn run(&self) {
+        self.check_identifiers();
+        self.check_missing_assets();
+        self.check_new_assets();
+        self.check_modification_timestamps();
+        self.check_context_binding();
+        self.check_thread_local_context_binding();
+        self.check_cache_scopes();
+        self.check_package_relative_paths();
+    }
+
+    /// Checks that identifiers are normalized, that relative asset paths are
+    /// anchored to the anchor's directory and that absolute asset paths are
+    /// unaffected by the anchor.
+    ///
+    /// # Panics
+    /// Panics if the resolver violates any of these contracts.
+    pub fn check_identifiers(&self) {
+        let resolver = (self.factory)();
+        let anchor = ResolvedPath::new(&self.path("identifiers/shot/shot.usda"));
+        let sibling = resolver.create_identifier(&self.path("identifiers/shot/layout.usda"), None);
+        let parent = resolver.create_identifier(&self.path("identifiers/layout.usda"), None);
+
+        assert!(
+            resolver.create_identifier("", Some(&anchor)).is_empty(),
+            "create_identifier must return an empty identifier for an empty asset path"
+        );
+        assert_eq!(
+            resolver.create_identifier(&sibling, None),
+            sibling,
+            "create_identifier must return identifiers unchanged"
+        );
+        assert_eq!(
+            resolver.create_identifier(&sibling, Some(&anchor)),
+            sibling,
+            "create_identifier must not anchor absolute asset paths"
+        );
+        assert_eq!(
+            resolver.create_identifier(&self.path("identifiers/shot/../shot/./layout.usda"), None),
+            sibling,
+            "create_identifier must normalize asset paths"
+        );
+        assert_eq!(
+            resolver.create_identifier("./layout.usda", Some(&anchor)),
+            sibling,
+            "create_identifier must anchor `./` paths to the anchor's directory"
+        );
+        assert_eq!(
+            resolver.create_identifier("../layout.usda", Some(&anchor)),
+            parent,
+            "create_identifier must anchor `../` paths to the anchor's parent directory"
+        );
+        assert_eq!(
+            resolver.create_identifier_for_new_asset("./layout.usda", &anchor),
+            sibling,
+            "create_identifier_for_new_asset must anchor `./` paths to the anchor's directory"
+        );
+        assert_eq!(
+            resolver.create_identifier_for_new_asset(&sibling, &anchor),
+            sibling,
+            "create_identifier_for_new_asset must not anchor absolute asset paths"
+        );
+    }
+
+    /// Checks that missing assets, including assets in missing packages,
+    /// resolve to an empty [`ResolvedPath`].
+    ///
+    /// # Panics
+    /// Panics if the resolver resolves a missing asset.
+    pub fn check_missing_assets(&self) {
+        let resolver = (self.factory)();
+        for asset_path in [
+            String::new(),
+            self.path("missing/missing.usda"),
+            self.path("missing/missing.usdz[missing.usda]"),
+        ] {
+            let identifier = resolver.create_identifier(&asset_path, None);
+            assert!(
+                resolver.resolve(&identifier).is_empty(),
+                "resolve must return an empty path for the missing asset `{}`",
+                identifier
+            );
+        }
+    }
+
+    /// Checks that [`Resolver::resolve_for_new_asset`] returns a path new
+    /// assets can be written to, whether or not the asset exists, and that
+    /// written assets can be resolved and read back.
+    ///
+    /// # Panics
+    /// Panics if the resolver violates any of these contracts.
+    pub fn check_new_assets(&self) {
+        let resolver = (self.factory)();
+        let anchor = ResolvedPath::new(&self.path("new_assets/shot.usda"));
+        let identifier = resolver.create_identifier_for_new_asset("./layout.usda", &anchor);
+
+        assert!(
+            resolver.resolve(&identifier).is_empty(),
+            "resolve must return an empty path for `{}` before it is written",
+            identifier
+        );
+        write_asset(&resolver, &identifier, b"#usda 1.0\n");
+
+        let resolved_path = resolver.resolve(&identifier);
+        assert!(
+            !resolved_path.is_empty(),
+            "resolve must return a path for `{}` once it is written",
+            identifier
+        );
+        assert_eq!(
+            read_asset(&resolver, &resolved_path),
+            b"#usda 1.0\n",
+            "open_asset must return the contents written to `{}`",
+            identifier
+        );
+        assert!(
+            !resolver.resolve_for_new_asset(&identifier).is_empty(),
+            "resolve_for_new_asset must return a path for the existing asset `{}`",
+            identifier
+        );
+
+        write_asset(&resolver, &identifier, b"#usda 1.0\n# replaced\n");
+        assert_eq!(
+            read_asset(&resolver, &resolver.resolve(&identifier)),
+            b"#usda 1.0\n# replaced\n",
+            "open_asset_for_write must replace the contents of `{}`",
+            identifier
+        );
+    }
+
+    /// Checks that the modification timestamp of an asset is stable until
+    /// the asset is modified, and changes when it is.
+    ///
+    /// # Panics
+    /// Panics if the resolver violates any of these contracts.
+    pub fn check_modification_timestamps(&self) {
+        let resolver = (self.factory)();
+        let identifier = resolver.create_identifier(&self.path("timestamps/layout.usda"), None);
+        self.write_fixture(&resolver, &identifier, b"#usda 1.0\n");
+
+        let timestamp = |resolver: &R| {
+            let resolved_path = resolver.resolve(&identifier);
+            resolver
+                .get_modification_timestamp(&identifier, &resolved_path)
+                .unwrap_or_else(|err| {
+                    panic!(
+                        "get_modification_timestamp must succeed for `{}`: {}",
+                        identifier, err
+                    )
+                })
+        };
+        let original = timestamp(&resolver);
+        assert_eq!(
+            timestamp(&resolver),
+            original,
+            "the modification timestamp of `{}` must not change until it is modified",
+            identifier
+        );
+
+        std::thread::sleep(self.modification_delay);
+        self.write_fixture(&resolver, &identifier, b"#usda 1.0\n# modified\n");
+        assert_ne!(
+            timestamp(&resolver),
+            original,
+            "the modification timestamp of `{}` must change when it is modified",
+            identifier
+        );
+    }
+
+    /// Checks that unbinding a context restores the context that was current
+    /// when it was bound, for nested bindings, and that the current context
+    /// is the bound one for resolvers that report bound contexts.
+    ///
+    /// # Panics
+    /// Panics if the resolver violates any of these contracts.
+    pub fn check_context_binding(&self) {
+        let resolver = (self.factory)();
+        let outer = conformance_context(1);
+        let inner = conformance_context(2);
+        let unbound = resolver.get_current_context();
+
+        let outer_binder = ResolverContextBinder::with_resolver(&resolver, outer.clone());
+        let current = resolver.get_current_context();
+        // Resolvers may ignore contexts, in which case none is reported.
+        let reports_contexts = current != unbound;
+        if reports_contexts {
+            assert_eq!(
+                current, outer,
+                "get_current_context must return the bound context"
+            );
+        }
+
+        {
+            let _inner_binder = ResolverContextBinder::with_resolver(&resolver, inner.clone());
+            let expected = if reports_contexts { &inner } else { &unbound };
+            assert_eq!(
+                &resolver.get_current_context(),
+                expected,
+                "get_current_context must return the innermost bound context"
+            );
+        }
+        assert_eq!(
+            resolver.get_current_context(),
+            current,
+            "unbinding a context must restore the context bound before it"
+        );
+
+        drop(outer_binder);
+        assert_eq!(
+            resolver.get_current_context(),
+            unbound,
+            "unbinding all contexts must restore the unbound context"
+        );
+    }
+
+    /// Checks that contexts are bound to the binding thread only.
+    ///
+    /// # Panics
+    /// Panics if a context bound in one thread is current in another.
+    pub fn check_thread_local_context_binding(&self) {
+        let resolver = (self.factory)();
+        let unbound = resolver.get_current_context();
+
+        let _binder = ResolverContextBinder::with_resolver(&resolver, conformance_context(1));
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                assert_eq!(
+                    resolver.get_current_context(),
+                    unbound,
+                    "contexts bound in one thread must not be current in other threads"
+                );
+            });
+        });
+    }
+
+    /// Checks that nested cache scopes, with or without the data of the
+    /// enclosing scope, keep the results of the enclosing scope, and that
+    /// assets created during a scope are found once all scopes are closed.
+    ///
+    /// # Panics
+    /// Panics if the resolver violates any of these contracts.
+    pub fn check_cache_scopes(&self) {
+        let resolver = (self.factory)();
+        let identifier = resolver.create_identifier(&self.path("cache_scopes/layout.usda"), None);
+
+        {
+            let outer = ResolverScopedCache::with_resolver(&resolver, None);
+            assert!(
+                resolver.resolve(&identifier).is_empty(),
+                "resolve must return an empty path for `{}` before it is written",
+                identifier
+            );
+            self.write_fixture(&resolver, &identifier, b"#usda 1.0\n");
+            // Resolvers may or may not have cached the result above.
+            let scoped = resolver.resolve(&identifier);
+
+            {
+                let _nested = ResolverScopedCache::with_resolver(&resolver, None);
+                assert_eq!(
+                    resolver.resolve(&identifier),
+                    scoped,
+                    "resolve must return the result of the enclosing cache scope"
+                );
+            }
+            {
+                let data = outer.get_cache_scope_data();
+                let _shared = ResolverScopedCache::with_resolver(&resolver, Some(&data));
+                assert_eq!(
+                    resolver.resolve(&identifier),
+                    scoped,
+                    "resolve must return the result of the cache scope whose data is shared"
+                );
+            }
+            assert_eq!(
+                resolver.resolve(&identifier),
+                scoped,
+                "closing a nested cache scope must not discard the enclosing scope's results"
+            );
+        }
+
+        assert!(
+            !resolver.resolve(&identifier).is_empty(),
+            "resolve must find `{}` once all cache scopes are closed",
+            identifier
+        );
+    }
+
+    /// Checks that assets in a `.usdz` package written through the resolver
+    /// can be resolved and read through a [`ResolverWrapper`], and that
+    /// relative asset paths are anchored inside the package of a packaged
+    /// anchor.
+    ///
+    /// Relative asset paths are anchored to the anchor's resolved path, so
+    /// the anchored identifiers name the resolved package path. That path
+    /// may differ from the package's identifier, e.g. to name the version
+    /// that was resolved, but must resolve to itself.
+    ///
+    /// # Panics
+    /// Panics if the resolver violates any of these contracts.
+    pub fn check_package_relative_paths(&self) {
+        let mut wrapper = ResolverWrapper::new(Box::new((self.factory)()));
+        wrapper
+            .register_package_resolver(&["usdz"], Arc::new(ZipPackageResolver::usdz()))
+            .unwrap();
+
+        let package = wrapper.create_identifier(&self.path("packages/props.usdz"), None);
+        self.write_fixture(
+            &wrapper,
+            &package,
+            &build_zip(&[("chair.usda", b"#usda 1.0\n", 0)], 64),
+        );
+
+        let identifier = format!("{}[chair.usda]", package);
+        let resolved_path = wrapper.resolve(&identifier);
+        assert!(
+            !resolved_path.is_empty(),
+            "resolve must return a path for the packaged asset `{}`",
+            identifier
+        );
+        assert_eq!(
+            read_asset(&wrapper, &resolved_path),
+            b"#usda 1.0\n",
+            "open_asset must return the contents of the packaged asset `{}`",
+            identifier
+        );
+        assert!(
+            wrapper
+                .resolve(&format!("{}[missing.usda]", package))
+                .is_empty(),
+            "resolve must return an empty path for missing assets in `{}`",
+            package
+        );
+        let (resolved_package, _) = split_package_path(resolved_path.get_path_string());
+        assert_eq!(
+            wrapper.resolve(resolved_package),
+            ResolvedPath::new(resolved_package),
+            "resolve must return the resolved package path `{}` unchanged",
+            resolved_package
+        );
+        assert_eq!(
+            wrapper.create_identifier("./table.usda", Some(&resolved_path)),
+            format!("{}[table.usda]", resolved_package),
+            "create_identifier must anchor relative paths inside the anchor's package"
+        );
+        assert_eq!(
+            wrapper.get_extension(&identifier),
+            "usda",
+            "get_extension must return the extension of the packaged asset"
+        );
+    }
+
+    fn path(&self, relative_path: &str) -> String {
+        format!("{}/{}", self.root, relative_path)
+    }
+
+    fn write_fixture(&self, resolver: &dyn Resolver, identifier: &str, data: &[u8]) {
+        (self.fixture_writer)(resolver, identifier, data);
+    }
+}
+
+impl<F> fmt::Debug for ConformanceSuite<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConformanceSuite")
+            .field("root", &self.root)
+            .field("modification_delay", &self.modification_delay)
+            .finish_non_exhaustive()
+    }
+}
+
+fn conformance_context(value: u32) -> ResolverContext {
+    let mut context = ResolverContext::new();
+    context.push(ConformanceContext(value));
+    context
+}
+
+/// Replaces the contents of the asset `identifier` with `data` through
+/// `resolver`, as [`ConformanceSuite`] does to create its fixtures unless
+/// given another writer. Panics if the asset cannot be written.
+///
+/// # Examples
+/// ```
+/// use ar::testing::write_asset;
+/// use ar::InMemoryResolver;
+///
+/// let resolver = InMemoryResolver::new();
+/// write_asset(&resolver, "/chair.usda", b"#usda 1.0\n");
+/// assert!(resolver.contains("/chair.usda"));
+/// ```
+pub fn write_asset(resolver: &dyn Resolver, identifier: &str, data: &[u8]) {
+    let resolved_path = resolver.resolve_for_new_asset(identifier);
+    assert!(
+        !resolved_path.is_empty(),
+        "resolve_for_new_asset must return a path for `{}`",
+        identifier
+    );
+    let mut asset = resolver
+        .open_asset_for_write(&resolved_path, WriteMode::Replace)
+        .unwrap_or_else(|err| {
+            panic!(
+                "open_asset_for_write must succeed for `{}`: {}",
+                resolved_path, err
+            )
+        });
+    asset
+        .write(data, data.len(), 0)
+        .unwrap_or_else(|err| panic!("failed to write `{}`: {}", resolved_path, err));
+    asset
+        .close()
+        .unwrap_or_else(|err| panic!("failed to close `{}`: {}", resolved_path, err));
+}
+
+/// Returns the contents of the asset at `resolved_path`.
+fn read_asset(resolver: &dyn Resolver, resolved_path: &ResolvedPath) -> Vec<u8> {
+    let asset = resolver
+        .open_asset(resolved_path)
+        .unwrap_or_else(|err| panic!("open_asset must succeed for `{}`: {}", resolved_path, err));
+    asset
+        .get_buffer()
+        .unwrap_or_else(|err| panic!("failed to read `{}`: {}", resolved_path, err))
+        .to_vec()
+}
+
+/// Builds a zip archive holding the given files, padding the local headers
+/// so file data is aligned to `alignment`, e.g. 64 bytes for `.usdz`
+/// packages. Each file is given with the compression method recorded in its
+/// headers, but its data is stored as is.
+///
+/// # Examples
+/// ```
+/// use ar::testing::build_zip;
+///
+/// let package = build_zip(&[("chair.usda", b"#usda 1.0\n", 0)], 64);
+/// assert_eq!(&package[..4], b"PK\x03\x04");
+/// ```
+pub fn build_zip(files: &[(&str, &[u8], u16)], alignment: usize) -> Vec<u8> {
+    crate::zip_file::build_zip(files, alignment)
+}
@@ -24,11 +24,41 @@ impl fmt::Display for WritableAssetError {
     }
 }
 
+/// Checks a write of `count` bytes from a buffer of `buffer_len` bytes, as
+/// done by [`WritableAsset::write`].
+#[cfg(any(feature = "std", feature = "resolver_v2"))]
+pub(crate) fn check_write_buffer(
+    buffer_len: usize,
+    count: usize,
+) -> Result<(), WritableAssetError> {
+    if count > buffer_len {
+        return Err(WritableAssetError::WriteFailed(alloc::format!(
+            "buffer of size {} does not hold {} bytes",
+            buffer_len, count
+        )));
+    }
+    Ok(())
+}
+
+/// Enumeration of write modes for open_asset_for_write
+#[derive(Clone, Debug)]
+pub enum WriteMode {
+    /// Open asset for in-place updates. If the asset exists, its contents
+    /// will not be discarded and writes may overwrite existing data.
+    /// Otherwise, the asset will be created.
+    Update = 0,
+
+    /// Open asset for replacement. If the asset exists, its contents will
+    /// be discarded by the time the ArWritableAsset is destroyed.
+    /// Otherwise, the asset will be created.
+    Replace,
+}
+
 /// Interface for writing data to an asset.
 ///
 /// see [`Resolver::open_asset_for_write()`] for how to retrieve instances of
 /// this object.
-pub trait WritableAsset {
+pub trait WritableAsset: Send {
     /// Close this asset, performing any necessary finalization or commits
     /// of data that was previously written. Returns [`WriteAssetError`]
     /// on failure.
@@ -0,0 +1,460 @@
+//! Reading of zip archives, such as `.usdz` packages.
+//!
+//! Only the subset of the zip format needed for packages is supported: the
+//! central directory is read to find the files in the archive, and files
+//! stored without compression can be read directly from the archive's
+//! asset. Zip64 archives and compressed or encrypted files are rejected.
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::asset::check_read_bounds;
+use crate::{Asset, AssetError};
+
+const LOCAL_FILE_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const LOCAL_FILE_HEADER_SIZE: usize = 30;
+const CENTRAL_DIRECTORY_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const CENTRAL_DIRECTORY_HEADER_SIZE: usize = 46;
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+const END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+const MAX_COMMENT_SIZE: usize = 0xffff;
+
+const COMPRESSION_METHOD_STORED: u16 = 0;
+const FLAG_ENCRYPTED: u16 = 0x1;
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+fn invalid_archive(reason: &str) -> AssetError {
+    AssetError::InvalidArchive(reason.to_owned())
+}
+
+/// Reads exactly `count` bytes at `offset` from `asset`.
+fn read_exact(asset: &dyn Asset, count: usize, offset: usize) -> Result<Vec<u8>, AssetError> {
+    if offset
+        .checked_add(count)
+        .is_none_or(|end| end > asset.get_size())
+    {
+        return Err(invalid_archive("unexpected end of archive"));
+    }
+    let mut buffer = vec![0; count];
+    if asset.read(&mut buffer, count, offset)? != count {
+        return Err(invalid_archive("unexpected end of archive"));
+    }
+    Ok(buffer)
+}
+
+/// Information about a file in a [`ZipFile`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ZipFileInfo {
+    /// Offset of the file's data from the start of the archive.
+    pub data_offset: usize,
+
+    /// Size of the file's data in the archive.
+    pub size: usize,
+
+    /// Size of the file once uncompressed.
+    pub uncompressed_size: usize,
+
+    /// Compression method of the file, 0 if it is stored uncompressed.
+    pub compression_method: u16,
+
+    /// CRC-32 of the uncompressed file.
+    pub crc: u32,
+
+    /// General purpose bit flags of the file.
+    pub flags: u16,
+}
+
+impl ZipFileInfo {
+    /// Returns true if the file is stored uncompressed and unencrypted, so
+    /// its data can be read directly from the archive.
+    pub fn is_stored(&self) -> bool {
+        self.compression_method == COMPRESSION_METHOD_STORED
+            && self.flags & FLAG_ENCRYPTED == 0
+            && self.size == self.uncompressed_size
+    }
+}
+
+/// Zip archive read from an [`Asset`].
+///
+/// Opening the archive reads its central directory; the data of the files
+/// in it is only read when they are opened with [`ZipFile::open_file`].
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use ar::{Asset, AssetError, ZipFile};
+///
+/// fn read_layer(package: Arc<dyn Asset>) -> Result<Vec<u8>, AssetError> {
+///     let zip_file = ZipFile::open(package)?;
+///     for (path, info) in zip_file.iter() {
+///         println!("{}: {} bytes", path, info.size);
+///     }
+///     let layer = zip_file.open_file("a.usdc")?;
+///     Ok(layer.get_buffer()?.to_vec())
+/// }
+/// ```
+pub struct ZipFile {
+    asset: Arc<dyn Asset>,
+    files: Vec<(String, ZipFileInfo)>,
+}
+
+impl ZipFile {
+    /// Reads the central directory of the zip archive held by `asset`.
+    pub fn open(asset: Arc<dyn Asset>) -> Result<Self, AssetError> {
+        let archive_size = asset.get_size();
+        if archive_size < END_OF_CENTRAL_DIRECTORY_SIZE {
+            return Err(invalid_archive("archive is too small"));
+        }
+
+        // The end of central directory record is followed by a comment of up
+        // to 64 KiB, so it has to be searched for from the end.
+        let tail_size = archive_size.min(END_OF_CENTRAL_DIRECTORY_SIZE + MAX_COMMENT_SIZE);
+        let tail = read_exact(asset.as_ref(), tail_size, archive_size - tail_size)?;
+        let end_offset = (0..=tail_size - END_OF_CENTRAL_DIRECTORY_SIZE)
+            .rev()
+            .find(|&offset| read_u32(&tail, offset) == END_OF_CENTRAL_DIRECTORY_SIGNATURE)
+            .ok_or_else(|| invalid_archive("end of central directory not found"))?;
+        let end_record = &tail[end_offset..];
+
+        let file_count = read_u16(end_record, 10);
+        let directory_size = read_u32(end_record, 12);
+        let directory_offset = read_u32(end_record, 16);
+        if file_count == u16::MAX || directory_size == u32::MAX || directory_offset == u32::MAX {
+            return Err(invalid_archive("zip64 archives are not supported"));
+        }
+
+        let directory = read_exact(
+            asset.as_ref(),
+            directory_size as usize,
+            directory_offset as usize,
+        )?;
+        let mut files = Vec::with_capacity(file_count as usize);
+        let mut offset = 0;
+        for _ in 0..file_count {
+            if offset + CENTRAL_DIRECTORY_HEADER_SIZE > directory.len()
+                || read_u32(&directory, offset) != CENTRAL_DIRECTORY_HEADER_SIGNATURE
+            {
+                return Err(invalid_archive("malformed central directory"));
+            }
+            let header = &directory[offset..];
+            let name_size = read_u16(header, 28) as usize;
+            let extra_size = read_u16(header, 30) as usize;
+            let comment_size = read_u16(header, 32) as usize;
+            let name_end = offset + CENTRAL_DIRECTORY_HEADER_SIZE + name_size;
+            if name_end > directory.len() {
+                return Err(invalid_archive("malformed central directory"));
+            }
+            let name = String::from_utf8_lossy(
+                &directory[offset + CENTRAL_DIRECTORY_HEADER_SIZE..name_end],
+            )
+            .into_owned();
+
+            // The file's data follows its local header, whose variable-sized
+            // fields may differ from the ones in the central directory.
+            let local_header_offset = read_u32(header, 42) as usize;
+            let local_header =
+                read_exact(asset.as_ref(), LOCAL_FILE_HEADER_SIZE, local_header_offset)?;
+            if read_u32(&local_header, 0) != LOCAL_FILE_HEADER_SIGNATURE {
+                return Err(AssetError::InvalidArchive(format!(
+                    "malformed local header for `{}`",
+                    name
+                )));
+            }
+            let data_offset = local_header_offset
+                + LOCAL_FILE_HEADER_SIZE
+                + read_u16(&local_header, 26) as usize
+                + read_u16(&local_header, 28) as usize;
+
+            let info = ZipFileInfo {
+                data_offset,
+                size: read_u32(header, 20) as usize,
+                uncompressed_size: read_u32(header, 24) as usize,
+                compression_method: read_u16(header, 10),
+                crc: read_u32(header, 16),
+                flags: read_u16(header, 8),
+            };
+            if data_offset
+                .checked_add(info.size)
+                .is_none_or(|end| end > archive_size)
+            {
+                return Err(AssetError::InvalidArchive(format!(
+                    "data of `{}` extends past the end of the archive",
+                    name
+                )));
+            }
+
+            files.push((name, info));
+            offset = name_end + extra_size + comment_size;
+        }
+
+        Ok(Self { asset, files })
+    }
+
+    /// Returns the path and information of each file in the archive, in the
+    /// order they are listed in the central directory.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ZipFileInfo)> {
+        self.files.iter().map(|(path, info)| (path.as_str(), info))
+    }
+
+    /// Returns the information of the file at `path` in the archive, if any.
+    pub fn find(&self, path: &str) -> Option<&ZipFileInfo> {
+        self.files
+            .iter()
+            .find(|(file_path, _)| file_path == path)
+            .map(|(_, info)| info)
+    }
+
+    /// Opens the file at `path` in the archive.
+    ///
+    /// The returned asset reads directly from the archive's asset, so no
+    /// data is copied. Returns [`AssetError::UnsupportedCompression`] if the
+    /// file is compressed.
+    pub fn open_file(&self, path: &str) -> Result<ZipFileAsset, AssetError> {
+        let info = self
+            .find(path)
+            .ok_or_else(|| AssetError::ReadError(format!("no file `{}` in zip archive", path)))?;
+        if info.flags & FLAG_ENCRYPTED != 0 {
+            return Err(AssetError::InvalidArchive(format!(
+                "`{}` is encrypted",
+                path
+            )));
+        }
+        if !info.is_stored() {
+            return Err(AssetError::UnsupportedCompression(
+                path.to_owned(),
+                info.compression_method,
+            ));
+        }
+
+        Ok(ZipFileAsset {
+            asset: self.asset.clone(),
+            offset: info.data_offset,
+            size: info.size,
+        })
+    }
+}
+
+impl fmt::Debug for ZipFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZipFile")
+            .field("files", &self.files)
+            .finish_non_exhaustive()
+    }
+}
+
+/// [`Asset`] implementation for a file stored in a [`ZipFile`].
+///
+/// The asset is a view of the range of the archive's asset holding the
+/// file's data. [`Asset::get_buffer`] returns a slice of the archive's
+/// buffer, so it is zero-copy whenever the archive's asset is.
+pub struct ZipFileAsset {
+    asset: Arc<dyn Asset>,
+    offset: usize,
+    size: usize,
+}
+
+impl Asset for ZipFileAsset {
+    fn get_size(&self) -> usize {
+        self.size
+    }
+
+    fn get_buffer(&self) -> Result<&[u8], AssetError> {
+        self.asset
+            .get_buffer()?
+            .get(self.offset..self.offset + self.size)
+            .ok_or_else(|| invalid_archive("archive was truncated after it was opened"))
+    }
+
+    fn read(&self, buffer: &mut [u8], count: usize, offset: usize) -> Result<usize, AssetError> {
+        check_read_bounds(buffer.len(), count, offset, self.size)?;
+        self.asset.read(buffer, count, self.offset + offset)
+    }
+}
+
+impl fmt::Debug for ZipFileAsset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZipFileAsset")
+            .field("offset", &self.offset)
+            .field("size", &self.size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builds a zip archive holding the given files and compression methods,
+/// padding the local headers so file data is aligned to `alignment`. Shared
+/// by the tests of this module and [`crate::testing::build_zip`].
+#[cfg(any(test, feature = "testing"))]
+pub(crate) fn build_zip(files: &[(&str, &[u8], u16)], alignment: usize) -> Vec<u8> {
+    let mut archive = Vec::new();
+    let mut directory = Vec::new();
+    for (name, data, method) in files {
+        let header_offset = archive.len() as u32;
+        let data_offset = archive.len() + LOCAL_FILE_HEADER_SIZE + name.len();
+        let padding = (alignment - data_offset % alignment) % alignment;
+
+        archive.extend(LOCAL_FILE_HEADER_SIGNATURE.to_le_bytes());
+        archive.extend([20, 0, 0, 0]);
+        archive.extend(method.to_le_bytes());
+        archive.extend([0; 8]);
+        archive.extend((data.len() as u32).to_le_bytes());
+        archive.extend((data.len() as u32).to_le_bytes());
+        archive.extend((name.len() as u16).to_le_bytes());
+        archive.extend((padding as u16).to_le_bytes());
+        archive.extend(name.as_bytes());
+        archive.resize(archive.len() + padding, 0);
+        archive.extend(*data);
+
+        directory.extend(CENTRAL_DIRECTORY_HEADER_SIGNATURE.to_le_bytes());
+        directory.extend([20, 0, 20, 0, 0, 0]);
+        directory.extend(method.to_le_bytes());
+        directory.extend([0; 8]);
+        directory.extend((data.len() as u32).to_le_bytes());
+        directory.extend((data.len() as u32).to_le_bytes());
+        directory.extend((name.len() as u16).to_le_bytes());
+        directory.extend([0; 12]);
+        directory.extend(header_offset.to_le_bytes());
+        directory.extend(name.as_bytes());
+    }
+
+    let directory_offset = archive.len() as u32;
+    archive.extend(&directory);
+    archive.extend(END_OF_CENTRAL_DIRECTORY_SIGNATURE.to_le_bytes());
+    archive.extend([0; 4]);
+    archive.extend((files.len() as u16).to_le_bytes());
+    archive.extend((files.len() as u16).to_le_bytes());
+    archive.extend((directory.len() as u32).to_le_bytes());
+    archive.extend(directory_offset.to_le_bytes());
+    archive.extend([0; 2]);
+    archive
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asset holding its contents in memory.
+    struct BytesAsset(Vec<u8>);
+
+    impl Asset for BytesAsset {
+        fn get_size(&self) -> usize {
+            self.0.len()
+        }
+
+        fn get_buffer(&self) -> Result<&[u8], AssetError> {
+            Ok(&self.0)
+        }
+
+        fn read(
+            &self,
+            buffer: &mut [u8],
+            count: usize,
+            offset: usize,
+        ) -> Result<usize, AssetError> {
+            let data = self
+                .0
+                .get(offset..offset + count)
+                .ok_or(AssetError::OutOfBounds(offset, count, self.0.len()))?;
+            buffer[..count].copy_from_slice(data);
+            Ok(count)
+        }
+    }
+
+    fn open_zip(archive: Vec<u8>) -> Result<ZipFile, AssetError> {
+        ZipFile::open(Arc::new(BytesAsset(archive)))
+    }
+
+    #[test]
+    fn test_open() {
+        let zip_file = open_zip(build_zip(
+            &[("a.usda", b"#usda 1.0", 0), ("tex/b.png", b"png", 8)],
+            64,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            zip_file.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+            ["a.usda", "tex/b.png"]
+        );
+        let info = zip_file.find("a.usda").unwrap();
+        assert_eq!(info.data_offset, 64);
+        assert_eq!(info.size, 9);
+        assert!(info.is_stored());
+        assert!(!zip_file.find("tex/b.png").unwrap().is_stored());
+        assert!(zip_file.find("c.usda").is_none());
+    }
+
+    #[test]
+    fn test_open_file() {
+        let archive = build_zip(&[("a.usda", b"#usda 1.0", 0), ("b.usda", b"", 0)], 1);
+        let zip_file = open_zip(archive).unwrap();
+
+        let asset = zip_file.open_file("a.usda").unwrap();
+        assert_eq!(asset.get_size(), 9);
+        assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+        // The buffer is a view of the archive's buffer.
+        let archive_buffer = zip_file.asset.get_buffer().unwrap().as_ptr_range();
+        assert!(archive_buffer.contains(&asset.get_buffer().unwrap().as_ptr()));
+
+        let mut buffer = [0; 3];
+        assert_eq!(asset.read(&mut buffer, 3, 6).unwrap(), 3);
+        assert_eq!(&buffer, b"1.0");
+        assert!(matches!(
+            asset.read(&mut buffer, 3, 7),
+            Err(AssetError::OutOfBounds(7, 3, 9))
+        ));
+
+        assert_eq!(zip_file.open_file("b.usda").unwrap().get_size(), 0);
+        assert!(matches!(
+            zip_file.open_file("c.usda"),
+            Err(AssetError::ReadError(_))
+        ));
+    }
+
+    #[test]
+    fn test_open_compressed_file() {
+        let zip_file = open_zip(build_zip(&[("a.usda", b"deflated", 8)], 1)).unwrap();
+        assert!(matches!(
+            zip_file.open_file("a.usda"),
+            Err(AssetError::UnsupportedCompression(path, 8)) if path == "a.usda"
+        ));
+    }
+
+    #[test]
+    fn test_open_invalid_archive() {
+        assert!(matches!(
+            open_zip(b"not a zip archive, but long enough".to_vec()),
+            Err(AssetError::InvalidArchive(_))
+        ));
+        assert!(matches!(
+            open_zip(Vec::new()),
+            Err(AssetError::InvalidArchive(_))
+        ));
+
+        // File data extending past the end of the archive.
+        let mut archive = build_zip(&[("a.usda", b"#usda 1.0", 0)], 1);
+        let header_offset = archive.len()
+            - END_OF_CENTRAL_DIRECTORY_SIZE
+            - CENTRAL_DIRECTORY_HEADER_SIZE
+            - "a.usda".len();
+        archive[header_offset + 20..header_offset + 24].copy_from_slice(&1000u32.to_le_bytes());
+        assert!(matches!(
+            open_zip(archive),
+            Err(AssetError::InvalidArchive(_))
+        ));
+    }
+}
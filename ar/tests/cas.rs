@@ -0,0 +1,186 @@
+#![cfg(all(feature = "cas", feature = "testing"))]
+
+use std::fs;
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+
+use ar::testing::write_asset;
+use ar::{
+    AssetError, CasResolver, DefaultResolver, ResolvedPath, Resolver, ResolverError,
+    ResolverWrapper, WritableAsset, WritableAssetError, WriteMode, CAS_NEW_OBJECT, CAS_URI_SCHEMES,
+};
+
+const UNKNOWN_DIGEST: &str = "ca01b5a9f0a4a3e7d1e4e5d0e94ca6dfbc7bde3d9bc8bbb6e5cbe6bbbd1aa4fe";
+
+#[test]
+fn write_and_read() {
+    let dir = tempfile::tempdir().unwrap();
+    let resolver = CasResolver::new(dir.path());
+
+    let mut asset = resolver
+        .open_object_for_write(&resolver.resolve_for_new_asset(CAS_NEW_OBJECT))
+        .unwrap();
+    assert_eq!(asset.write(b"#usda ", 6, 0).unwrap(), 6);
+    assert_eq!(asset.write(b"1.0", 3, 6).unwrap(), 3);
+    assert!(matches!(
+        asset.write(b"1.0", 4, 9),
+        Err(WritableAssetError::WriteFailed(_))
+    ));
+    assert_eq!(asset.get_identifier(), None);
+    asset.close().unwrap();
+    assert!(asset.close().is_err());
+    assert!(asset.write(b"", 0, 9).is_err());
+
+    let identifier = asset.get_identifier().unwrap();
+    let digest = identifier.strip_prefix("cas:sha256:").unwrap();
+    assert!(dir
+        .path()
+        .join("sha256")
+        .join(&digest[..2])
+        .join(digest)
+        .is_file());
+
+    let resolved_path = resolver.resolve(identifier);
+    assert_eq!(resolved_path, ResolvedPath::new(identifier));
+    let asset = resolver.open_asset(&resolved_path).unwrap();
+    assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+    assert!(resolver
+        .get_modification_timestamp(identifier, &resolved_path)
+        .is_ok());
+
+    // Digests are case-insensitive, and resolve to lowercase.
+    assert_eq!(
+        resolver.resolve(
+            &identifier
+                .to_ascii_uppercase()
+                .replace("CAS:SHA256:", "cas:sha256:")
+        ),
+        resolved_path
+    );
+    assert!(resolver
+        .resolve(&format!("cas:sha256:{}", UNKNOWN_DIGEST))
+        .is_empty());
+}
+
+#[test]
+fn deduplication() {
+    let dir = tempfile::tempdir().unwrap();
+    let resolver = CasResolver::new(dir.path());
+
+    let first = resolver.insert(b"#usda 1.0").unwrap();
+    let second = resolver.insert(b"#usda 1.0").unwrap();
+    assert_eq!(first, second);
+    assert_ne!(first, resolver.insert(b"#usda 1.0\n").unwrap());
+
+    let objects = fs::read_dir(dir.path().join("sha256"))
+        .unwrap()
+        .map(|entry| fs::read_dir(entry.unwrap().path()).unwrap().count())
+        .sum::<usize>();
+    assert_eq!(objects, 2);
+    assert_eq!(fs::read_dir(dir.path().join("tmp")).unwrap().count(), 0);
+}
+
+#[test]
+fn expected_digest() {
+    let dir = tempfile::tempdir().unwrap();
+    let resolver = CasResolver::new(dir.path());
+    let identifier = resolver.insert(b"#usda 1.0").unwrap();
+
+    // Writing the expected content succeeds, even if it already exists.
+    write_asset(&resolver, &identifier, b"#usda 1.0");
+
+    let mut asset = resolver
+        .open_asset_for_write(&ResolvedPath::new(&identifier), WriteMode::Replace)
+        .unwrap();
+    asset.write(b"#usda 1.0\n", 10, 0).unwrap();
+    assert!(asset.close().is_err());
+    assert_eq!(fs::read_dir(dir.path().join("tmp")).unwrap().count(), 0);
+
+    assert!(resolver.resolve_for_new_asset("cas:sha256:abcd").is_empty());
+    assert!(resolver
+        .open_asset_for_write(&ResolvedPath::new("cas:md5"), WriteMode::Replace)
+        .is_err());
+}
+
+#[test]
+fn discard_on_drop() {
+    let dir = tempfile::tempdir().unwrap();
+    let resolver = CasResolver::new(dir.path());
+
+    let mut asset = resolver
+        .open_object_for_write(&ResolvedPath::new(CAS_NEW_OBJECT))
+        .unwrap();
+    asset.write(b"#usda 1.0", 9, 0).unwrap();
+    drop(asset);
+    assert_eq!(fs::read_dir(dir.path().join("tmp")).unwrap().count(), 0);
+    assert!(!dir.path().join("sha256").exists());
+}
+
+#[test]
+fn digest_mismatch() {
+    let dir = tempfile::tempdir().unwrap();
+    let resolver = CasResolver::new(dir.path());
+    let identifier = resolver.insert(b"#usda 1.0").unwrap();
+    let digest = identifier.strip_prefix("cas:sha256:").unwrap();
+
+    // Corrupt the object behind the resolver's back.
+    let path = dir.path().join("sha256").join(&digest[..2]).join(digest);
+    let mut permissions = fs::metadata(&path).unwrap().permissions();
+    assert!(permissions.readonly());
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    fs::set_permissions(&path, permissions).unwrap();
+    fs::write(&path, b"#usda 1.0\n").unwrap();
+
+    match resolver.open_asset(&resolver.resolve(&identifier)) {
+        Err(ResolverError::InvalidAsset(path, AssetError::DigestMismatch(expected, actual))) => {
+            assert_eq!(path, identifier);
+            assert_eq!(expected, digest);
+            assert_ne!(actual, digest);
+        }
+        result => panic!("unexpected result: {:?}", result.map(|_| ())),
+    }
+}
+
+#[test]
+fn uri_resolver() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut wrapper = ResolverWrapper::new(Box::new(DefaultResolver::new()));
+    wrapper
+        .register_uri_resolver(CAS_URI_SCHEMES, Arc::new(CasResolver::new(dir.path())))
+        .unwrap();
+
+    let resolved_path = wrapper.resolve_for_new_asset(CAS_NEW_OBJECT);
+    assert_eq!(resolved_path, ResolvedPath::new(CAS_NEW_OBJECT));
+    write_asset(&wrapper, CAS_NEW_OBJECT, b"#usda 1.0");
+
+    let identifier = CasResolver::new(dir.path()).insert(b"#usda 1.0").unwrap();
+    let resolved_path = wrapper.resolve(&identifier);
+    assert_eq!(resolved_path, ResolvedPath::new(&identifier));
+    let asset = wrapper.open_asset(&resolved_path).unwrap();
+    assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+}
+
+#[test]
+fn new_objects_through_trait() {
+    let dir = tempfile::tempdir().unwrap();
+    let resolver: &dyn Resolver = &CasResolver::new(dir.path());
+
+    // Objects written to CAS_NEW_OBJECT through the trait are published, but
+    // can only be addressed by the identifier of their content.
+    write_asset(resolver, CAS_NEW_OBJECT, b"#usda 1.0");
+    assert!(resolver.resolve(CAS_NEW_OBJECT).is_empty());
+    let identifier = format!("cas:sha256:{:x}", Sha256::digest(b"#usda 1.0"));
+    assert_eq!(
+        resolver.resolve(&identifier),
+        ResolvedPath::new(&identifier)
+    );
+
+    // Writing to the identifier of the expected content makes it resolvable.
+    let identifier = format!("cas:sha256:{:x}", Sha256::digest(b"#usda 1.0\n"));
+    assert!(resolver.resolve(&identifier).is_empty());
+    write_asset(resolver, &identifier, b"#usda 1.0\n");
+    let asset = resolver.open_asset(&resolver.resolve(&identifier)).unwrap();
+    assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0\n");
+}
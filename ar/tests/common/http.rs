@@ -0,0 +1,136 @@
+//! Stand-in asset server for the tests of [`ar::HttpResolver`].
+
+use std::collections::BTreeMap;
+
+use super::{HttpRequest, HttpResponse, Server};
+
+const LAST_MODIFIED: &str = "Wed, 21 Oct 2015 07:28:00 GMT";
+
+/// A request received by the stand-in server.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+}
+
+/// A file served by the stand-in server.
+struct File {
+    data: Vec<u8>,
+    etag: Option<String>,
+    last_modified: String,
+}
+
+#[derive(Default)]
+pub struct State {
+    /// Served files, keyed by path.
+    files: BTreeMap<String, File>,
+    requests: Vec<Request>,
+    /// Drop connections without responding, as if the server was down.
+    down: bool,
+}
+
+/// Server standing in for an asset server. It supports HEAD and GET
+/// requests, single byte ranges and conditional requests. Dates are only
+/// compared for equality with the last modification time of the file.
+pub type AssetServer = Server<State>;
+
+impl AssetServer {
+    pub fn start() -> Self {
+        Server::spawn(State::default(), handle)
+    }
+
+    /// Serves `data` at `path` with the entity tag `etag`.
+    pub fn put(&self, path: &str, data: &[u8], etag: &str) {
+        self.insert(path, data, Some(format!("\"{}\"", etag)), LAST_MODIFIED);
+    }
+
+    /// Serves `data` at `path` without an entity tag, last modified at
+    /// `last_modified`.
+    pub fn put_without_etag(&self, path: &str, data: &[u8], last_modified: &str) {
+        self.insert(path, data, None, last_modified);
+    }
+
+    fn insert(&self, path: &str, data: &[u8], etag: Option<String>, last_modified: &str) {
+        let file = File {
+            data: data.to_vec(),
+            etag,
+            last_modified: last_modified.to_owned(),
+        };
+        self.state
+            .lock()
+            .unwrap()
+            .files
+            .insert(path.to_owned(), file);
+    }
+
+    pub fn set_down(&self, down: bool) {
+        self.state.lock().unwrap().down = down;
+    }
+
+    /// Returns the requests received since the last call.
+    pub fn take_requests(&self) -> Vec<Request> {
+        std::mem::take(&mut self.state.lock().unwrap().requests)
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.url, path)
+    }
+}
+
+fn handle(request: HttpRequest, state: &mut State) -> Option<HttpResponse> {
+    if state.down {
+        return None;
+    }
+    let headers = &request.headers;
+    let response = match state.files.get(&request.target) {
+        None => (404, String::new(), Vec::new()),
+        Some(file) if file.etag.is_some() && headers.get("if-none-match") == file.etag.as_ref() => {
+            (304, String::new(), Vec::new())
+        }
+        Some(file)
+            if headers
+                .get("if-match")
+                .is_some_and(|tag| Some(tag) != file.etag.as_ref())
+                || headers
+                    .get("if-unmodified-since")
+                    .is_some_and(|date| *date != file.last_modified) =>
+        {
+            (412, String::new(), Vec::new())
+        }
+        Some(file) => {
+            let mut validators = format!("Last-Modified: {}\r\n", file.last_modified);
+            if let Some(etag) = &file.etag {
+                validators += &format!("ETag: {}\r\n", etag);
+            }
+            match request.get_range() {
+                Some((first, last)) if request.method == "GET" => (
+                    206,
+                    format!(
+                        "{}Content-Range: bytes {}-{}/{}\r\n",
+                        validators,
+                        first,
+                        last,
+                        file.data.len()
+                    ),
+                    file.data[first..=last].to_vec(),
+                ),
+                _ => (200, validators, file.data.clone()),
+            }
+        }
+    };
+    state.requests.push(Request {
+        method: request.method,
+        path: request.target,
+        status: response.0,
+    });
+    Some(response)
+}
+
+pub fn request(method: &str, path: &str, status: u16) -> Request {
+    Request {
+        method: method.to_owned(),
+        path: path.to_owned(),
+        status,
+    }
+}
@@ -0,0 +1,119 @@
+//! Stand-in server shared by the tests of the resolvers reading assets over
+//! HTTP, and the stand-in services built on it.
+
+// Each test crate only uses some of the helpers.
+#![allow(dead_code)]
+
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "s3")]
+pub mod s3;
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A request received by a [`Server`].
+pub struct HttpRequest {
+    pub method: String,
+    /// Path and query of the request.
+    pub target: String,
+    /// Header values, keyed by lowercase header name.
+    pub headers: BTreeMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// Returns the first and last byte of the single range requested, if any.
+    pub fn get_range(&self) -> Option<(usize, usize)> {
+        let (first, last) = self
+            .headers
+            .get("range")?
+            .strip_prefix("bytes=")?
+            .split_once('-')?;
+        Some((first.parse().unwrap(), last.parse().unwrap()))
+    }
+}
+
+/// Status, extra header lines and body of a response sent by a [`Server`].
+pub type HttpResponse = (u16, String, Vec<u8>);
+
+/// Handles a request with exclusive access to the server state. Returning
+/// [`None`] drops the connection without responding.
+pub type Handler<S> = fn(HttpRequest, &mut S) -> Option<HttpResponse>;
+
+/// Minimal HTTP/1.1 server handing the requests it receives to a handler,
+/// and closing the connection after each response.
+pub struct Server<S> {
+    pub url: String,
+    pub state: Arc<Mutex<S>>,
+}
+
+impl<S: Send + 'static> Server<S> {
+    /// Starts a server on a free local port, handling requests with
+    /// `handler` on a background thread.
+    pub fn spawn(state: S, handler: Handler<S>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(state));
+        let server_state = state.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle(stream, &server_state, handler);
+            }
+        });
+        Self { url, state }
+    }
+}
+
+fn handle<S>(mut stream: TcpStream, state: &Mutex<S>, handler: Handler<S>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_owned();
+    let target = parts.next().unwrap_or_default().to_owned();
+
+    let mut headers = BTreeMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_owned());
+        }
+    }
+    let length = headers
+        .get("content-length")
+        .map_or(0, |length| length.parse().unwrap());
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).unwrap();
+
+    let request = HttpRequest {
+        method: method.clone(),
+        target,
+        headers,
+        body,
+    };
+    let (status, extra_headers, body) = match handler(request, &mut state.lock().unwrap()) {
+        Some(response) => response,
+        None => return,
+    };
+
+    let mut response = format!(
+        "HTTP/1.1 {} Status\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        extra_headers,
+        body.len()
+    )
+    .into_bytes();
+    if method != "HEAD" {
+        response.extend(body);
+    }
+    let _ = stream.write_all(&response);
+}
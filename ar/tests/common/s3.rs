@@ -0,0 +1,193 @@
+//! Fake S3 endpoint for the tests of [`ar::S3Resolver`].
+
+use std::collections::BTreeMap;
+
+use ar::{S3Credentials, S3Resolver};
+use sha2::{Digest, Sha256};
+
+use super::{HttpRequest, HttpResponse, Server};
+
+const ACCESS_KEY_ID: &str = "AKIDEXAMPLE";
+
+/// A request received by the fake endpoint.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub target: String,
+    pub status: u16,
+}
+
+#[derive(Default)]
+pub struct State {
+    /// Content and ETag of the stored objects, keyed by `/bucket/key`.
+    objects: BTreeMap<String, (Vec<u8>, String)>,
+    /// Parts of the multipart uploads in progress, keyed by upload ID.
+    uploads: BTreeMap<String, BTreeMap<u32, Vec<u8>>>,
+    next_id: usize,
+    requests: Vec<Request>,
+    /// Reject the next part uploaded, as if the request failed.
+    reject_next_part: bool,
+}
+
+impl State {
+    fn next_etag(&mut self) -> String {
+        self.next_id += 1;
+        format!("\"etag-{}\"", self.next_id)
+    }
+}
+
+/// Minimal S3-compatible endpoint serving path-style requests. It supports
+/// HEAD, GET with single byte ranges and If-Match, PUT and multipart
+/// uploads, and checks that requests are signed with [`ACCESS_KEY_ID`] and
+/// their payload hash.
+pub type Endpoint = Server<State>;
+
+impl Endpoint {
+    pub fn start() -> Self {
+        Server::spawn(State::default(), handle)
+    }
+
+    pub fn resolver(&self) -> S3Resolver {
+        let credentials = S3Credentials::new(ACCESS_KEY_ID, "secret");
+        S3Resolver::new(&self.url, "us-east-1", credentials)
+    }
+
+    pub fn put(&self, path: &str, data: &[u8]) {
+        let mut state = self.state.lock().unwrap();
+        let etag = state.next_etag();
+        state.objects.insert(path.to_owned(), (data.to_vec(), etag));
+    }
+
+    pub fn get(&self, path: &str) -> Option<Vec<u8>> {
+        let state = self.state.lock().unwrap();
+        state.objects.get(path).map(|(data, _)| data.clone())
+    }
+
+    pub fn reject_next_part(&self) {
+        self.state.lock().unwrap().reject_next_part = true;
+    }
+
+    pub fn get_upload_count(&self) -> usize {
+        self.state.lock().unwrap().uploads.len()
+    }
+
+    /// Returns the requests received since the last call.
+    pub fn take_requests(&self) -> Vec<Request> {
+        std::mem::take(&mut self.state.lock().unwrap().requests)
+    }
+}
+
+fn handle(request: HttpRequest, state: &mut State) -> Option<HttpResponse> {
+    let range = request.get_range();
+    let HttpRequest {
+        method,
+        target,
+        headers,
+        body,
+    } = request;
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let query: BTreeMap<_, _> = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .collect();
+
+    let signed = headers.get("authorization").is_some_and(|authorization| {
+        authorization.starts_with(&format!("AWS4-HMAC-SHA256 Credential={}/", ACCESS_KEY_ID))
+    }) && headers.contains_key("x-amz-date")
+        && headers.get("x-amz-content-sha256") == Some(&hex(&Sha256::digest(&body)));
+
+    let response = if !signed {
+        (403, String::new(), Vec::new())
+    } else {
+        match (method.as_str(), query.get("uploadId")) {
+            ("HEAD", None) | ("GET", None) => match state.objects.get(path) {
+                None => (404, String::new(), Vec::new()),
+                Some((_, etag)) if headers.get("if-match").is_some_and(|tag| tag != etag) => {
+                    (412, String::new(), Vec::new())
+                }
+                Some((data, etag)) => match range {
+                    Some((first, last)) if method == "GET" => (
+                        206,
+                        format!(
+                            "ETag: {}\r\nContent-Range: bytes {}-{}/{}\r\n",
+                            etag,
+                            first,
+                            last,
+                            data.len()
+                        ),
+                        data[first..=last].to_vec(),
+                    ),
+                    _ => (200, format!("ETag: {}\r\n", etag), data.clone()),
+                },
+            },
+            ("PUT", None) => {
+                let etag = state.next_etag();
+                state.objects.insert(path.to_owned(), (body, etag.clone()));
+                (200, format!("ETag: {}\r\n", etag), Vec::new())
+            }
+            ("POST", None) if query.contains_key("uploads") => {
+                let id = format!("upload-{}", state.next_etag().trim_matches('"'));
+                state.uploads.insert(id.clone(), BTreeMap::new());
+                let xml = format!(
+                    "<InitiateMultipartUploadResult><UploadId>{}</UploadId>\
+                     </InitiateMultipartUploadResult>",
+                    id
+                );
+                (200, String::new(), xml.into_bytes())
+            }
+            ("PUT", Some(&id)) if state.reject_next_part && state.uploads.contains_key(id) => {
+                state.reject_next_part = false;
+                (500, String::new(), Vec::new())
+            }
+            ("PUT", Some(&id)) if state.uploads.contains_key(id) => {
+                let part_number = query["partNumber"].parse().unwrap();
+                state.uploads.get_mut(id).unwrap().insert(part_number, body);
+                (
+                    200,
+                    format!("ETag: \"part-{}\"\r\n", part_number),
+                    Vec::new(),
+                )
+            }
+            ("POST", Some(&id)) if state.uploads.contains_key(id) => {
+                let parts = state.uploads.remove(id).unwrap();
+                let expected_part_count = String::from_utf8(body)
+                    .unwrap()
+                    .matches("<PartNumber>")
+                    .count();
+                if parts.len() == expected_part_count {
+                    let etag = state.next_etag();
+                    let data = parts.into_values().flatten().collect();
+                    state.objects.insert(path.to_owned(), (data, etag));
+                    let xml = "<CompleteMultipartUploadResult></CompleteMultipartUploadResult>";
+                    (200, String::new(), xml.as_bytes().to_vec())
+                } else {
+                    let xml = "<Error><Code>InvalidPart</Code><Message>missing part</Message>\
+                               </Error>";
+                    (200, String::new(), xml.as_bytes().to_vec())
+                }
+            }
+            ("DELETE", Some(&id)) if state.uploads.remove(id).is_some() => {
+                (204, String::new(), Vec::new())
+            }
+            _ => (404, String::new(), Vec::new()),
+        }
+    };
+    state.requests.push(Request {
+        method,
+        target,
+        status: response.0,
+    });
+    Some(response)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn request(method: &str, target: &str, status: u16) -> Request {
+    Request {
+        method: method.to_owned(),
+        target: target.to_owned(),
+        status,
+    }
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "testing")]
+
+mod common;
+
+use ar::testing::ConformanceSuite;
+use ar::{
+    DefaultResolver, InMemoryResolver, InMemoryVersionStore, RecordingResolver, ResolverWrapper,
+    VersionedResolver,
+};
+
+#[test]
+fn default_resolver() {
+    let dir = tempfile::tempdir().unwrap();
+    ConformanceSuite::new(dir.path().to_str().unwrap(), DefaultResolver::new).run();
+}
+
+#[test]
+fn in_memory_resolver() {
+    ConformanceSuite::new("/conformance", InMemoryResolver::new).run();
+}
+
+#[test]
+fn resolver_wrapper() {
+    let dir = tempfile::tempdir().unwrap();
+    ConformanceSuite::new(dir.path().to_str().unwrap(), || {
+        ResolverWrapper::new(Box::new(DefaultResolver::new()))
+    })
+    .run();
+}
+
+#[test]
+fn recording_resolver() {
+    ConformanceSuite::new("/conformance", || {
+        RecordingResolver::new(InMemoryResolver::new())
+    })
+    .run();
+}
+
+#[test]
+fn versioned_resolver() {
+    ConformanceSuite::new("/conformance", || {
+        VersionedResolver::new(InMemoryResolver::new(), InMemoryVersionStore::new())
+    })
+    .run();
+}
+
+#[test]
+fn fixture_writer() {
+    let dir = tempfile::tempdir().unwrap();
+    let suite = ConformanceSuite::new(dir.path().to_str().unwrap(), DefaultResolver::new)
+        .with_fixture_writer(|resolver, identifier, data| {
+            let resolved_path = resolver.resolve_for_new_asset(identifier);
+            let path = std::path::Path::new(resolved_path.get_path_string());
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, data).unwrap();
+        });
+    // Assets are written behind the resolver's back, as for read-only
+    // resolvers, so the check writing through the resolver is skipped.
+    suite.check_identifiers();
+    suite.check_missing_assets();
+    suite.check_modification_timestamps();
+    suite.check_context_binding();
+    suite.check_thread_local_context_binding();
+    suite.check_cache_scopes();
+    suite.check_package_relative_paths();
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_resolver() {
+    ConformanceSuite::new("db:/conformance", || {
+        ar::SqliteResolver::open_in_memory().unwrap()
+    })
+    .run();
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn http_resolver() {
+    let server = common::http::AssetServer::start();
+    let cache_dir = tempfile::tempdir().unwrap();
+    let cache_path = cache_dir.path().to_owned();
+    let etag = std::cell::Cell::new(0);
+    let suite = ConformanceSuite::new(&server.url("/conformance"), move || {
+        ar::HttpResolver::new(&cache_path)
+    })
+    .with_fixture_writer(move |_, identifier, data| {
+        // Each write is a new version of the asset, with an entity tag of its
+        // own.
+        etag.set(etag.get() + 1);
+        let path = identifier.strip_prefix(&server.url).unwrap();
+        server.put(path, data, &format!("v{}", etag.get()));
+    });
+    // The resolver is read-only, so the check writing through it is skipped.
+    suite.check_identifiers();
+    suite.check_missing_assets();
+    suite.check_modification_timestamps();
+    suite.check_context_binding();
+    suite.check_thread_local_context_binding();
+    suite.check_cache_scopes();
+    suite.check_package_relative_paths();
+}
+
+#[cfg(feature = "s3")]
+#[test]
+fn s3_resolver() {
+    let endpoint = common::s3::Endpoint::start();
+    ConformanceSuite::new("s3://bucket/conformance", || endpoint.resolver()).run();
+}
+
+#[cfg(feature = "resolver_v1")]
+#[test]
+fn v1_resolver_adapter() {
+    let dir = tempfile::tempdir().unwrap();
+    let suite = ConformanceSuite::new(dir.path().to_str().unwrap(), || {
+        ar::V1ResolverAdapter::new(ar::v1::DefaultResolver::new())
+    });
+    // Ar 1.0 resolvers have a single context stack shared by all threads, so
+    // the thread-local binding check doesn't apply.
+    suite.check_identifiers();
+    suite.check_missing_assets();
+    suite.check_new_assets();
+    suite.check_modification_timestamps();
+    suite.check_context_binding();
+    suite.check_cache_scopes();
+    suite.check_package_relative_paths();
+}
@@ -0,0 +1,164 @@
+#![cfg(feature = "resolver_v2")]
+
+use std::sync::Arc;
+
+use ar::{
+    create_resolver, define_package_resolver, define_resolver, get_available_resolvers,
+    get_resolver, get_underlying_resolver, register_plugins, set_preferred_resolver, Asset,
+    PackageResolver, ResolvedPath, Resolver, ResolverError, WritableAsset, WriteMode,
+    DEFAULT_RESOLVER_TYPE_NAME,
+};
+use usd_plugin::info::{PluginInfo, PluginVariants};
+
+/// Resolver that prefixes resolved paths with its name.
+struct TaggedResolver(&'static str);
+
+impl Resolver for TaggedResolver {
+    fn create_identifier(&self, asset_path: &str, _anchor: Option<&ResolvedPath>) -> String {
+        asset_path.to_string()
+    }
+
+    fn create_identifier_for_new_asset(&self, asset_path: &str, _anchor: &ResolvedPath) -> String {
+        asset_path.to_string()
+    }
+
+    fn resolve(&self, asset_path: &str) -> ResolvedPath {
+        ResolvedPath::new(&format!("{}|{}", self.0, asset_path))
+    }
+
+    fn resolve_for_new_asset(&self, asset_path: &str) -> ResolvedPath {
+        self.resolve(asset_path)
+    }
+
+    fn open_asset(&self, _resolved_path: &ResolvedPath) -> Result<Arc<dyn Asset>, ResolverError> {
+        Err(ResolverError::OpenAssetError(self.0.to_string()))
+    }
+
+    fn open_asset_for_write(
+        &self,
+        _resolved_path: &ResolvedPath,
+        _write_mode: WriteMode,
+    ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+        Err(ResolverError::OpenAssetError(self.0.to_string()))
+    }
+}
+
+/// Package resolver that prefixes resolved packaged paths with `packaged/`.
+struct TaggedPackageResolver;
+
+impl PackageResolver for TaggedPackageResolver {
+    fn resolve(
+        &self,
+        _resolver: &dyn Resolver,
+        _resolved_package_path: &ResolvedPath,
+        packaged_path: &str,
+    ) -> String {
+        format!("packaged/{}", packaged_path)
+    }
+
+    fn open_asset(
+        &self,
+        _resolver: &dyn Resolver,
+        _resolved_package_path: &ResolvedPath,
+        _resolved_packaged_path: &str,
+    ) -> Result<Arc<dyn Asset>, ResolverError> {
+        Err(ResolverError::OpenAssetError("package".to_string()))
+    }
+}
+
+const PLUG_INFO: &str = r#"
+{
+    "Plugins": [
+        {
+            "Type": "library",
+            "Name": "studioResolvers",
+            "Root": "/plugins",
+            "LibraryPath": "libstudio.so",
+            "Info": {
+                "Types": {
+                    "ZebraResolver": { "bases": ["ArResolver"] },
+                    "StudioResolver": { "bases": ["ArResolver"] },
+                    "StudioUriResolver": {
+                        "bases": ["ArResolver"],
+                        "uriSchemes": ["studio", "bad_scheme"]
+                    },
+                    "MissingUriResolver": {
+                        "bases": ["ArResolver"],
+                        "uriSchemes": ["missing"]
+                    },
+                    "StudioPackageResolver": {
+                        "bases": ["ArPackageResolver"],
+                        "extensions": ["spkg", ".bad"]
+                    }
+                }
+            }
+        }
+    ]
+}"#;
+
+// Discovery state is process-wide and the resolver is only created once, so
+// everything is exercised from a single test.
+#[test]
+fn resolver_discovery() {
+    define_resolver("StudioResolver", || Box::new(TaggedResolver("studio")));
+    define_resolver("ZebraResolver", || Box::new(TaggedResolver("zebra")));
+    define_resolver("StudioUriResolver", || Box::new(TaggedResolver("uri")));
+    define_resolver("LinkedResolver", || Box::new(TaggedResolver("linked")));
+    define_package_resolver("StudioPackageResolver", || Box::new(TaggedPackageResolver));
+
+    let plugin_info: PluginInfo = serde_json::from_str::<PluginVariants>(PLUG_INFO)
+        .unwrap()
+        .into();
+    register_plugins(&plugin_info);
+
+    assert_eq!(
+        get_available_resolvers(),
+        [
+            "LinkedResolver",
+            "StudioResolver",
+            "ZebraResolver",
+            DEFAULT_RESOLVER_TYPE_NAME
+        ]
+    );
+
+    assert_eq!(
+        create_resolver("ZebraResolver")
+            .resolve("a.usd")
+            .get_path_string(),
+        "zebra|a.usd"
+    );
+    // Unknown types fall back to the default resolver, which can't find the
+    // asset.
+    assert!(create_resolver("UnknownResolver")
+        .resolve("does/not/exist.usd")
+        .is_empty());
+
+    set_preferred_resolver("StudioResolver");
+    let resolver = get_resolver();
+    assert!(std::ptr::eq(resolver, get_resolver()));
+    assert_eq!(resolver.resolve("a.usd").get_path_string(), "studio|a.usd");
+    assert_eq!(
+        get_underlying_resolver().resolve("a.usd").get_path_string(),
+        "studio|a.usd"
+    );
+    assert_eq!(
+        resolver.resolve("STUDIO:/a.usd").get_path_string(),
+        "uri|STUDIO:/a.usd"
+    );
+    assert_eq!(resolver.get_uri_schemes().collect::<Vec<_>>(), ["studio"]);
+    assert_eq!(
+        resolver.get_package_extensions().collect::<Vec<_>>(),
+        ["spkg", "usdz", "zip"]
+    );
+    assert_eq!(
+        resolver.resolve("a.spkg[b.usd]").get_path_string(),
+        "studio|a.spkg[packaged/b.usd]"
+    );
+
+    // Too late to change the resolver.
+    set_preferred_resolver("ZebraResolver");
+    assert_eq!(
+        get_resolver().resolve("a.usd").get_path_string(),
+        "studio|a.usd"
+    );
+}
@@ -0,0 +1,188 @@
+#![cfg(feature = "http")]
+
+mod common;
+
+use std::sync::Arc;
+
+use ar::{
+    DefaultResolver, HttpResolver, ResolvedPath, Resolver, ResolverWrapper, HTTP_URI_SCHEMES,
+};
+use common::http::{request, AssetServer};
+
+#[test]
+fn resolve() {
+    let server = AssetServer::start();
+    server.put("/props/chair.usda", b"#usda 1.0", "v1");
+    let cache_dir = tempfile::tempdir().unwrap();
+    let resolver = HttpResolver::new(cache_dir.path());
+
+    let url = server.url("/props/chair.usda");
+    assert_eq!(resolver.resolve(&url), ResolvedPath::new(&url));
+    assert!(resolver
+        .resolve(&server.url("/props/missing.usda"))
+        .is_empty());
+    assert_eq!(
+        resolver.create_identifier("../sets/set.usda", Some(&ResolvedPath::new(&url))),
+        server.url("/sets/set.usda")
+    );
+    assert_eq!(
+        server.take_requests(),
+        [
+            request("HEAD", "/props/chair.usda", 200),
+            request("HEAD", "/props/missing.usda", 404),
+        ]
+    );
+}
+
+#[test]
+fn ranged_reads() {
+    let server = AssetServer::start();
+    server.put(
+        "/props/chair.usda",
+        b"#usda 1.0\ndef Xform \"chair\" {}\n",
+        "v1",
+    );
+    let cache_dir = tempfile::tempdir().unwrap();
+    let resolver = HttpResolver::new(cache_dir.path());
+
+    let asset = resolver
+        .open_asset(&resolver.resolve(&server.url("/props/chair.usda")))
+        .unwrap();
+    assert_eq!(asset.get_size(), 31);
+
+    let mut buffer = [0; 5];
+    assert_eq!(asset.read(&mut buffer, 5, 10).unwrap(), 5);
+    assert_eq!(&buffer, b"def X");
+    assert!(asset.read(&mut buffer, 5, 30).is_err());
+    assert_eq!(
+        server.take_requests(),
+        [
+            request("HEAD", "/props/chair.usda", 200),
+            request("HEAD", "/props/chair.usda", 200),
+            request("GET", "/props/chair.usda", 206),
+        ]
+    );
+}
+
+#[test]
+fn disk_cache_revalidation() {
+    let server = AssetServer::start();
+    server.put("/props/chair.usda", b"#usda 1.0", "v1");
+    let cache_dir = tempfile::tempdir().unwrap();
+    let url = server.url("/props/chair.usda");
+    let resolved_path = ResolvedPath::new(&url);
+
+    let resolver = HttpResolver::new(cache_dir.path());
+    let asset = resolver.open_asset(&resolved_path).unwrap();
+    assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+    let timestamp = resolver
+        .get_modification_timestamp(&url, &resolved_path)
+        .unwrap();
+    server.take_requests();
+
+    // Unchanged content is revalidated and served from the disk cache, even
+    // by another resolver using the same cache directory.
+    let resolver = HttpResolver::new(cache_dir.path());
+    let asset = resolver.open_asset(&resolved_path).unwrap();
+    assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+    assert_eq!(
+        server.take_requests(),
+        [request("HEAD", "/props/chair.usda", 304)]
+    );
+
+    // Modified content is downloaded again.
+    server.put("/props/chair.usda", b"#usda 1.0\n# v2\n", "v2");
+    assert_ne!(
+        resolver
+            .get_modification_timestamp(&url, &resolved_path)
+            .unwrap(),
+        timestamp
+    );
+    let asset = resolver.open_asset(&resolved_path).unwrap();
+    assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0\n# v2\n");
+    assert_eq!(
+        server.take_requests(),
+        [
+            request("HEAD", "/props/chair.usda", 200),
+            request("HEAD", "/props/chair.usda", 200),
+            request("GET", "/props/chair.usda", 200),
+        ]
+    );
+
+    // Cached content is served while the server is down.
+    server.set_down(true);
+    assert_eq!(resolver.resolve(&url), resolved_path);
+    let asset = resolver.open_asset(&resolved_path).unwrap();
+    assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0\n# v2\n");
+    assert!(resolver
+        .resolve(&server.url("/props/missing.usda"))
+        .is_empty());
+}
+
+#[test]
+fn content_changed_while_open() {
+    let server = AssetServer::start();
+    server.put("/props/chair.usda", b"#usda 1.0", "v1");
+    let cache_dir = tempfile::tempdir().unwrap();
+    let resolver = HttpResolver::new(cache_dir.path());
+
+    let asset = resolver
+        .open_asset(&ResolvedPath::new(&server.url("/props/chair.usda")))
+        .unwrap();
+    server.put("/props/chair.usda", b"#usda 1.0", "v2");
+    assert!(asset.get_buffer().is_err());
+}
+
+#[test]
+fn content_changed_while_open_without_etag() {
+    let server = AssetServer::start();
+    server.put_without_etag(
+        "/props/chair.usda",
+        b"#usda 1.0",
+        "Wed, 21 Oct 2015 07:28:00 GMT",
+    );
+    let cache_dir = tempfile::tempdir().unwrap();
+    let resolver = HttpResolver::new(cache_dir.path());
+
+    let asset = resolver
+        .open_asset(&ResolvedPath::new(&server.url("/props/chair.usda")))
+        .unwrap();
+    server.put_without_etag(
+        "/props/chair.usda",
+        b"#usda 1.0",
+        "Thu, 22 Oct 2015 07:28:00 GMT",
+    );
+    assert!(asset.get_buffer().is_err());
+    assert_eq!(
+        server.take_requests(),
+        [
+            request("HEAD", "/props/chair.usda", 200),
+            request("GET", "/props/chair.usda", 412),
+        ]
+    );
+}
+
+#[test]
+fn uri_resolver() {
+    let server = AssetServer::start();
+    server.put("/shots/s01/shot.usda", b"#usda 1.0", "v1");
+    server.put("/shots/s01/layout.usda", b"#usda 1.0", "v1");
+    let cache_dir = tempfile::tempdir().unwrap();
+
+    let mut wrapper = ResolverWrapper::new(Box::new(DefaultResolver::new()));
+    wrapper
+        .register_uri_resolver(
+            HTTP_URI_SCHEMES,
+            Arc::new(HttpResolver::new(cache_dir.path())),
+        )
+        .unwrap();
+
+    let shot = wrapper.resolve(&server.url("/shots/s01/shot.usda"));
+    let layout = wrapper.create_identifier("./layout.usda", Some(&shot));
+    assert_eq!(layout, server.url("/shots/s01/layout.usda"));
+    let asset = wrapper.open_asset(&wrapper.resolve(&layout)).unwrap();
+    assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+    assert!(wrapper
+        .resolve_for_new_asset(&server.url("/shots/s01/new.usda"))
+        .is_empty());
+}
@@ -0,0 +1,93 @@
+use ar::{
+    is_package_relative_path, join_package_relative_path, split_package_relative_path_inner,
+    split_package_relative_path_outer,
+};
+use proptest::prelude::*;
+
+/// Paths that may contain delimiters, but don't end with `]` since such
+/// paths are always treated as package-relative paths.
+fn path() -> impl Strategy<Value = String> {
+    "[a-z0-9_./\\[\\]-]{0,8}[a-z0-9_.-]"
+}
+
+fn paths() -> impl Strategy<Value = Vec<String>> {
+    prop::collection::vec(path(), 1..6)
+}
+
+fn as_strs(paths: &[String]) -> Vec<&str> {
+    paths.iter().map(String::as_str).collect()
+}
+
+proptest! {
+    #[test]
+    fn join_is_package_relative_when_nested(paths in paths()) {
+        let joined = join_package_relative_path(&as_strs(&paths));
+        prop_assert_eq!(is_package_relative_path(&joined), paths.len() > 1);
+    }
+
+    #[test]
+    fn split_inner_recovers_joined_paths(paths in paths()) {
+        let mut path = join_package_relative_path(&as_strs(&paths));
+        let mut split = Vec::new();
+        loop {
+            let (package_path, packaged_path) = split_package_relative_path_inner(&path);
+            if packaged_path.is_empty() {
+                split.push(package_path);
+                break;
+            }
+            split.push(packaged_path);
+            path = package_path;
+        }
+        split.reverse();
+        prop_assert_eq!(split, paths);
+    }
+
+    #[test]
+    fn split_outer_recovers_joined_paths(paths in paths()) {
+        let mut path = join_package_relative_path(&as_strs(&paths));
+        let mut split = Vec::new();
+        loop {
+            let (package_path, packaged_path) = split_package_relative_path_outer(&path);
+            split.push(package_path);
+            if packaged_path.is_empty() {
+                break;
+            }
+            path = packaged_path;
+        }
+        prop_assert_eq!(split, paths);
+    }
+
+    #[test]
+    fn join_of_split_is_identity(paths in paths()) {
+        let path = join_package_relative_path(&as_strs(&paths));
+
+        let (package_path, packaged_path) = split_package_relative_path_outer(&path);
+        prop_assert_eq!(
+            join_package_relative_path(&[&package_path, &packaged_path]),
+            path.clone()
+        );
+
+        let (package_path, packaged_path) = split_package_relative_path_inner(&path);
+        prop_assert_eq!(
+            join_package_relative_path(&[&package_path, &packaged_path]),
+            path
+        );
+    }
+}
+
+#[test]
+fn nested_package_paths() {
+    let path = "a.usdz[b.usdz[c.usd]]";
+    assert_eq!(
+        join_package_relative_path(&["a.usdz", "b.usdz", "c.usd"]),
+        path
+    );
+    assert_eq!(
+        split_package_relative_path_outer(path),
+        ("a.usdz".to_string(), "b.usdz[c.usd]".to_string())
+    );
+    assert_eq!(
+        split_package_relative_path_inner(path),
+        ("a.usdz[b.usdz]".to_string(), "c.usd".to_string())
+    );
+}
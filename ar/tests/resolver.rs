@@ -0,0 +1,149 @@
+#![cfg(feature = "resolver_v2")]
+
+use std::sync::Arc;
+use std::thread;
+
+use ar::{
+    Asset, AssetError, DefaultResolver, ResolvedPath, Resolver, ResolverError, WritableAsset,
+    WritableAssetError, WriteMode,
+};
+
+#[derive(Debug)]
+struct BytesAsset(Vec<u8>);
+
+impl Asset for BytesAsset {
+    fn get_size(&self) -> usize {
+        self.0.len()
+    }
+
+    fn get_buffer(&self) -> Result<&[u8], AssetError> {
+        Ok(&self.0)
+    }
+
+    fn read(&self, buffer: &mut [u8], count: usize, offset: usize) -> Result<usize, AssetError> {
+        let data = self
+            .0
+            .get(offset..offset + count)
+            .ok_or(AssetError::OutOfBounds(offset, count, self.0.len()))?;
+        buffer[..count].copy_from_slice(data);
+        Ok(count)
+    }
+}
+
+#[derive(Debug)]
+struct NullWritableAsset;
+
+impl WritableAsset for NullWritableAsset {
+    fn close(&mut self) -> Result<(), WritableAssetError> {
+        Ok(())
+    }
+
+    fn write(
+        &mut self,
+        _buffer: &[u8],
+        count: usize,
+        _offset: usize,
+    ) -> Result<usize, WritableAssetError> {
+        Ok(count)
+    }
+}
+
+/// A resolver implementing only the required methods.
+#[derive(Debug)]
+struct MinimalResolver;
+
+impl Resolver for MinimalResolver {
+    fn create_identifier(
+        &self,
+        asset_path: &str,
+        _anchor_asset_path: Option<&ResolvedPath>,
+    ) -> String {
+        asset_path.to_string()
+    }
+
+    fn create_identifier_for_new_asset(
+        &self,
+        asset_path: &str,
+        _anchor_asset_path: &ResolvedPath,
+    ) -> String {
+        asset_path.to_string()
+    }
+
+    fn resolve(&self, asset_path: &str) -> ResolvedPath {
+        ResolvedPath::new(asset_path)
+    }
+
+    fn resolve_for_new_asset(&self, asset_path: &str) -> ResolvedPath {
+        ResolvedPath::new(asset_path)
+    }
+
+    fn open_asset(&self, resolved_path: &ResolvedPath) -> Result<Arc<dyn Asset>, ResolverError> {
+        Ok(Arc::new(BytesAsset(
+            resolved_path.get_path_string().as_bytes().to_vec(),
+        )))
+    }
+
+    fn open_asset_for_write(
+        &self,
+        _resolved_path: &ResolvedPath,
+        _write_mode: WriteMode,
+    ) -> Result<Box<dyn WritableAsset>, ResolverError> {
+        Ok(Box::new(NullWritableAsset))
+    }
+}
+
+fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+
+#[test]
+fn resolver_can_be_boxed() {
+    assert_send_sync::<DefaultResolver>();
+    assert_send_sync::<dyn Resolver + Send + Sync>();
+    assert_send_sync::<dyn Asset>();
+
+    let resolvers: Vec<Box<dyn Resolver + Send + Sync>> =
+        vec![Box::new(MinimalResolver), Box::new(DefaultResolver::new())];
+    for resolver in &resolvers {
+        assert_eq!(resolver.create_identifier("/foo.usd", None), "/foo.usd");
+        assert_eq!(resolver.get_extension("/foo.usd"), "usd");
+        assert!(resolver.get_current_context().is_empty());
+    }
+}
+
+#[test]
+fn resolver_can_be_shared_across_threads() {
+    let resolver: Arc<dyn Resolver + Send + Sync> = Arc::new(MinimalResolver);
+
+    let handles: Vec<_> = (0..4)
+        .map(|index| {
+            let resolver = Arc::clone(&resolver);
+            thread::spawn(move || {
+                let asset_path = format!("asset{}.usd", index);
+                let resolved_path = resolver.resolve(&asset_path);
+                resolver.open_asset(&resolved_path).unwrap()
+            })
+        })
+        .collect();
+
+    for (index, handle) in handles.into_iter().enumerate() {
+        let asset = handle.join().unwrap();
+        assert_eq!(
+            asset.get_buffer().unwrap(),
+            format!("asset{}.usd", index).as_bytes()
+        );
+    }
+}
+
+#[test]
+fn writable_asset_can_be_moved_across_threads() {
+    let resolver = MinimalResolver;
+    let mut writable_asset = resolver
+        .open_asset_for_write(&ResolvedPath::new("/foo.usd"), WriteMode::Replace)
+        .unwrap();
+
+    thread::spawn(move || {
+        assert_eq!(writable_asset.write(b"data", 4, 0).unwrap(), 4);
+        writable_asset.close().unwrap();
+    })
+    .join()
+    .unwrap();
+}
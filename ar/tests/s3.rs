@@ -0,0 +1,295 @@
+#![cfg(feature = "s3")]
+
+mod common;
+
+use std::sync::Arc;
+
+use ar::{
+    DefaultResolver, ResolvedPath, Resolver, ResolverWrapper, S3Credentials, S3Resolver, WriteMode,
+    S3_URI_SCHEMES,
+};
+use common::s3::{request, Endpoint};
+
+#[test]
+fn resolve() {
+    let endpoint = Endpoint::start();
+    endpoint.put("/published/props/chair.usda", b"#usda 1.0");
+    let resolver = endpoint.resolver();
+
+    let resolved_path = resolver.resolve("s3://published/props/chair.usda");
+    assert_eq!(
+        resolved_path,
+        ResolvedPath::new("s3://published/props/chair.usda")
+    );
+    assert!(resolver
+        .resolve("s3://published/props/missing.usda")
+        .is_empty());
+    assert!(resolver.resolve("s3://published").is_empty());
+    assert_eq!(
+        resolver.create_identifier("../sets/set.usda", Some(&resolved_path)),
+        "s3://published/sets/set.usda"
+    );
+    assert_eq!(
+        endpoint.take_requests(),
+        [
+            request("HEAD", "/published/props/chair.usda", 200),
+            request("HEAD", "/published/props/missing.usda", 404),
+        ]
+    );
+}
+
+#[test]
+fn etag_timestamps() {
+    let endpoint = Endpoint::start();
+    endpoint.put("/published/props/chair.usda", b"#usda 1.0");
+    let resolver = endpoint.resolver();
+    let resolved_path = ResolvedPath::new("s3://published/props/chair.usda");
+
+    let timestamp = resolver
+        .get_modification_timestamp("s3://published/props/chair.usda", &resolved_path)
+        .unwrap();
+    assert_eq!(
+        resolver
+            .get_modification_timestamp("s3://published/props/chair.usda", &resolved_path)
+            .unwrap(),
+        timestamp
+    );
+    endpoint.put("/published/props/chair.usda", b"#usda 1.0");
+    assert_ne!(
+        resolver
+            .get_modification_timestamp("s3://published/props/chair.usda", &resolved_path)
+            .unwrap(),
+        timestamp
+    );
+    assert!(resolver
+        .get_modification_timestamp(
+            "s3://published/props/missing.usda",
+            &ResolvedPath::new("s3://published/props/missing.usda")
+        )
+        .is_err());
+}
+
+#[test]
+fn ranged_reads() {
+    let endpoint = Endpoint::start();
+    endpoint.put(
+        "/published/props/chair.usda",
+        b"#usda 1.0\ndef Xform \"chair\" {}\n",
+    );
+    let resolver = endpoint.resolver();
+
+    let asset = resolver
+        .open_asset(&ResolvedPath::new("s3://published/props/chair.usda"))
+        .unwrap();
+    assert_eq!(asset.get_size(), 31);
+    let mut buffer = [0; 5];
+    assert_eq!(asset.read(&mut buffer, 5, 10).unwrap(), 5);
+    assert_eq!(&buffer, b"def X");
+    assert!(asset.read(&mut buffer, 5, 30).is_err());
+    assert_eq!(
+        endpoint.take_requests(),
+        [
+            request("HEAD", "/published/props/chair.usda", 200),
+            request("GET", "/published/props/chair.usda", 206),
+        ]
+    );
+
+    // Reads fail once the object changed.
+    endpoint.put("/published/props/chair.usda", b"#usda 1.0\n");
+    assert!(asset.read(&mut buffer, 5, 10).is_err());
+    assert!(asset.get_buffer().is_err());
+}
+
+#[test]
+fn single_put() {
+    let endpoint = Endpoint::start();
+    let resolver = endpoint.resolver();
+
+    let resolved_path = resolver.resolve_for_new_asset("s3://published/props/chair.usda");
+    let mut asset = resolver
+        .open_asset_for_write(&resolved_path, WriteMode::Replace)
+        .unwrap();
+    asset.write(b"1.0", 3, 6).unwrap();
+    asset.write(b"#usda ", 6, 0).unwrap();
+    assert_eq!(endpoint.get("/published/props/chair.usda"), None);
+    asset.close().unwrap();
+    assert!(asset.close().is_err());
+
+    assert_eq!(
+        endpoint.get("/published/props/chair.usda").unwrap(),
+        b"#usda 1.0"
+    );
+    assert_eq!(
+        endpoint.take_requests(),
+        [request("PUT", "/published/props/chair.usda", 200)]
+    );
+}
+
+#[test]
+fn multipart_upload() {
+    let endpoint = Endpoint::start();
+    let resolver = endpoint.resolver().with_part_size(4);
+
+    let mut asset = resolver
+        .open_asset_for_write(
+            &ResolvedPath::new("s3://published/props/chair.usda"),
+            WriteMode::Replace,
+        )
+        .unwrap();
+    asset.write(b"#usda", 5, 0).unwrap();
+    // The first part was uploaded, so it cannot be written to anymore.
+    assert!(asset.write(b"!", 1, 2).is_err());
+    asset.write(b" 1.0", 4, 5).unwrap();
+    assert_eq!(endpoint.get("/published/props/chair.usda"), None);
+    asset.close().unwrap();
+
+    assert_eq!(
+        endpoint.get("/published/props/chair.usda").unwrap(),
+        b"#usda 1.0"
+    );
+    assert_eq!(endpoint.get_upload_count(), 0);
+    let requests = endpoint.take_requests();
+    let methods: Vec<_> = requests
+        .iter()
+        .map(|request| (request.method.as_str(), request.status))
+        .collect();
+    assert_eq!(
+        methods,
+        [
+            ("POST", 200),
+            ("PUT", 200),
+            ("PUT", 200),
+            ("PUT", 200),
+            ("POST", 200)
+        ]
+    );
+    assert_eq!(requests[0].target, "/published/props/chair.usda?uploads=");
+    assert!(requests[1]
+        .target
+        .starts_with("/published/props/chair.usda?partNumber=1&uploadId="));
+}
+
+#[test]
+fn rejected_part_is_uploaded_again() {
+    let endpoint = Endpoint::start();
+    let resolver = endpoint.resolver().with_part_size(4);
+
+    let mut asset = resolver
+        .open_asset_for_write(
+            &ResolvedPath::new("s3://published/props/chair.usda"),
+            WriteMode::Replace,
+        )
+        .unwrap();
+    endpoint.reject_next_part();
+    assert!(asset.write(b"#usda", 5, 0).is_err());
+    // The rejected part is kept, and uploaded again by the next write.
+    asset.write(b" 1.0", 4, 5).unwrap();
+    asset.close().unwrap();
+
+    assert_eq!(
+        endpoint.get("/published/props/chair.usda").unwrap(),
+        b"#usda 1.0"
+    );
+    let requests = endpoint.take_requests();
+    let methods: Vec<_> = requests
+        .iter()
+        .map(|request| (request.method.as_str(), request.status))
+        .collect();
+    assert_eq!(
+        methods,
+        [
+            ("POST", 200),
+            ("PUT", 500),
+            ("PUT", 200),
+            ("PUT", 200),
+            ("PUT", 200),
+            ("POST", 200)
+        ]
+    );
+}
+
+#[test]
+fn abort_on_drop() {
+    let endpoint = Endpoint::start();
+    let resolver = endpoint.resolver().with_part_size(4);
+
+    let mut asset = resolver
+        .open_asset_for_write(
+            &ResolvedPath::new("s3://published/props/chair.usda"),
+            WriteMode::Replace,
+        )
+        .unwrap();
+    asset.write(b"#usda 1.0", 9, 0).unwrap();
+    assert_eq!(endpoint.get_upload_count(), 1);
+    drop(asset);
+
+    assert_eq!(endpoint.get_upload_count(), 0);
+    assert_eq!(endpoint.get("/published/props/chair.usda"), None);
+    let requests = endpoint.take_requests();
+    assert_eq!(requests.last().unwrap().method, "DELETE");
+    assert_eq!(requests.last().unwrap().status, 204);
+}
+
+#[test]
+fn update() {
+    let endpoint = Endpoint::start();
+    endpoint.put("/published/props/chair.usda", b"#usda 1.0");
+    let resolver = endpoint.resolver();
+
+    let mut asset = resolver
+        .open_asset_for_write(
+            &ResolvedPath::new("s3://published/props/chair.usda"),
+            WriteMode::Update,
+        )
+        .unwrap();
+    asset.write(b"\n", 1, 9).unwrap();
+    asset.close().unwrap();
+    assert_eq!(
+        endpoint.get("/published/props/chair.usda").unwrap(),
+        b"#usda 1.0\n"
+    );
+}
+
+#[test]
+fn unsigned_requests_are_rejected() {
+    let endpoint = Endpoint::start();
+    endpoint.put("/published/props/chair.usda", b"#usda 1.0");
+    let credentials = S3Credentials::new("OTHERKEY", "secret");
+    let resolver = S3Resolver::new(&endpoint.url, "us-east-1", credentials);
+
+    assert!(resolver
+        .resolve("s3://published/props/chair.usda")
+        .is_empty());
+    assert_eq!(
+        endpoint.take_requests(),
+        [request("HEAD", "/published/props/chair.usda", 403)]
+    );
+}
+
+#[test]
+fn uri_resolver() {
+    let endpoint = Endpoint::start();
+    endpoint.put("/published/shots/s01/shot.usda", b"#usda 1.0");
+    endpoint.put("/published/shots/s01/layout.usda", b"#usda 1.0");
+
+    let mut wrapper = ResolverWrapper::new(Box::new(DefaultResolver::new()));
+    wrapper
+        .register_uri_resolver(S3_URI_SCHEMES, Arc::new(endpoint.resolver()))
+        .unwrap();
+
+    let shot = wrapper.resolve("s3://published/shots/s01/shot.usda");
+    let layout = wrapper.create_identifier("./layout.usda", Some(&shot));
+    assert_eq!(layout, "s3://published/shots/s01/layout.usda");
+    let asset = wrapper.open_asset(&wrapper.resolve(&layout)).unwrap();
+    assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+
+    let new_path = wrapper.resolve_for_new_asset("s3://published/shots/s01/new.usda");
+    let mut asset = wrapper
+        .open_asset_for_write(&new_path, WriteMode::Replace)
+        .unwrap();
+    asset.write(b"#usda 1.0", 9, 0).unwrap();
+    asset.close().unwrap();
+    assert!(!wrapper
+        .resolve("s3://published/shots/s01/new.usda")
+        .is_empty());
+}
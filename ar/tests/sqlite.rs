@@ -0,0 +1,175 @@
+#![cfg(all(feature = "sqlite", feature = "testing"))]
+
+use std::sync::Arc;
+
+use ar::testing::write_asset;
+use ar::{
+    DefaultResolver, ResolvedPath, Resolver, ResolverWrapper, SqliteAssetMetadata, SqliteResolver,
+    SqliteResolverError, WriteMode, SQLITE_URI_SCHEMES,
+};
+
+#[test]
+fn versions() {
+    let resolver = SqliteResolver::open_in_memory().unwrap();
+    assert!(resolver.resolve("db:/char/hero/model").is_empty());
+
+    write_asset(&resolver, "db:/char/hero/model", b"#usda 1.0");
+    write_asset(&resolver, "db:/char/hero/model", b"#usda 1.0\n");
+    assert_eq!(resolver.get_versions("db:/char/hero/model"), ["v1", "v2"]);
+
+    let latest = resolver.resolve("db:/char/hero/model");
+    assert_eq!(latest, ResolvedPath::new("db:/char/hero/model@v2"));
+    let first = resolver.resolve("db:/char/hero/model@v1");
+    assert_eq!(first, ResolvedPath::new("db:/char/hero/model@v1"));
+    assert!(resolver.resolve("db:/char/hero/model@v3").is_empty());
+    assert!(resolver.resolve("db:/char/hero/model@latest").is_empty());
+
+    assert_eq!(
+        resolver.open_asset(&first).unwrap().get_buffer().unwrap(),
+        b"#usda 1.0"
+    );
+    assert_eq!(
+        resolver.open_asset(&latest).unwrap().get_buffer().unwrap(),
+        b"#usda 1.0\n"
+    );
+    assert_eq!(
+        resolver
+            .get_modification_timestamp("db:/char/hero/model", &latest)
+            .unwrap(),
+        2
+    );
+}
+
+#[test]
+fn asset_info() {
+    let resolver = SqliteResolver::open_in_memory().unwrap();
+    resolver
+        .insert("db:/char/hero/model", b"#usda 1.0")
+        .unwrap();
+    resolver
+        .set_metadata("db:/char/hero/model", "status", "approved")
+        .unwrap();
+
+    let resolved_path = resolver.resolve("db:/char/hero/model");
+    let info = resolver.get_asset_info("db:/char/hero/model", &resolved_path);
+    assert_eq!(info.get_version(), Some("v1"));
+    assert_eq!(info.get_asset_name(), Some("model"));
+    assert_eq!(info.get_repo_path(), "db:/char/hero/model");
+    let metadata = info.get_resolver_info_as::<SqliteAssetMetadata>().unwrap();
+    assert_eq!(metadata.0["status"], "approved");
+
+    assert!(matches!(
+        resolver.set_metadata("db:/char/villain/model", "status", "wip"),
+        Err(SqliteResolverError::UnknownAsset(_))
+    ));
+    assert!(matches!(
+        resolver.insert("db:/char/hero/model@v2", b""),
+        Err(SqliteResolverError::InvalidIdentifier(_))
+    ));
+}
+
+#[test]
+fn ranged_reads() {
+    let resolver = SqliteResolver::open_in_memory().unwrap();
+    resolver
+        .insert("db:/char/hero/model", b"#usda 1.0\ndef Xform \"hero\" {}\n")
+        .unwrap();
+
+    let asset = resolver
+        .open_asset(&resolver.resolve("db:/char/hero/model"))
+        .unwrap();
+    assert_eq!(asset.get_size(), 30);
+    let mut buffer = [0; 5];
+    assert_eq!(asset.read(&mut buffer, 5, 10).unwrap(), 5);
+    assert_eq!(&buffer, b"def X");
+    assert!(asset.read(&mut buffer, 5, 29).is_err());
+}
+
+#[test]
+fn writes() {
+    let resolver = SqliteResolver::open_in_memory().unwrap();
+    resolver
+        .insert("db:/char/hero/model", b"#usda 1.0")
+        .unwrap();
+
+    // Updates start from the latest version.
+    let resolved_path = resolver.resolve_for_new_asset("db:/char/hero/model");
+    let mut asset = resolver
+        .open_asset_for_write(&resolved_path, WriteMode::Update)
+        .unwrap();
+    asset.write(b"\n", 1, 9).unwrap();
+    asset.close().unwrap();
+    assert!(asset.close().is_err());
+    let asset = resolver
+        .open_asset(&resolver.resolve("db:/char/hero/model@v2"))
+        .unwrap();
+    assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0\n");
+
+    // Nothing is committed for assets dropped without being closed.
+    let mut asset = resolver
+        .open_asset_for_write(&resolved_path, WriteMode::Replace)
+        .unwrap();
+    asset.write(b"#usda 1.0", 9, 0).unwrap();
+    drop(asset);
+    assert_eq!(resolver.get_versions("db:/char/hero/model"), ["v1", "v2"]);
+
+    // Existing versions cannot be written to.
+    assert!(resolver
+        .resolve_for_new_asset("db:/char/hero/model@v1")
+        .is_empty());
+    assert!(resolver
+        .open_asset_for_write(
+            &ResolvedPath::new("db:/char/hero/model@v1"),
+            WriteMode::Replace
+        )
+        .is_err());
+}
+
+#[test]
+fn database_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("assets.db");
+
+    let resolver = SqliteResolver::open(&path).unwrap();
+    write_asset(&resolver, "db:/sets/city", b"#usda 1.0");
+    // Another connection sees committed versions and adds its own.
+    let other = SqliteResolver::open(&path).unwrap();
+    assert_eq!(
+        other.resolve("db:/sets/city"),
+        ResolvedPath::new("db:/sets/city@v1")
+    );
+    write_asset(&other, "db:/sets/city", b"#usda 1.0\n");
+    drop(resolver);
+
+    let resolver = SqliteResolver::open(&path).unwrap();
+    assert_eq!(resolver.get_versions("db:/sets/city"), ["v1", "v2"]);
+}
+
+#[test]
+fn uri_resolver() {
+    let resolver = SqliteResolver::open_in_memory().unwrap();
+    resolver
+        .insert("db:/char/hero/model", b"#usda 1.0")
+        .unwrap();
+    resolver.insert("db:/char/hero/rig", b"#usda 1.0").unwrap();
+    resolver
+        .insert("db:/char/hero/rig", b"#usda 1.0\n")
+        .unwrap();
+
+    let mut wrapper = ResolverWrapper::new(Box::new(DefaultResolver::new()));
+    wrapper
+        .register_uri_resolver(SQLITE_URI_SCHEMES, Arc::new(resolver))
+        .unwrap();
+
+    let model = wrapper.resolve("db:/char/hero/model");
+    let rig = wrapper.create_identifier("./rig@v1", Some(&model));
+    assert_eq!(rig, "db:/char/hero/rig@v1");
+    let asset = wrapper.open_asset(&wrapper.resolve(&rig)).unwrap();
+    assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0");
+    assert_eq!(
+        wrapper
+            .get_asset_info(&rig, &wrapper.resolve(&rig))
+            .get_version(),
+        Some("v1")
+    );
+}
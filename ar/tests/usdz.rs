@@ -0,0 +1,93 @@
+#![cfg(feature = "testing")]
+
+use std::sync::Arc;
+
+use ar::testing::build_zip;
+use ar::{
+    AssetError, DefaultResolver, ResolvedPath, Resolver, ResolverError, ResolverScopedCache,
+    ResolverWrapper, ZipPackageResolver,
+};
+
+fn wrapper() -> ResolverWrapper {
+    let mut wrapper = ResolverWrapper::new(Box::new(DefaultResolver::new()));
+    wrapper
+        .register_package_resolver(&["usdz"], Arc::new(ZipPackageResolver::usdz()))
+        .unwrap();
+    wrapper
+        .register_package_resolver(&["zip"], Arc::new(ZipPackageResolver::new()))
+        .unwrap();
+    wrapper
+}
+
+fn read_asset(wrapper: &ResolverWrapper, path: &str) -> Vec<u8> {
+    let resolved_path = wrapper.resolve(path);
+    assert!(!resolved_path.is_empty(), "cannot resolve {}", path);
+    let asset = wrapper.open_asset(&resolved_path).unwrap();
+    asset.get_buffer().unwrap().to_vec()
+}
+
+#[test]
+fn open_packaged_assets() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir_path = dir.path().to_str().unwrap();
+
+    let inner = build_zip(&[("b.usda", b"#usda 1.0 inner", 0)], 64);
+    let outer = build_zip(
+        &[
+            ("a.usda", b"#usda 1.0 outer", 0),
+            ("inner.usdz", &inner, 0),
+            ("deflated.usda", b"not really deflated", 8),
+        ],
+        64,
+    );
+    std::fs::write(dir.path().join("outer.usdz"), &outer).unwrap();
+
+    let wrapper = wrapper();
+    let _cache = ResolverScopedCache::with_resolver(&wrapper, None);
+
+    let path = format!("{}/outer.usdz[a.usda]", dir_path);
+    assert_eq!(wrapper.resolve(&path), ResolvedPath::new(&path));
+    assert_eq!(read_asset(&wrapper, &path), b"#usda 1.0 outer");
+
+    let path = format!("{}/outer.usdz[inner.usdz[b.usda]]", dir_path);
+    assert_eq!(wrapper.resolve(&path), ResolvedPath::new(&path));
+    assert_eq!(read_asset(&wrapper, &path), b"#usda 1.0 inner");
+
+    assert!(wrapper
+        .resolve(&format!("{}/outer.usdz[missing.usda]", dir_path))
+        .is_empty());
+    assert!(wrapper
+        .resolve(&format!("{}/missing.usdz[a.usda]", dir_path))
+        .is_empty());
+
+    let resolved_path = wrapper.resolve(&format!("{}/outer.usdz[deflated.usda]", dir_path));
+    assert!(matches!(
+        wrapper.open_asset(&resolved_path),
+        Err(ResolverError::InvalidAsset(_, AssetError::UnsupportedCompression(path, 8)))
+            if path == "deflated.usda"
+    ));
+}
+
+#[test]
+fn misaligned_usdz_files_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let dir_path = dir.path().to_str().unwrap();
+
+    let archive = build_zip(&[("a.usda", b"#usda 1.0", 0)], 1);
+    std::fs::write(dir.path().join("a.usdz"), &archive).unwrap();
+    std::fs::write(dir.path().join("a.zip"), &archive).unwrap();
+
+    let wrapper = wrapper();
+    let resolved_path = wrapper.resolve(&format!("{}/a.usdz[a.usda]", dir_path));
+    assert!(matches!(
+        wrapper.open_asset(&resolved_path),
+        Err(ResolverError::InvalidAsset(_, AssetError::MisalignedData(path, 36, 64)))
+            if path == "a.usda"
+    ));
+
+    // Plain zip archives don't require any alignment.
+    assert_eq!(
+        read_asset(&wrapper, &format!("{}/a.zip[a.usda]", dir_path)),
+        b"#usda 1.0"
+    );
+}
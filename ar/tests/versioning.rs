@@ -0,0 +1,167 @@
+#![cfg(all(feature = "resolver_v2", feature = "std"))]
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+
+use ar::{
+    DefaultResolver, DefaultResolverContext, InMemoryVersionStore, Lockfile, ResolvedPath,
+    Resolver, ResolverContext, ResolverContextBinder, ResolverWrapper, VersionedResolver,
+};
+
+/// Publishes `version` of `char/hero.usda` in `root`, returning its path.
+fn publish(root: &Path, versions: &InMemoryVersionStore, version: u32) -> String {
+    let dir = root.join("char").join("hero").join(format!("v{}", version));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("hero.usda");
+    fs::write(&path, format!("#usda 1.0\n# v{}\n", version)).unwrap();
+    let path = path.to_string_lossy().into_owned();
+    versions.insert_version("char/hero.usda", version, &path);
+    path
+}
+
+fn search_path_context(root: &Path) -> ResolverContext {
+    let mut context = ResolverContext::new();
+    context.push(DefaultResolverContext::new(&[&root.to_string_lossy()]));
+    context
+}
+
+#[test]
+fn qualifiers() {
+    let dir = tempfile::tempdir().unwrap();
+    let versions = Arc::new(InMemoryVersionStore::new());
+    let v1 = publish(dir.path(), &versions, 1);
+    let v2 = publish(dir.path(), &versions, 2);
+    versions.set_tag("char/hero.usda", "approved", 1);
+    versions.set_tag("char/hero.usda", "retired", 7);
+    fs::write(dir.path().join("char").join("hero.usda"), "#usda 1.0").unwrap();
+
+    let resolver = VersionedResolver::new(DefaultResolver::new(), versions.clone());
+    let _binder = ResolverContextBinder::with_resolver(&resolver, search_path_context(dir.path()));
+
+    assert_eq!(
+        resolver.resolve("char/hero.usda@latest"),
+        ResolvedPath::new(&v2)
+    );
+    assert_eq!(
+        resolver.resolve("char/hero.usda@v1"),
+        ResolvedPath::new(&v1)
+    );
+    assert_eq!(
+        resolver.resolve("char/hero.usda@approved"),
+        ResolvedPath::new(&v1)
+    );
+    assert!(resolver.resolve("char/hero.usda@v3").is_empty());
+    assert!(resolver.resolve("char/hero.usda@retired").is_empty());
+    assert!(resolver.resolve("char/hero.usda@final").is_empty());
+
+    // Unqualified paths are left to the wrapped resolver.
+    let unversioned = resolver.resolve("char/hero.usda");
+    assert!(unversioned.get_path_string().ends_with("hero.usda"));
+    assert_ne!(unversioned, ResolvedPath::new(&v1));
+    assert!(resolver
+        .get_asset_info("char/hero.usda", &unversioned)
+        .get_version()
+        .is_none());
+
+    let resolved_path = resolver.resolve("char/hero.usda@approved");
+    let info = resolver.get_asset_info("char/hero.usda@approved", &resolved_path);
+    assert_eq!(info.get_version(), Some("v1"));
+    let asset = resolver.open_asset(&resolved_path).unwrap();
+    assert_eq!(asset.get_buffer().unwrap(), b"#usda 1.0\n# v1\n");
+
+    let lockfile = resolver.get_lockfile();
+    assert_eq!(lockfile.len(), 4);
+    let resolution = lockfile.get("char/hero.usda@latest").unwrap();
+    assert_eq!(resolution.get_version(), Some("v2"));
+    assert_eq!(resolution.get_resolved_path(), &ResolvedPath::new(&v2));
+    assert_eq!(lockfile.get("char/hero.usda").unwrap().get_version(), None);
+    assert!(lockfile.get("char/hero.usda@v3").is_none());
+
+    resolver.clear_lockfile();
+    assert!(resolver.get_lockfile().is_empty());
+}
+
+#[test]
+fn lockfile_replay() {
+    let dir = tempfile::tempdir().unwrap();
+    let versions = Arc::new(InMemoryVersionStore::new());
+    let v1 = publish(dir.path(), &versions, 1);
+    versions.set_tag("char/hero.usda", "approved", 1);
+
+    let resolver = VersionedResolver::new(DefaultResolver::new(), versions.clone());
+    let context = search_path_context(dir.path());
+    {
+        let _binder = ResolverContextBinder::with_resolver(&resolver, context.clone());
+        resolver.resolve("char/hero.usda@latest");
+        resolver.resolve("char/hero.usda@approved");
+    }
+
+    // Persist the session's lockfile along with its search path.
+    let mut locked_context = context.clone();
+    locked_context.push(resolver.get_lockfile());
+    let serialized = locked_context.serialize_contexts().unwrap();
+    let locked_context = ResolverContext::deserialize_contexts(
+        serialized
+            .iter()
+            .map(|(tag, context)| (tag.as_str(), context.as_str())),
+    )
+    .unwrap();
+    assert_eq!(locked_context.get::<Lockfile>().unwrap().len(), 2);
+
+    let v2 = publish(dir.path(), &versions, 2);
+    versions.set_tag("char/hero.usda", "approved", 2);
+
+    let resolver = VersionedResolver::new(DefaultResolver::new(), versions);
+    {
+        let _binder = ResolverContextBinder::with_resolver(&resolver, locked_context);
+        for asset_path in &["char/hero.usda@latest", "char/hero.usda@approved"] {
+            let resolved_path = resolver.resolve(asset_path);
+            assert_eq!(resolved_path, ResolvedPath::new(&v1));
+            let info = resolver.get_asset_info(asset_path, &resolved_path);
+            assert_eq!(info.get_version(), Some("v1"));
+        }
+        // Identifiers missing from the lockfile are resolved again.
+        assert_eq!(
+            resolver.resolve("char/hero.usda@v2"),
+            ResolvedPath::new(&v2)
+        );
+
+        // Other threads don't see the bound lockfile.
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                let _binder = ResolverContextBinder::with_resolver(&resolver, context);
+                assert_eq!(
+                    resolver.resolve("char/hero.usda@latest"),
+                    ResolvedPath::new(&v2)
+                );
+            });
+        });
+    }
+    assert_eq!(
+        resolver.resolve("char/hero.usda@approved"),
+        ResolvedPath::new(&v2)
+    );
+}
+
+#[test]
+fn primary_resolver() {
+    let dir = tempfile::tempdir().unwrap();
+    let versions = InMemoryVersionStore::new();
+    let v1 = publish(dir.path(), &versions, 1);
+
+    let resolver = VersionedResolver::new(DefaultResolver::new(), versions);
+    let wrapper = ResolverWrapper::new(Box::new(resolver));
+    let _binder = ResolverContextBinder::with_resolver(&wrapper, search_path_context(dir.path()));
+
+    let resolved_path = wrapper.resolve("char/hero.usda@latest");
+    assert_eq!(resolved_path, ResolvedPath::new(&v1));
+    assert_eq!(
+        wrapper
+            .get_asset_info("char/hero.usda@latest", &resolved_path)
+            .get_version(),
+        Some("v1")
+    );
+    assert_eq!(wrapper.get_extension("char/hero.usda@latest"), "usda");
+}